@@ -9,9 +9,15 @@ type FixedCost = {
   name: string;
   amount: number;
   is_active: boolean;
+  cadence: string;
   paid_date_local: string | null;
   paid_ts_utc: number | null;
   paid_tx_id: number | null;
+  paid_amount: number | null;
+  paid_total: number;
+  due_day: number | null;
+  is_overdue: boolean;
+  is_skipped: boolean;
 };
 
 const formatLocalDate = (date: Date) => {
@@ -115,7 +121,10 @@ export default function FixedCostsPage() {
     return true;
   });
 
-  const totalPeriod = items.reduce((sum, item) => sum + item.amount, 0);
+  const totalPeriod = items.reduce(
+    (sum, item) => (item.is_skipped ? sum : sum + item.amount),
+    0,
+  );
   const totalPaid = items.reduce(
     (sum, item) => (item.paid_date_local ? sum + item.amount : sum),
     0,
@@ -255,7 +264,9 @@ export default function FixedCostsPage() {
                   >
                     {item.paid_date_local
                       ? `Lunas ${item.paid_date_local}`
-                      : `Belum Lunas (${currentPeriod})`}
+                      : item.is_skipped
+                        ? `Dilewati (${currentPeriod})`
+                        : `Belum Lunas (${currentPeriod})`}
                   </span>
                 </div>
               </div>
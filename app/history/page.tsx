@@ -14,6 +14,7 @@ type Transaction = {
   source: "manual" | "fixed_cost";
   fixed_cost_id: number | null;
   description: string | null;
+  note: string | null;
 };
 
 const formatLocalDate = (date: Date) => {
@@ -180,6 +181,7 @@ export default function HistoryPage() {
                   <span className="tx-amount">{formatRupiah(tx.amount)}</span>
                 </div>
                 {tx.description && <div className="tx-desc">{tx.description}</div>}
+                {tx.note && <div className="tx-desc">{tx.note}</div>}
                 <div className="tx-meta">
                   <span>{tx.date_local}</span>
                   <span>{formatLocalTime(tx.ts_utc)}</span>
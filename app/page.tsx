@@ -18,6 +18,8 @@ type Transaction = {
   source: "manual" | "fixed_cost";
   fixed_cost_id: number | null;
   description: string | null;
+  note: string | null;
+  warning?: string | null;
 };
 
 type TodaySummary = {
@@ -26,6 +28,7 @@ type TodaySummary = {
   today_remaining: number;
   today_remaining_clamped: number;
   overspent_today: boolean;
+  spend_status: "under" | "near" | "over";
 };
 
 const formatLocalDate = (date: Date) => {
@@ -53,6 +56,7 @@ export default function Home() {
     today_remaining: 0,
     today_remaining_clamped: 0,
     overspent_today: false,
+    spend_status: "under",
   });
   const [submitStatus, setSubmitStatus] = useState("");
   const [toastMessage, setToastMessage] = useState("");
@@ -160,14 +164,14 @@ export default function Home() {
     setIsSubmitting(true);
     const command = activeKind === "OUT" ? "add_expense" : "add_income";
     try {
-      await invoke(command, {
+      const created = await invoke<Transaction>(command, {
         amount: Math.trunc(parsedAmount),
         date_local: resolvedDate,
         description: description || null,
       });
       setAmount("");
       setDescription("");
-      setSubmitStatus("Tercatat.");
+      setSubmitStatus(created.warning ?? "Tercatat.");
       await Promise.all([
         refreshTransactions(),
         refreshSummary(),
@@ -380,6 +384,7 @@ export default function Home() {
                   <span className="tx-amount">{formatRupiah(tx.amount)}</span>
                 </div>
                 {tx.description && <div className="tx-desc">{tx.description}</div>}
+                {tx.note && <div className="tx-desc">{tx.note}</div>}
                 <div className="tx-meta">
                   <span>{formatLocalTime(tx.ts_utc)}</span>
                   <span className="pill pill-muted">
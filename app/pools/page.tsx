@@ -2,6 +2,7 @@
 
 import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { save } from "@tauri-apps/plugin-dialog";
 import { formatRupiah } from "../lib/format";
 import CoachingInsightCard, {
   type CoachingInsight,
@@ -21,9 +22,17 @@ type PoolsSummary = {
   today_remaining: number;
   today_remaining_clamped: number;
   overspent_today: boolean;
+  spend_status: "under" | "near" | "over";
   hari_ketahanan_stop_pemasukan: number;
 };
 
+const formatLocalDate = (date: Date) => {
+  const year = date.getFullYear();
+  const month = String(date.getMonth() + 1).padStart(2, "0");
+  const day = String(date.getDate()).padStart(2, "0");
+  return `${year}-${month}-${day}`;
+};
+
 type MetricCardProps = {
   title: string;
   value: string;
@@ -51,6 +60,7 @@ export default function PoolsPage() {
   const [summary, setSummary] = useState<PoolsSummary | null>(null);
   const [insight, setInsight] = useState<CoachingInsight | null>(null);
   const [error, setError] = useState("");
+  const [exportNote, setExportNote] = useState("");
 
   const refresh = async () => {
     setError("");
@@ -70,6 +80,33 @@ export default function PoolsPage() {
     refresh();
   }, []);
 
+  const exportJournal = async () => {
+    setError("");
+    setExportNote("");
+    const today = new Date();
+    const startDate = formatLocalDate(
+      new Date(today.getFullYear(), today.getMonth(), 1),
+    );
+    const endDate = formatLocalDate(today);
+    const path = await save({
+      defaultPath: `jurnal-coaching-${endDate}.md`,
+      filters: [{ name: "Markdown", extensions: ["md"] }],
+    });
+    if (!path) {
+      return;
+    }
+    try {
+      const count = await invoke<number>("export_insights_markdown", {
+        path,
+        start_date: startDate,
+        end_date: endDate,
+      });
+      setExportNote(`${count} catatan coaching diekspor.`);
+    } catch (err) {
+      setError(String(err));
+    }
+  };
+
   const showSoftWarn =
     summary &&
     summary.recommended_spend_today > 0 &&
@@ -87,6 +124,8 @@ export default function PoolsPage() {
       <section>
         <div className="pools-toolbar">
           <button onClick={refresh}>Muat Ulang</button>
+          <button onClick={exportJournal}>Ekspor Jurnal Bulan Ini</button>
+          {exportNote && <span>{exportNote}</span>}
           {error && <span className="metric-error">{error}</span>}
         </div>
         <details className="metric-helper">
@@ -50,8 +50,8 @@ export default function RulesPage() {
       setError('Batas minimum harus <= batas maksimum')
       return
     }
-    if (config.resilience_days < 1) {
-      setError('Target hari penyangga minimal 1')
+    if (config.resilience_days < 0) {
+      setError('Target hari penyangga tidak boleh negatif')
       return
     }
 
@@ -133,7 +133,8 @@ export default function RulesPage() {
                 onChange={(event) => updateField('resilience_days', event.target.value)}
               />
               <div className="helper-text">
-                Menentukan target penyangga dan horizon pembagian dana fleksibel.
+                Menentukan target penyangga dan horizon pembagian dana fleksibel. Isi 0 untuk
+                tanpa penyangga.
               </div>
             </div>
           </div>
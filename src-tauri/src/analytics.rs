@@ -0,0 +1,962 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Weekday};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::{
+    compute_income_deferred, compute_pools_summary_for_date, daily_recommendation, fetch_config,
+    insight, parse_date_local, savings, Config,
+};
+
+#[derive(Serialize)]
+pub struct MtdPace {
+    pub this_month_out: i64,
+    pub last_month_out: i64,
+    pub compared_day: u32,
+    pub last_month_compared_day: u32,
+    pub delta: i64,
+    pub delta_percent: Option<i64>,
+    pub direction: String,
+}
+
+#[derive(Serialize)]
+pub struct BreakEvenDailySpend {
+    pub net_balance: i64,
+    pub unpaid_fixed_costs_month: i64,
+    pub target_penyangga: i64,
+    pub days_remaining_in_month: i64,
+    pub break_even_daily_spend: i64,
+}
+
+#[derive(Serialize)]
+pub struct PeriodActivity {
+    pub period_ym: String,
+    pub income_count: i64,
+    pub expense_count: i64,
+    pub avg_income: i64,
+    pub avg_expense: i64,
+    pub busiest_date_local: Option<String>,
+    pub busiest_tx_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct RunwayProjection {
+    pub net_balance: i64,
+    pub avg_daily_out_30d: i64,
+    pub projected_days_remaining: Option<i64>,
+    pub projected_breach_date: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DailySpend {
+    pub date_local: String,
+    pub total_out: i64,
+    pub total_in: i64,
+    pub recommended_spend_today: i64,
+}
+
+#[derive(Serialize)]
+pub struct BalancePoint {
+    pub date_local: String,
+    pub balance: i64,
+}
+
+#[derive(Serialize)]
+pub struct DisciplineStreak {
+    pub days: i64,
+    pub start_date_local: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct SourceBreakdown {
+    pub source: String,
+    pub total_out: i64,
+    pub percent_of_out: i64,
+}
+
+#[derive(Serialize)]
+pub struct IncomeCategoryTotal {
+    pub category: String,
+    pub total_in: i64,
+    pub tx_count: i64,
+}
+
+#[derive(Serialize)]
+pub struct WeekdaySpend {
+    pub weekday: String,
+    pub total_out: i64,
+    pub day_count: i64,
+    pub avg_out: i64,
+}
+
+#[derive(Serialize)]
+pub struct CategoryTotal {
+    pub category: String,
+    pub total_out: i64,
+    pub tx_count: i64,
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+pub(crate) fn days_in_month(year: i32, month: u32) -> u32 {
+    let (next_year, next_month) = if month == 12 {
+        (year + 1, 1)
+    } else {
+        (year, month + 1)
+    };
+    NaiveDate::from_ymd_opt(next_year, next_month, 1)
+        .and_then(|first| first.pred_opt())
+        .map(|last| last.day())
+        .unwrap_or(28)
+}
+
+fn sum_out_between(conn: &Connection, start: &str, end: &str) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(amount), 0) FROM transactions
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2",
+        params![start, end],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+pub fn mtd_pace(conn: &Connection, now_local: DateTime<Local>) -> Result<MtdPace, String> {
+    let today = now_local.date_naive();
+    let this_start = today
+        .with_day(1)
+        .ok_or_else(|| "invalid date".to_string())?;
+
+    let (last_year, last_month) = if today.month() == 1 {
+        (today.year() - 1, 12)
+    } else {
+        (today.year(), today.month() - 1)
+    };
+    // Bulan lalu bisa lebih pendek; batasi hari pembanding ke hari terakhir bulan lalu.
+    let last_day = std::cmp::min(today.day(), days_in_month(last_year, last_month));
+    let last_start = NaiveDate::from_ymd_opt(last_year, last_month, 1)
+        .ok_or_else(|| "invalid date".to_string())?;
+    let last_end = NaiveDate::from_ymd_opt(last_year, last_month, last_day)
+        .ok_or_else(|| "invalid date".to_string())?;
+
+    let this_month_out = sum_out_between(conn, &format_date(this_start), &format_date(today))?;
+    let last_month_out = sum_out_between(conn, &format_date(last_start), &format_date(last_end))?;
+
+    let delta = this_month_out - last_month_out;
+    let delta_percent = if last_month_out > 0 {
+        Some(delta * 100 / last_month_out)
+    } else {
+        None
+    };
+    let direction = if delta > 0 {
+        "faster"
+    } else if delta < 0 {
+        "slower"
+    } else {
+        "same"
+    }
+    .to_string();
+
+    Ok(MtdPace {
+        this_month_out,
+        last_month_out,
+        compared_day: today.day(),
+        last_month_compared_day: last_day,
+        delta,
+        delta_percent,
+        direction,
+    })
+}
+
+pub fn break_even_daily_spend(
+    conn: &Connection,
+    now_local: DateTime<Local>,
+) -> Result<BreakEvenDailySpend, String> {
+    let today = now_local.date_naive();
+    let summary = compute_pools_summary_for_date(conn, today)?;
+    let (_, unpaid_fixed_costs_month) =
+        insight::fetch_unpaid_fixed_cost_totals(conn, &format_date(today))?;
+
+    // Hari ini ikut dihitung sebagai hari tersisa.
+    let days_remaining_in_month =
+        i64::from(days_in_month(today.year(), today.month()) - today.day() + 1);
+    let spendable = summary.net_balance - unpaid_fixed_costs_month - summary.target_penyangga;
+    let break_even_daily_spend = std::cmp::max(0, spendable / days_remaining_in_month);
+
+    Ok(BreakEvenDailySpend {
+        net_balance: summary.net_balance,
+        unpaid_fixed_costs_month,
+        target_penyangga: summary.target_penyangga,
+        days_remaining_in_month,
+        break_even_daily_spend,
+    })
+}
+
+pub fn runway_projection(
+    conn: &Connection,
+    now_local: DateTime<Local>,
+) -> Result<RunwayProjection, String> {
+    let today = now_local.date_naive();
+    let start_30d = today
+        .checked_sub_signed(Duration::days(29))
+        .ok_or_else(|| "date underflow".to_string())?;
+    let total_out_30d = sum_out_between(conn, &format_date(start_30d), &format_date(today))?;
+    let avg_daily_out_30d = total_out_30d / 30;
+    let net_balance = compute_pools_summary_for_date(conn, today)?.net_balance;
+
+    // Tanpa pengeluaran 30 hari terakhir, tidak ada laju untuk diproyeksikan.
+    let projected_days_remaining = if avg_daily_out_30d > 0 {
+        Some(std::cmp::max(0, net_balance / avg_daily_out_30d))
+    } else {
+        None
+    };
+    let projected_breach_date = projected_days_remaining
+        .and_then(|days| today.checked_add_signed(Duration::days(days)))
+        .map(format_date);
+
+    Ok(RunwayProjection {
+        net_balance,
+        avg_daily_out_30d,
+        projected_days_remaining,
+        projected_breach_date,
+    })
+}
+
+// Satu baris per hari (terlama dulu), hari kosong bernilai 0. recommended_spend_today
+// mengulang rekomendasi hari ini; belum ada riwayat rekomendasi per hari.
+pub fn daily_spend_series(
+    conn: &Connection,
+    now_local: DateTime<Local>,
+    days: u32,
+) -> Result<Vec<DailySpend>, String> {
+    if days == 0 {
+        return Err("days must be > 0".to_string());
+    }
+    let today = now_local.date_naive();
+    let start = today
+        .checked_sub_signed(Duration::days(i64::from(days) - 1))
+        .ok_or_else(|| "date underflow".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, kind, COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local >= ?1 AND date_local <= ?2
+             GROUP BY date_local, kind",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![format_date(start), format_date(today)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut totals: HashMap<String, (i64, i64)> = HashMap::new();
+    for row in rows {
+        let (date_local, kind, total) = row.map_err(|err| err.to_string())?;
+        let entry = totals.entry(date_local).or_insert((0, 0));
+        match kind.as_str() {
+            "OUT" => entry.0 += total,
+            "IN" => entry.1 += total,
+            _ => {}
+        }
+    }
+
+    let recommended_spend_today =
+        compute_pools_summary_for_date(conn, today)?.recommended_spend_today;
+    let series = start
+        .iter_days()
+        .take(days as usize)
+        .map(|date| {
+            let date_local = format_date(date);
+            let (total_out, total_in) = totals.get(&date_local).copied().unwrap_or((0, 0));
+            DailySpend {
+                date_local,
+                total_out,
+                total_in,
+                recommended_spend_today,
+            }
+        })
+        .collect();
+    Ok(series)
+}
+
+pub fn net_worth_timeline(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<BalancePoint>, String> {
+    let start = parse_date_local(start_date)?;
+    let end = parse_date_local(end_date)?;
+    if end < start {
+        return Err("end_date must be >= start_date".to_string());
+    }
+
+    // Saldo sebelum start_date cukup satu agregat; sisanya digulirkan per hari.
+    let before_start: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(CASE WHEN kind = 'IN' THEN amount ELSE -amount END), 0)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local < ?1",
+            [format_date(start)],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, COALESCE(SUM(CASE WHEN kind = 'IN' THEN amount ELSE -amount END), 0)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local >= ?1 AND date_local <= ?2
+             GROUP BY date_local",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![format_date(start), format_date(end)], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut deltas: HashMap<String, i64> = HashMap::new();
+    for row in rows {
+        let (date_local, delta) = row.map_err(|err| err.to_string())?;
+        deltas.insert(date_local, delta);
+    }
+
+    let mut balance = fetch_config(conn)?.opening_balance + before_start;
+    let timeline = start
+        .iter_days()
+        .take_while(|date| *date <= end)
+        .map(|date| {
+            let date_local = format_date(date);
+            balance += deltas.get(&date_local).copied().unwrap_or(0);
+            BalancePoint {
+                date_local,
+                balance,
+            }
+        })
+        .collect();
+    Ok(timeline)
+}
+
+// Mundur dari `from` sampai hari transaksi pertama; `visit` mengembalikan false untuk berhenti.
+pub(crate) fn walk_daily_budgets(
+    conn: &Connection,
+    config: &Config,
+    from: NaiveDate,
+    mut visit: impl FnMut(NaiveDate, i64, i64) -> bool,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local,
+                    COALESCE(SUM(CASE WHEN kind = 'IN' THEN amount ELSE -amount END), 0),
+                    COALESCE(SUM(CASE WHEN kind = 'OUT' AND source != 'savings' THEN amount ELSE 0 END), 0)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local <= ?1
+             GROUP BY date_local",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([format_date(from)], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut days: HashMap<String, (i64, i64)> = HashMap::new();
+    for row in rows {
+        let (date_local, delta, spend) = row.map_err(|err| err.to_string())?;
+        days.insert(date_local, (delta, spend));
+    }
+    // Hari sebelum transaksi pertama tidak punya riwayat untuk dinilai.
+    let Some(first_date) = days
+        .keys()
+        .min()
+        .map(|date| parse_date_local(date))
+        .transpose()?
+    else {
+        return Ok(());
+    };
+
+    // Dana tabungan tidak punya riwayat; pakai nilai saat ini untuk semua hari.
+    let savings_reserved = savings::reserved_amount(conn)?;
+    let mut balance = config.opening_balance + days.values().map(|(delta, _)| delta).sum::<i64>();
+    let mut date = from;
+    while date >= first_date {
+        let date_local = format_date(date);
+        let (delta, spend) = days.get(&date_local).copied().unwrap_or((0, 0));
+        let income_deferred =
+            compute_income_deferred(conn, &date_local, config.income_smoothing_days)?;
+        let recommended = daily_recommendation(config, balance, income_deferred, savings_reserved)
+            .recommended_daily;
+        if !visit(date, spend, recommended) {
+            break;
+        }
+        balance -= delta;
+        date = date
+            .pred_opt()
+            .ok_or_else(|| "date underflow".to_string())?;
+    }
+    Ok(())
+}
+
+// Streak berhenti di hari pertama yang melewati rekomendasinya.
+pub fn discipline_streak(conn: &Connection, today: NaiveDate) -> Result<DisciplineStreak, String> {
+    let config = fetch_config(conn)?;
+    let mut streak = DisciplineStreak {
+        days: 0,
+        start_date_local: None,
+    };
+    walk_daily_budgets(conn, &config, today, |date, spend, recommended| {
+        if spend > recommended {
+            return false;
+        }
+        streak.days += 1;
+        streak.start_date_local = Some(format_date(date));
+        true
+    })?;
+    Ok(streak)
+}
+
+pub fn period_activity(conn: &Connection, period_ym: &str) -> Result<PeriodActivity, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", period_ym), "%Y-%m-%d")
+        .map_err(|err| format!("invalid period_ym: {}", err))?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT kind, COUNT(*), COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND substr(date_local, 1, 7) = ?1
+             GROUP BY kind",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([period_ym], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut activity = PeriodActivity {
+        period_ym: period_ym.to_string(),
+        income_count: 0,
+        expense_count: 0,
+        avg_income: 0,
+        avg_expense: 0,
+        busiest_date_local: None,
+        busiest_tx_count: 0,
+    };
+    for row in rows {
+        let (kind, count, total) = row.map_err(|err| err.to_string())?;
+        let avg = if count > 0 { total / count } else { 0 };
+        match kind.as_str() {
+            "IN" => {
+                activity.income_count = count;
+                activity.avg_income = avg;
+            }
+            "OUT" => {
+                activity.expense_count = count;
+                activity.avg_expense = avg;
+            }
+            _ => {}
+        }
+    }
+
+    let busiest: Option<(String, i64)> = conn
+        .query_row(
+            "SELECT date_local, COUNT(*) AS tx_count FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND substr(date_local, 1, 7) = ?1
+             GROUP BY date_local
+             ORDER BY tx_count DESC, date_local ASC
+             LIMIT 1",
+            [period_ym],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    if let Some((date_local, count)) = busiest {
+        activity.busiest_date_local = Some(date_local);
+        activity.busiest_tx_count = count;
+    }
+
+    Ok(activity)
+}
+
+// (category, total, tx_count) per kategori untuk satu kind, terbesar dulu.
+fn kind_category_totals(
+    conn: &Connection,
+    kind: &str,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<(String, i64, i64)>, String> {
+    parse_date_local(start_date)?;
+    parse_date_local(end_date)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT COALESCE(category, 'uncategorized') AS category_key,
+                    COALESCE(SUM(amount), 0) AS total, COUNT(*)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = ?1 AND date_local >= ?2 AND date_local <= ?3
+             GROUP BY category_key
+             ORDER BY total DESC, category_key ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![kind, start_date, end_date], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut totals = Vec::new();
+    for row in rows {
+        totals.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(totals)
+}
+
+pub fn category_totals(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<CategoryTotal>, String> {
+    Ok(kind_category_totals(conn, "OUT", start_date, end_date)?
+        .into_iter()
+        .map(|(category, total_out, tx_count)| CategoryTotal {
+            category,
+            total_out,
+            tx_count,
+        })
+        .collect())
+}
+
+pub fn income_breakdown(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<IncomeCategoryTotal>, String> {
+    Ok(kind_category_totals(conn, "IN", start_date, end_date)?
+        .into_iter()
+        .map(|(category, total_in, tx_count)| IncomeCategoryTotal {
+            category,
+            total_in,
+            tx_count,
+        })
+        .collect())
+}
+
+// Persentase dibulatkan ke bawah, jadi totalnya bisa sedikit di bawah 100.
+pub fn spending_by_source(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<SourceBreakdown>, String> {
+    parse_date_local(start_date)?;
+    parse_date_local(end_date)?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT source, COALESCE(SUM(amount), 0) AS total_out
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2
+             GROUP BY source
+             ORDER BY total_out DESC, source ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+    let mut totals = Vec::new();
+    for row in rows {
+        totals.push(row.map_err(|err| err.to_string())?);
+    }
+
+    let grand_total: i64 = totals.iter().map(|(_, total)| total).sum();
+    Ok(totals
+        .into_iter()
+        .map(|(source, total_out)| SourceBreakdown {
+            percent_of_out: if grand_total > 0 {
+                total_out * 100 / grand_total
+            } else {
+                0
+            },
+            source,
+            total_out,
+        })
+        .collect())
+}
+
+// Dibagi jumlah kemunculan hari itu di rentang, jadi hari tanpa belanja ikut menurunkan rata-rata.
+pub fn weekday_spending_profile(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<WeekdaySpend>, String> {
+    let start = parse_date_local(start_date)?;
+    let end = parse_date_local(end_date)?;
+    if end < start {
+        return Err("end_date must be >= start_date".to_string());
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2
+             GROUP BY date_local",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut totals = [0_i64; 7];
+    for row in rows {
+        let (date_local, total) = row.map_err(|err| err.to_string())?;
+        let weekday = parse_date_local(&date_local)?.weekday();
+        totals[weekday.num_days_from_monday() as usize] += total;
+    }
+    let mut day_counts = [0_i64; 7];
+    for date in start.iter_days().take_while(|date| *date <= end) {
+        day_counts[date.weekday().num_days_from_monday() as usize] += 1;
+    }
+
+    let mut weekday = Weekday::Mon;
+    let mut profile = Vec::with_capacity(7);
+    for (total_out, day_count) in totals.into_iter().zip(day_counts) {
+        profile.push(WeekdaySpend {
+            weekday: weekday.to_string(),
+            total_out,
+            day_count,
+            avg_out: if day_count > 0 {
+                total_out / day_count
+            } else {
+                0
+            },
+        });
+        weekday = weekday.succ();
+    }
+    Ok(profile)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    fn insert_tx(conn: &Connection, date_local: &str, kind: &str, amount: i64) {
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (1, ?1, ?2, ?3, 'manual', NULL)",
+            params![date_local, kind, amount],
+        )
+        .expect("insert tx");
+    }
+
+    fn at(year: i32, month: u32, day: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(year, month, day, 12, 0, 0)
+            .single()
+            .expect("dt")
+    }
+
+    #[test]
+    fn discipline_streak_stops_at_first_overspent_day() {
+        let conn = setup_conn();
+        conn.execute(
+            "UPDATE config SET min_floor = 10000, max_ceil = 100000, resilience_days = 10 WHERE id = 1",
+            [],
+        )
+        .expect("config");
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).expect("date");
+        assert_eq!(discipline_streak(&conn, today).expect("streak").days, 0);
+
+        insert_tx(&conn, "2025-03-01", "IN", 1_000_000);
+        insert_tx(&conn, "2025-03-02", "OUT", 200_000);
+        insert_tx(&conn, "2025-03-03", "OUT", 10_000);
+        insert_tx(&conn, "2025-03-05", "OUT", 20_000);
+
+        let streak = discipline_streak(&conn, today).expect("streak");
+        assert_eq!(streak.days, 3);
+        assert_eq!(streak.start_date_local.as_deref(), Some("2025-03-03"));
+    }
+
+    #[test]
+    fn net_worth_timeline_carries_balance_across_empty_days() {
+        let conn = setup_conn();
+        conn.execute("UPDATE config SET opening_balance = 1000 WHERE id = 1", [])
+            .expect("opening balance");
+        insert_tx(&conn, "2025-04-28", "IN", 500);
+        insert_tx(&conn, "2025-04-30", "OUT", 200);
+        insert_tx(&conn, "2025-05-02", "IN", 300);
+        insert_tx(&conn, "2025-05-02", "OUT", 50);
+        insert_tx(&conn, "2025-05-09", "OUT", 999);
+
+        let timeline = net_worth_timeline(&conn, "2025-04-30", "2025-05-03").expect("timeline");
+        let points: Vec<(&str, i64)> = timeline
+            .iter()
+            .map(|point| (point.date_local.as_str(), point.balance))
+            .collect();
+        assert_eq!(
+            points,
+            vec![
+                ("2025-04-30", 1300),
+                ("2025-05-01", 1300),
+                ("2025-05-02", 1550),
+                ("2025-05-03", 1550),
+            ]
+        );
+        assert!(net_worth_timeline(&conn, "2025-05-03", "2025-05-01").is_err());
+    }
+
+    #[test]
+    fn spending_by_source_shares_of_total_out() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-05-01", "OUT", 300);
+        insert_tx(&conn, "2025-05-03", "OUT", 100);
+        insert_tx(&conn, "2025-05-02", "IN", 5000);
+        insert_tx(&conn, "2025-06-01", "OUT", 999);
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+             VALUES (1, '2025-05-05', 'OUT', 600, 'fixed_cost')",
+            [],
+        )
+        .expect("insert fixed cost tx");
+
+        let breakdown = spending_by_source(&conn, "2025-05-01", "2025-05-31").expect("breakdown");
+        let rows: Vec<(&str, i64, i64)> = breakdown
+            .iter()
+            .map(|row| (row.source.as_str(), row.total_out, row.percent_of_out))
+            .collect();
+        assert_eq!(rows, vec![("fixed_cost", 600, 60), ("manual", 400, 40)]);
+        assert!(spending_by_source(&conn, "2025-06-01", "2025-06-01")
+            .expect("june")
+            .iter()
+            .all(|row| row.percent_of_out == 100));
+    }
+
+    #[test]
+    fn weekday_profile_averages_over_weekday_occurrences() {
+        let conn = setup_conn();
+        // 2025-05-03 dan 2025-05-10 adalah Sabtu; 2025-05-05 Senin.
+        insert_tx(&conn, "2025-05-03", "OUT", 300);
+        insert_tx(&conn, "2025-05-10", "OUT", 100);
+        insert_tx(&conn, "2025-05-10", "OUT", 200);
+        insert_tx(&conn, "2025-05-05", "OUT", 70);
+        insert_tx(&conn, "2025-05-05", "IN", 5000);
+        insert_tx(&conn, "2025-05-11", "OUT", 999);
+
+        let profile = weekday_spending_profile(&conn, "2025-05-01", "2025-05-10").expect("profile");
+        assert_eq!(profile.len(), 7);
+        assert_eq!(profile[0].weekday, "Mon");
+        assert_eq!((profile[0].total_out, profile[0].day_count), (70, 1));
+        assert_eq!(profile[5].weekday, "Sat");
+        assert_eq!(
+            (
+                profile[5].total_out,
+                profile[5].day_count,
+                profile[5].avg_out
+            ),
+            (600, 2, 300)
+        );
+        assert_eq!((profile[6].total_out, profile[6].day_count), (0, 1));
+        assert!(weekday_spending_profile(&conn, "2025-05-10", "2025-05-01").is_err());
+    }
+
+    #[test]
+    fn mtd_pace_caps_last_month_day_across_31_to_30_boundary() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-04-01", "OUT", 100);
+        insert_tx(&conn, "2025-04-30", "OUT", 200);
+        insert_tx(&conn, "2025-03-31", "OUT", 999);
+        insert_tx(&conn, "2025-05-02", "OUT", 150);
+        insert_tx(&conn, "2025-05-31", "OUT", 300);
+        insert_tx(&conn, "2025-05-31", "IN", 5000);
+
+        let pace = mtd_pace(&conn, at(2025, 5, 31)).expect("pace");
+        assert_eq!(pace.this_month_out, 450);
+        assert_eq!(pace.last_month_out, 300);
+        assert_eq!(pace.last_month_compared_day, 30);
+        assert_eq!(pace.delta, 150);
+        assert_eq!(pace.delta_percent, Some(50));
+        assert_eq!(pace.direction, "faster");
+    }
+
+    #[test]
+    fn break_even_mid_month_subtracts_unpaid_bill() {
+        let conn = setup_conn();
+        conn.execute(
+            "UPDATE config SET min_floor = 1000, max_ceil = 100000, resilience_days = 10 WHERE id = 1",
+            [],
+        )
+        .expect("update config");
+        insert_tx(&conn, "2025-06-01", "IN", 200_000);
+        insert_tx(&conn, "2025-06-10", "OUT", 20_000);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Listrik', 50000, 1)",
+            [],
+        )
+        .expect("insert fixed cost");
+
+        let result = break_even_daily_spend(&conn, at(2025, 6, 15)).expect("break even");
+        assert_eq!(result.net_balance, 180_000);
+        assert_eq!(result.unpaid_fixed_costs_month, 50_000);
+        assert_eq!(result.target_penyangga, 10_000);
+        assert_eq!(result.days_remaining_in_month, 16);
+        assert_eq!(result.break_even_daily_spend, 7_500);
+    }
+
+    #[test]
+    fn period_activity_counts_and_busiest_day() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-07-01", "IN", 1_000);
+        insert_tx(&conn, "2025-07-15", "IN", 3_000);
+        insert_tx(&conn, "2025-07-03", "OUT", 100);
+        insert_tx(&conn, "2025-07-03", "OUT", 200);
+        insert_tx(&conn, "2025-07-03", "OUT", 300);
+        insert_tx(&conn, "2025-08-01", "OUT", 999);
+
+        let activity = period_activity(&conn, "2025-07").expect("activity");
+        assert_eq!(activity.income_count, 2);
+        assert_eq!(activity.avg_income, 2_000);
+        assert_eq!(activity.expense_count, 3);
+        assert_eq!(activity.avg_expense, 200);
+        assert_eq!(activity.busiest_date_local.as_deref(), Some("2025-07-03"));
+        assert_eq!(activity.busiest_tx_count, 3);
+
+        let empty = period_activity(&conn, "2025-01").expect("empty");
+        assert_eq!(empty.expense_count, 0);
+        assert!(empty.busiest_date_local.is_none());
+    }
+
+    #[test]
+    fn category_totals_groups_expenses_with_uncategorized_fallback() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-07-01", "OUT", 10_000);
+        insert_tx(&conn, "2025-07-02", "IN", 90_000);
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, category)
+             VALUES (1, '2025-07-02', 'OUT', 25000, 'manual', 'makan'),
+                    (1, '2025-07-03', 'OUT', 15000, 'manual', 'makan'),
+                    (1, '2025-08-01', 'OUT', 99000, 'manual', 'makan')",
+            [],
+        )
+        .expect("insert categorized");
+
+        let totals = category_totals(&conn, "2025-07-01", "2025-07-31").expect("totals");
+        assert_eq!(totals.len(), 2);
+        assert_eq!(totals[0].category, "makan");
+        assert_eq!(totals[0].total_out, 40_000);
+        assert_eq!(totals[0].tx_count, 2);
+        assert_eq!(totals[1].category, "uncategorized");
+        assert_eq!(totals[1].total_out, 10_000);
+    }
+
+    #[test]
+    fn income_breakdown_groups_in_by_category() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-07-01", "IN", 50_000);
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, category)
+             VALUES (1, '2025-07-01', 'IN', 8000000, 'manual', 'gaji'),
+                    (1, '2025-07-10', 'IN', 1500000, 'manual', 'freelance'),
+                    (1, '2025-07-20', 'IN', 500000, 'manual', 'freelance'),
+                    (1, '2025-07-05', 'OUT', 70000, 'manual', 'gaji')",
+            [],
+        )
+        .expect("insert categorized");
+
+        let totals = income_breakdown(&conn, "2025-07-01", "2025-07-31").expect("totals");
+        let summary: Vec<(&str, i64, i64)> = totals
+            .iter()
+            .map(|total| (total.category.as_str(), total.total_in, total.tx_count))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("gaji", 8_000_000, 1),
+                ("freelance", 2_000_000, 2),
+                ("uncategorized", 50_000, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn runway_projection_uses_30_day_average() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-06-01", "IN", 600_000);
+        insert_tx(&conn, "2025-05-31", "OUT", 30_000);
+        insert_tx(&conn, "2025-06-10", "OUT", 60_000);
+        insert_tx(&conn, "2025-05-20", "OUT", 99_000);
+
+        let runway = runway_projection(&conn, at(2025, 6, 29)).expect("runway");
+        assert_eq!(runway.net_balance, 411_000);
+        assert_eq!(runway.avg_daily_out_30d, 3_000);
+        assert_eq!(runway.projected_days_remaining, Some(137));
+        assert_eq!(runway.projected_breach_date.as_deref(), Some("2025-11-13"));
+
+        let idle = runway_projection(&conn, at(2026, 1, 31)).expect("idle");
+        assert_eq!(idle.avg_daily_out_30d, 0);
+        assert!(idle.projected_days_remaining.is_none());
+        assert!(idle.projected_breach_date.is_none());
+    }
+
+    #[test]
+    fn daily_spend_series_fills_idle_days() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2025-02-27", "OUT", 500);
+        insert_tx(&conn, "2025-03-01", "OUT", 1_000);
+        insert_tx(&conn, "2025-03-01", "OUT", 250);
+        insert_tx(&conn, "2025-03-01", "IN", 9_000);
+        insert_tx(&conn, "2025-03-02", "OUT", 7_777);
+
+        let series = daily_spend_series(&conn, at(2025, 3, 1), 3).expect("series");
+        let dates: Vec<&str> = series.iter().map(|day| day.date_local.as_str()).collect();
+        assert_eq!(dates, ["2025-02-27", "2025-02-28", "2025-03-01"]);
+        assert_eq!(series[0].total_out, 500);
+        assert_eq!(series[1].total_out, 0);
+        assert_eq!(series[1].total_in, 0);
+        assert_eq!(series[2].total_out, 1_250);
+        assert_eq!(series[2].total_in, 9_000);
+        assert!(daily_spend_series(&conn, at(2025, 3, 1), 0).is_err());
+    }
+
+    #[test]
+    fn mtd_pace_wraps_to_previous_year_in_january() {
+        let conn = setup_conn();
+        insert_tx(&conn, "2024-12-05", "OUT", 400);
+        insert_tx(&conn, "2024-12-20", "OUT", 400);
+        insert_tx(&conn, "2025-01-03", "OUT", 100);
+
+        let pace = mtd_pace(&conn, at(2025, 1, 10)).expect("pace");
+        assert_eq!(pace.last_month_out, 400);
+        assert_eq!(pace.this_month_out, 100);
+        assert_eq!(pace.direction, "slower");
+    }
+}
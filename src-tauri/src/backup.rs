@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+
+use chrono::Utc;
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::profiles;
+
+// Naikkan setiap kali bentuk dokumen backup berubah secara tidak kompatibel.
+pub const BACKUP_SCHEMA_VERSION: i64 = 2;
+
+// Urutan penting untuk restore: tabel induk diisi lebih dulu, dihapus paling akhir.
+// Backup mencakup semua profil sekaligus (v2); dokumen v1 belum punya profiles/app_state.
+const BACKUP_TABLES: &[&str] = &[
+    "profiles",
+    "app_state",
+    "config",
+    "fixed_costs",
+    "recurring_incomes",
+    "transactions",
+    "fixed_cost_payments",
+    "recurring_income_receipts",
+    "coaching_memory",
+    "no_spend_days",
+    "savings_goals",
+    "config_history",
+];
+
+// Riwayat undo tidak ikut diekspor; snapshot-nya merujuk baris yang diganti saat restore.
+const CLEARED_ON_RESTORE: &[&str] = &["transaction_trash", "fixed_cost_action_log"];
+
+type BackupRow = Map<String, Value>;
+
+#[derive(Serialize, Deserialize)]
+pub struct BackupDocument {
+    pub schema_version: i64,
+    pub exported_ts_utc: i64,
+    pub tables: BTreeMap<String, Vec<BackupRow>>,
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare(&format!("PRAGMA table_info({})", table))
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(1))
+        .map_err(|err| err.to_string())?;
+    let mut columns = Vec::new();
+    for row in rows {
+        columns.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(columns)
+}
+
+fn sql_to_json(value: ValueRef) -> Result<Value, String> {
+    match value {
+        ValueRef::Null => Ok(Value::Null),
+        ValueRef::Integer(number) => Ok(Value::from(number)),
+        ValueRef::Real(number) => Ok(Value::from(number)),
+        ValueRef::Text(text) => Ok(Value::from(
+            std::str::from_utf8(text).map_err(|err| err.to_string())?,
+        )),
+        ValueRef::Blob(_) => Err("blob columns are not supported in backups".to_string()),
+    }
+}
+
+fn json_to_sql(value: &Value) -> Result<SqlValue, String> {
+    match value {
+        Value::Null => Ok(SqlValue::Null),
+        Value::Bool(flag) => Ok(SqlValue::Integer(i64::from(*flag))),
+        Value::Number(number) => number
+            .as_i64()
+            .map(SqlValue::Integer)
+            .or_else(|| number.as_f64().map(SqlValue::Real))
+            .ok_or_else(|| format!("unsupported number: {}", number)),
+        Value::String(text) => Ok(SqlValue::Text(text.clone())),
+        _ => Err(format!("unsupported value: {}", value)),
+    }
+}
+
+fn dump_table(conn: &Connection, table: &str) -> Result<Vec<BackupRow>, String> {
+    let columns = table_columns(conn, table)?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {} FROM {} ORDER BY rowid",
+            columns.join(", "),
+            table
+        ))
+        .map_err(|err| err.to_string())?;
+    let mut rows = stmt.query([]).map_err(|err| err.to_string())?;
+
+    let mut dumped = Vec::new();
+    while let Some(row) = rows.next().map_err(|err| err.to_string())? {
+        let mut object = Map::new();
+        for (index, column) in columns.iter().enumerate() {
+            let value = row.get_ref(index).map_err(|err| err.to_string())?;
+            object.insert(column.clone(), sql_to_json(value)?);
+        }
+        dumped.push(object);
+    }
+    Ok(dumped)
+}
+
+pub fn export_backup(conn: &Connection) -> Result<BackupDocument, String> {
+    let mut tables = BTreeMap::new();
+    for table in BACKUP_TABLES {
+        tables.insert(table.to_string(), dump_table(conn, table)?);
+    }
+    Ok(BackupDocument {
+        schema_version: BACKUP_SCHEMA_VERSION,
+        exported_ts_utc: Utc::now().timestamp_millis(),
+        tables,
+    })
+}
+
+// Data semua profil diganti dalam satu transaksi.
+pub fn restore_backup(conn: &mut Connection, document: &BackupDocument) -> Result<(), String> {
+    if document.schema_version > BACKUP_SCHEMA_VERSION {
+        return Err(format!(
+            "Backup schema_version {} lebih baru dari versi aplikasi ({}); perbarui aplikasi dulu",
+            document.schema_version, BACKUP_SCHEMA_VERSION
+        ));
+    }
+    if document.schema_version < 1 {
+        return Err(format!(
+            "Backup schema_version {} tidak valid",
+            document.schema_version
+        ));
+    }
+    if !document.tables.contains_key("config") {
+        return Err("Backup tidak berisi tabel config".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    for table in CLEARED_ON_RESTORE.iter().chain(BACKUP_TABLES.iter().rev()) {
+        tx.execute(&format!("DELETE FROM {}", table), [])
+            .map_err(|err| err.to_string())?;
+    }
+
+    for table in BACKUP_TABLES {
+        let Some(rows) = document.tables.get(*table) else {
+            continue;
+        };
+        // Kolom yang tidak dikenal (mis. dari dump lama) diabaikan; kolom hilang memakai default.
+        let known_columns = table_columns(&tx, table)?;
+        for (index, row) in rows.iter().enumerate() {
+            let columns: Vec<&String> = row
+                .keys()
+                .filter(|column| known_columns.contains(column))
+                .collect();
+            if columns.is_empty() {
+                continue;
+            }
+            let values = columns
+                .iter()
+                .map(|column| json_to_sql(&row[column.as_str()]))
+                .collect::<Result<Vec<_>, String>>()
+                .map_err(|err| format!("{} row {}: {}", table, index + 1, err))?;
+            let placeholders = vec!["?"; columns.len()].join(", ");
+            let column_list = columns
+                .iter()
+                .map(|column| column.as_str())
+                .collect::<Vec<_>>()
+                .join(", ");
+            tx.execute(
+                &format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    table, column_list, placeholders
+                ),
+                params_from_iter(values),
+            )
+            .map_err(|err| format!("{} row {}: {}", table, index + 1, err))?;
+        }
+    }
+
+    // Dokumen v1 hanya berisi data profil utama (profile_id kosong memakai default).
+    tx.execute(
+        "INSERT OR IGNORE INTO profiles (id, name, created_ts_utc) VALUES (?1, 'Utama', ?2)",
+        params![profiles::DEFAULT_PROFILE_ID, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "INSERT OR IGNORE INTO app_state (id, active_profile_id) VALUES (1, ?1)",
+        [profiles::DEFAULT_PROFILE_ID],
+    )
+    .map_err(|err| err.to_string())?;
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    #[test]
+    fn backup_round_trip_restores_rows() {
+        let source = setup_conn();
+        source
+            .execute(
+                "UPDATE config SET min_floor = 15000, opening_balance = 250000 WHERE id = 1",
+                [],
+            )
+            .expect("update config");
+        source
+            .execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, description)
+                 VALUES (1, '2025-04-01', 'OUT', 32000, 'manual', 'Bensin')",
+                [],
+            )
+            .expect("insert tx");
+        source
+            .execute(
+                "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Internet', 300000, 1)",
+                [],
+            )
+            .expect("insert fixed cost");
+
+        let json = serde_json::to_string(&export_backup(&source).expect("export")).expect("json");
+
+        let mut target = setup_conn();
+        target
+            .execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+                 VALUES (1, '2025-01-01', 'IN', 1, 'manual')",
+                [],
+            )
+            .expect("insert stale tx");
+        let document: BackupDocument = serde_json::from_str(&json).expect("parse");
+        restore_backup(&mut target, &document).expect("restore");
+
+        let (count, description): (i64, String) = target
+            .query_row(
+                "SELECT COUNT(*), MAX(description) FROM transactions",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("tx");
+        assert_eq!(count, 1);
+        assert_eq!(description, "Bensin");
+        let (min_floor, opening_balance): (i64, i64) = target
+            .query_row(
+                "SELECT min_floor, opening_balance FROM config WHERE id = 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("config");
+        assert_eq!(min_floor, 15000);
+        assert_eq!(opening_balance, 250000);
+    }
+
+    #[test]
+    fn restore_clears_undo_history() {
+        let mut conn = setup_conn();
+        let document = export_backup(&conn).expect("export");
+        conn.execute_batch(
+            "INSERT INTO transaction_trash (transaction_id, deleted_ts_utc, snapshot_json)
+               VALUES (7, 1, '{}');
+             INSERT INTO fixed_cost_action_log
+               (action_type, fixed_cost_id, period_ym, snapshot_json, created_ts_utc)
+               VALUES ('paid', 3, '2025-04', '[]', 1);",
+        )
+        .expect("seed undo history");
+
+        restore_backup(&mut conn, &document).expect("restore");
+        for table in CLEARED_ON_RESTORE {
+            let count: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .expect("count");
+            assert_eq!(count, 0, "{}", table);
+        }
+    }
+
+    #[test]
+    fn restore_rejects_newer_schema_version() {
+        let mut conn = setup_conn();
+        let mut document = export_backup(&conn).expect("export");
+        document.schema_version = BACKUP_SCHEMA_VERSION + 1;
+        let result = restore_backup(&mut conn, &document);
+        assert!(matches!(result, Err(err) if err.contains("lebih baru")));
+    }
+}
@@ -0,0 +1,46 @@
+use chrono::{DateTime, Local, NaiveDate};
+
+// Sumber "sekarang"; command memakai SystemClock, test memakai FixedClock.
+pub(crate) trait Clock {
+    fn now_local(&self) -> DateTime<Local>;
+
+    fn today(&self) -> NaiveDate {
+        self.now_local().date_naive()
+    }
+
+    fn today_local(&self) -> String {
+        self.today().format("%Y-%m-%d").to_string()
+    }
+}
+
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_local(&self) -> DateTime<Local> {
+        Local::now()
+    }
+}
+
+#[cfg(test)]
+pub(crate) struct FixedClock(pub DateTime<Local>);
+
+#[cfg(test)]
+impl FixedClock {
+    // Jam 12 siang menjaga tanggal tetap sama apa pun zona waktu mesin test.
+    pub(crate) fn at(year: i32, month: u32, day: u32) -> Self {
+        use chrono::TimeZone;
+        FixedClock(
+            Local
+                .with_ymd_and_hms(year, month, day, 12, 0, 0)
+                .single()
+                .expect("valid local time"),
+        )
+    }
+}
+
+#[cfg(test)]
+impl Clock for FixedClock {
+    fn now_local(&self) -> DateTime<Local> {
+        self.0
+    }
+}
@@ -0,0 +1,242 @@
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct CsvImportReport {
+    pub dry_run: bool,
+    pub committed: bool,
+    pub rows_ok: i64,
+    pub rows_failed: i64,
+    pub errors: Vec<String>,
+}
+
+struct CsvRow {
+    date_local: String,
+    kind: String,
+    amount: i64,
+    description: Option<String>,
+    category: Option<String>,
+}
+
+struct ColumnIndex {
+    date_local: usize,
+    kind: usize,
+    amount: usize,
+    description: Option<usize>,
+    category: Option<usize>,
+}
+
+// Parser sederhana: mendukung field ber-kutip dan "" sebagai escape, tanpa baris ganda.
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    field.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if field.is_empty() => in_quotes = true,
+            ',' if !in_quotes => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    if in_quotes {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn resolve_columns(header: &[String]) -> Result<ColumnIndex, String> {
+    let find = |name: &str| header.iter().position(|column| column.trim() == name);
+    let require = |name: &str| find(name).ok_or_else(|| format!("missing column: {}", name));
+    Ok(ColumnIndex {
+        date_local: require("date_local")?,
+        kind: require("kind")?,
+        amount: require("amount")?,
+        description: find("description"),
+        category: find("category"),
+    })
+}
+
+fn optional_field(fields: &[String], index: Option<usize>) -> Option<String> {
+    index
+        .and_then(|index| fields.get(index))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn parse_row(fields: &[String], columns: &ColumnIndex) -> Result<CsvRow, String> {
+    let field = |index: usize| {
+        fields
+            .get(index)
+            .map(|value| value.trim())
+            .ok_or_else(|| "missing field".to_string())
+    };
+
+    let date_local = field(columns.date_local)?;
+    // chrono menerima "2025-5-1"; disimpan dalam bentuk baku supaya filter rentang cocok.
+    let date_local = NaiveDate::parse_from_str(date_local, "%Y-%m-%d")
+        .map_err(|err| format!("invalid date_local '{}': {}", date_local, err))?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let kind = field(columns.kind)?;
+    if kind != "IN" && kind != "OUT" {
+        return Err(format!("invalid kind '{}': must be IN or OUT", kind));
+    }
+
+    let amount_raw = field(columns.amount)?;
+    let amount: i64 = amount_raw
+        .parse()
+        .map_err(|_| format!("invalid amount '{}'", amount_raw))?;
+    crate::validate_amount_for_kind(kind, amount)?;
+
+    Ok(CsvRow {
+        date_local,
+        kind: kind.to_string(),
+        amount,
+        description: optional_field(fields, columns.description),
+        category: optional_field(fields, columns.category).map(|value| value.to_lowercase()),
+    })
+}
+
+// Tidak ada yang ditulis bila satu baris gagal atau saat dry_run.
+pub fn import_transactions_csv(
+    conn: &mut Connection,
+    content: &str,
+    dry_run: bool,
+) -> Result<CsvImportReport, String> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty());
+    let (_, header_line) = lines.next().ok_or_else(|| "CSV kosong".to_string())?;
+    let header_line = header_line.trim_start_matches('\u{feff}');
+    let columns = resolve_columns(&split_csv_line(header_line)?)?;
+
+    let mut report = CsvImportReport {
+        dry_run,
+        committed: false,
+        rows_ok: 0,
+        rows_failed: 0,
+        errors: Vec::new(),
+    };
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let ts_utc = Utc::now().timestamp_millis();
+    for (index, line) in lines {
+        let line_no = index + 1;
+        let row = split_csv_line(line).and_then(|fields| parse_row(&fields, &columns));
+        match row {
+            Ok(row) => {
+                tx.execute(
+                    "INSERT INTO transactions
+                        (ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, profile_id)
+                     VALUES (?1, ?2, ?3, ?4, 'import', NULL, ?5, ?6, (SELECT active_profile_id FROM app_state))",
+                    params![
+                        ts_utc,
+                        row.date_local,
+                        row.kind,
+                        row.amount,
+                        row.description,
+                        row.category
+                    ],
+                )
+                .map_err(|err| err.to_string())?;
+                report.rows_ok += 1;
+            }
+            Err(err) => {
+                report.rows_failed += 1;
+                report.errors.push(format!("line {}: {}", line_no, err));
+            }
+        }
+    }
+
+    if dry_run || report.rows_failed > 0 {
+        tx.rollback().map_err(|err| err.to_string())?;
+    } else {
+        tx.commit().map_err(|err| err.to_string())?;
+        report.committed = true;
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    fn count_tx(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count")
+    }
+
+    #[test]
+    fn import_commits_valid_rows() {
+        let mut conn = setup_conn();
+        let csv = "date_local,kind,amount,description\n\
+                   2025-05-01,IN,500000,Gaji\n\
+                   2025-05-02,OUT,25000,\"Makan, siang\"\n\
+                   2025-5-3,OUT,10000,Parkir\n";
+
+        let report = import_transactions_csv(&mut conn, csv, false).expect("import");
+        assert!(report.committed);
+        assert_eq!(report.rows_ok, 3);
+        assert_eq!(report.rows_failed, 0);
+        let description: String = conn
+            .query_row(
+                "SELECT description FROM transactions WHERE kind = 'OUT' AND amount = 25000",
+                [],
+                |row| row.get(0),
+            )
+            .expect("description");
+        assert_eq!(description, "Makan, siang");
+        let unpadded: String = conn
+            .query_row(
+                "SELECT date_local FROM transactions WHERE description = 'Parkir'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("date_local");
+        assert_eq!(unpadded, "2025-05-03");
+    }
+
+    #[test]
+    fn import_rolls_back_on_bad_row_and_in_dry_run() {
+        let mut conn = setup_conn();
+        let csv = "date_local,kind,amount\n\
+                   2025-05-01,IN,500000\n\
+                   2025-05-32,OUT,1000\n\
+                   2025-05-03,XFER,1000\n\
+                   2025-05-04,OUT,-5\n\
+                   2025-05-05,IN,0\n";
+
+        let report = import_transactions_csv(&mut conn, csv, false).expect("import");
+        assert!(!report.committed);
+        assert_eq!(report.rows_ok, 1);
+        assert_eq!(report.rows_failed, 4);
+        assert!(report.errors[0].starts_with("line 3:"));
+        assert_eq!(count_tx(&conn), 0);
+
+        let valid = "date_local,kind,amount\n2025-05-01,IN,500000\n";
+        let report = import_transactions_csv(&mut conn, valid, true).expect("dry run");
+        assert_eq!(report.rows_ok, 1);
+        assert!(!report.committed);
+        assert_eq!(count_tx(&conn), 0);
+    }
+}
@@ -1,8 +1,11 @@
 use std::{error::Error, fs, path::PathBuf};
 
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
 use tauri::{AppHandle, Manager};
 
+use crate::profiles;
+
 type AnyResult<T> = Result<T, Box<dyn Error>>;
 
 fn db_path(app: &AppHandle) -> AnyResult<PathBuf> {
@@ -18,9 +21,102 @@ pub fn open_connection(app: &AppHandle) -> AnyResult<Connection> {
 
 pub fn init_db(app: &AppHandle) -> AnyResult<()> {
     let conn = open_connection(app)?;
+    init_schema(&conn)
+}
+
+#[derive(Serialize)]
+pub struct CompactReport {
+    pub size_before_bytes: u64,
+    pub size_after_bytes: u64,
+}
+
+// VACUUM ditolak di dalam transaksi, jadi pakai koneksi baru.
+pub fn compact_database(app: &AppHandle) -> AnyResult<CompactReport> {
+    let path = db_path(app)?;
+    let size_before_bytes = fs::metadata(&path)?.len();
+    let conn = Connection::open(&path)?;
+    conn.execute_batch("VACUUM;")?;
+    drop(conn);
+    let size_after_bytes = fs::metadata(&path)?.len();
+    Ok(CompactReport {
+        size_before_bytes,
+        size_after_bytes,
+    })
+}
+
+#[derive(Serialize)]
+pub struct QueryPlanRow {
+    pub id: i64,
+    pub parent: i64,
+    pub detail: String,
+}
+
+pub(crate) fn explain_query_plan(conn: &Connection, sql: &str) -> AnyResult<Vec<QueryPlanRow>> {
+    let mut stmt = conn.prepare(&format!("EXPLAIN QUERY PLAN {}", sql))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(QueryPlanRow {
+            id: row.get(0)?,
+            parent: row.get(1)?,
+            detail: row.get(3)?,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+type Migration = fn(&Connection) -> AnyResult<()>;
+
+// Migrasi bernomor; PRAGMA user_version menyimpan berapa yang sudah jalan.
+// Tambahkan langkah baru di akhir, jangan ubah urutan. v1 adalah skema sebelum ada
+// versioning, jadi langkahnya harus tetap idempoten untuk DB lama (user_version = 0).
+const MIGRATIONS: &[Migration] = &[
+    migrate_v1_baseline,
+    migrate_v2_transactions_date_kind_index,
+    migrate_v3_memory_retention_limit,
+    migrate_v4_recurring_incomes,
+    migrate_v5_large_amount_multiplier,
+    migrate_v6_transaction_note,
+    migrate_v7_recommend_rounding,
+    migrate_v8_spending_frozen,
+    migrate_v9_carryover_enabled,
+    migrate_v10_time_buckets,
+    migrate_v11_memory_recording_enabled,
+    migrate_v12_max_ceil_mode,
+    migrate_v13_profiles,
+    migrate_v14_transaction_trash,
+    migrate_v15_week_start_day,
+    migrate_v16_fixed_cost_payment_skipped,
+    migrate_v17_low_buffer_days,
+    migrate_v18_exclude_fixed_from_daily,
+    migrate_v19_config_history_reason,
+    migrate_v20_profile_scoping,
+    migrate_v21_savings_goal_link,
+];
+
+pub(crate) fn latest_schema_version() -> i64 {
+    MIGRATIONS.len() as i64
+}
+
+pub(crate) fn init_schema(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+    let current: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if current > latest_schema_version() {
+        return Err(format!(
+            "database schema version {} is newer than this app supports ({})",
+            current,
+            latest_schema_version()
+        )
+        .into());
+    }
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        migration(conn)?;
+        conn.pragma_update(None, "user_version", index as i64 + 1)?;
+    }
+    Ok(())
+}
+
+fn migrate_v1_baseline(conn: &Connection) -> AnyResult<()> {
     conn.execute_batch(
-        "PRAGMA foreign_keys = ON;
-        CREATE TABLE IF NOT EXISTS transactions (
+        "CREATE TABLE IF NOT EXISTS transactions (
           id INTEGER PRIMARY KEY AUTOINCREMENT,
           ts_utc INTEGER NOT NULL,
           date_local TEXT NOT NULL,
@@ -57,15 +153,445 @@ pub fn init_db(app: &AppHandle) -> AnyResult<()> {
         );",
     )?;
 
-    ensure_config_row(&conn)?;
-    ensure_config_columns(&conn)?;
-    ensure_transactions_columns(&conn)?;
-    ensure_fixed_cost_columns(&conn)?;
-    ensure_fixed_cost_payments_columns(&conn)?;
-    ensure_fixed_cost_payments_nullable(&conn)?;
-    ensure_fixed_cost_payments_index(&conn)?;
-    ensure_coaching_memory_table(&conn)?;
-    migrate_legacy_fixed_cost_payments(&conn)?;
+    ensure_config_row(conn)?;
+    ensure_config_columns(conn)?;
+    ensure_transactions_columns(conn)?;
+    ensure_fixed_cost_columns(conn)?;
+    ensure_fixed_cost_payments_columns(conn)?;
+    ensure_fixed_cost_payments_nullable(conn)?;
+    ensure_fixed_cost_payments_index(conn)?;
+    ensure_coaching_memory_table(conn)?;
+    ensure_no_spend_days_table(conn)?;
+    ensure_savings_goals_table(conn)?;
+    ensure_config_history_table(conn)?;
+    ensure_fixed_cost_action_log_table(conn)?;
+    migrate_legacy_fixed_cost_payments(conn)?;
+    Ok(())
+}
+
+// Rentang tanggal + kind dipakai list_transactions_between dan jumlah 7 hari di insight.
+fn migrate_v2_transactions_date_kind_index(conn: &Connection) -> AnyResult<()> {
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_transactions_date_kind ON transactions(date_local, kind)",
+        [],
+    )?;
+    Ok(())
+}
+
+// Default 200 sama dengan batas trim_memory sebelum bisa diatur.
+fn migrate_v3_memory_retention_limit(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "memory_retention_limit")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN memory_retention_limit INTEGER NOT NULL DEFAULT 200",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Pola sama dengan fixed_costs + fixed_cost_payments: satu receipt per bulan.
+fn migrate_v4_recurring_incomes(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS recurring_incomes (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          name TEXT NOT NULL,
+          amount INTEGER NOT NULL,
+          is_active INTEGER NOT NULL DEFAULT 1,
+          day_of_month INTEGER,
+          created_ts_utc INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS recurring_income_receipts (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          recurring_income_id INTEGER NOT NULL,
+          period_ym TEXT NOT NULL,
+          received_date_local TEXT NOT NULL,
+          received_ts_utc INTEGER NOT NULL,
+          tx_id INTEGER,
+          FOREIGN KEY(recurring_income_id) REFERENCES recurring_incomes(id)
+        );
+        CREATE UNIQUE INDEX IF NOT EXISTS idx_recurring_income_receipts_period
+          ON recurring_income_receipts(recurring_income_id, period_ym);",
+    )?;
+    Ok(())
+}
+
+fn migrate_v5_large_amount_multiplier(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "large_amount_multiplier")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN large_amount_multiplier INTEGER NOT NULL DEFAULT 10",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Catatan bebas per transaksi; NULL untuk baris lama.
+fn migrate_v6_transaction_note(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "transactions", "note")? {
+        conn.execute("ALTER TABLE transactions ADD COLUMN note TEXT", [])?;
+    }
+    Ok(())
+}
+
+fn migrate_v7_recommend_rounding(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "recommend_rounding")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN recommend_rounding INTEGER NOT NULL DEFAULT 1000",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_v8_spending_frozen(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "spending_frozen")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN spending_frozen INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+fn migrate_v9_carryover_enabled(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "carryover_enabled")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN carryover_enabled INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Jam mulai tiap bagian hari untuk insight; bawaan sama dengan nilai lama yang di-hardcode.
+fn migrate_v10_time_buckets(conn: &Connection) -> AnyResult<()> {
+    for (column, default_hour) in [
+        ("morning_start_hour", 5),
+        ("midday_start_hour", 10),
+        ("afternoon_start_hour", 15),
+        ("evening_start_hour", 18),
+        ("night_start_hour", 22),
+    ] {
+        if !table_has_column(conn, "config", column)? {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE config ADD COLUMN {} INTEGER NOT NULL DEFAULT {}",
+                    column, default_hour
+                ),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v11_memory_recording_enabled(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "memory_recording_enabled")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN memory_recording_enabled INTEGER NOT NULL DEFAULT 1",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// 'absolute' mempertahankan perilaku lama: max_ceil dipakai apa adanya.
+fn migrate_v12_max_ceil_mode(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "max_ceil_mode")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN max_ceil_mode TEXT NOT NULL DEFAULT 'absolute'",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "max_ceil_percent")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN max_ceil_percent INTEGER NOT NULL DEFAULT 10",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Daftar profil dan profil aktif; kolom profile_id di tabel data menyusul di v20.
+fn migrate_v13_profiles(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS profiles (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          name TEXT NOT NULL,
+          created_ts_utc INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS app_state (
+          id INTEGER PRIMARY KEY CHECK (id = 1),
+          active_profile_id INTEGER NOT NULL
+        );",
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO profiles (id, name, created_ts_utc)
+         VALUES (?1, 'Utama', CAST(strftime('%s', 'now') AS INTEGER) * 1000)",
+        [profiles::DEFAULT_PROFILE_ID],
+    )?;
+    conn.execute(
+        "INSERT OR IGNORE INTO app_state (id, active_profile_id) VALUES (1, ?1)",
+        [profiles::DEFAULT_PROFILE_ID],
+    )?;
+    Ok(())
+}
+
+// Transaksi yang dihapus disimpan utuh (beserta pembayaran/penerimaan terkait) agar bisa
+// dikembalikan; tabel transactions sendiri tetap hanya berisi transaksi hidup.
+fn migrate_v14_transaction_trash(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS transaction_trash (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          transaction_id INTEGER NOT NULL,
+          deleted_ts_utc INTEGER NOT NULL,
+          snapshot_json TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+// 0 = Minggu .. 6 = Sabtu; bawaan Senin sama dengan minggu ISO yang dipakai sebelumnya.
+fn migrate_v15_week_start_day(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "week_start_day")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN week_start_day INTEGER NOT NULL DEFAULT 1",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Baris "dilewati" tidak punya transaksi maupun tanggal bayar; is_skipped yang membedakannya
+// dari pembayaran yatim.
+fn migrate_v16_fixed_cost_payment_skipped(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "fixed_cost_payments", "is_skipped")? {
+        conn.execute(
+            "ALTER TABLE fixed_cost_payments ADD COLUMN is_skipped INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Bawaan 7 hari sama dengan ambang low_buffer sebelum bisa diatur.
+fn migrate_v17_low_buffer_days(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "low_buffer_days")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN low_buffer_days INTEGER NOT NULL DEFAULT 7",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Bawaan 0: today_out tetap menghitung pembayaran biaya tetap seperti sebelumnya.
+fn migrate_v18_exclude_fixed_from_daily(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config", "exclude_fixed_from_daily")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN exclude_fixed_from_daily INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    Ok(())
+}
+
+// Alasan opsional dari pengguna, mis. saat mengganti coach_mode.
+fn migrate_v19_config_history_reason(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "config_history", "reason")? {
+        conn.execute("ALTER TABLE config_history ADD COLUMN reason TEXT", [])?;
+    }
+    Ok(())
+}
+
+// fixed_cost_payments dan recurring_income_receipts ikut profil induknya.
+const PROFILE_SCOPED_TABLES: &[&str] = &[
+    "transactions",
+    "fixed_costs",
+    "recurring_incomes",
+    "coaching_memory",
+    "savings_goals",
+    "config_history",
+    "fixed_cost_action_log",
+    "transaction_trash",
+];
+
+// Semua data yang sudah ada menjadi milik profil bawaan: DEFAULT mengisi baris lama.
+// config dan no_spend_days dibangun ulang karena batasan unik lamanya berlaku global.
+fn migrate_v20_profile_scoping(conn: &Connection) -> AnyResult<()> {
+    for table in PROFILE_SCOPED_TABLES {
+        if !table_has_column(conn, table, "profile_id")? {
+            conn.execute(
+                &format!(
+                    "ALTER TABLE {} ADD COLUMN profile_id INTEGER NOT NULL DEFAULT {}",
+                    table,
+                    profiles::DEFAULT_PROFILE_ID
+                ),
+                [],
+            )?;
+        }
+    }
+
+    if !table_has_column(conn, "config", "profile_id")? {
+        conn.execute_batch(&format!(
+            "CREATE TABLE config_new (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL UNIQUE DEFAULT {default_profile},
+              min_floor INTEGER NOT NULL,
+              max_ceil INTEGER NOT NULL,
+              resilience_days INTEGER NOT NULL,
+              created_ts_utc INTEGER NOT NULL,
+              updated_ts_utc INTEGER NOT NULL,
+              coach_mode TEXT NOT NULL DEFAULT 'calm',
+              income_smoothing_days INTEGER NOT NULL DEFAULT 0,
+              opening_balance INTEGER NOT NULL DEFAULT 0,
+              budget_granularity TEXT NOT NULL DEFAULT 'daily',
+              near_limit_ratio INTEGER NOT NULL DEFAULT 80,
+              record_near_limit INTEGER NOT NULL DEFAULT 0,
+              currency_code TEXT NOT NULL DEFAULT 'IDR',
+              minor_units INTEGER NOT NULL DEFAULT 0,
+              memory_retention_limit INTEGER NOT NULL DEFAULT 200,
+              large_amount_multiplier INTEGER NOT NULL DEFAULT 10,
+              recommend_rounding INTEGER NOT NULL DEFAULT 1000,
+              spending_frozen INTEGER NOT NULL DEFAULT 0,
+              carryover_enabled INTEGER NOT NULL DEFAULT 0,
+              morning_start_hour INTEGER NOT NULL DEFAULT 5,
+              midday_start_hour INTEGER NOT NULL DEFAULT 10,
+              afternoon_start_hour INTEGER NOT NULL DEFAULT 15,
+              evening_start_hour INTEGER NOT NULL DEFAULT 18,
+              night_start_hour INTEGER NOT NULL DEFAULT 22,
+              memory_recording_enabled INTEGER NOT NULL DEFAULT 1,
+              max_ceil_mode TEXT NOT NULL DEFAULT 'absolute',
+              max_ceil_percent INTEGER NOT NULL DEFAULT 10,
+              week_start_day INTEGER NOT NULL DEFAULT 1,
+              low_buffer_days INTEGER NOT NULL DEFAULT 7,
+              exclude_fixed_from_daily INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT INTO config_new (id, profile_id, min_floor, max_ceil, resilience_days,
+              created_ts_utc, updated_ts_utc, coach_mode, income_smoothing_days, opening_balance,
+              budget_granularity, near_limit_ratio, record_near_limit, currency_code, minor_units,
+              memory_retention_limit, large_amount_multiplier, recommend_rounding, spending_frozen,
+              carryover_enabled, morning_start_hour, midday_start_hour, afternoon_start_hour,
+              evening_start_hour, night_start_hour, memory_recording_enabled, max_ceil_mode,
+              max_ceil_percent, week_start_day, low_buffer_days, exclude_fixed_from_daily)
+              SELECT id, {default_profile}, min_floor, max_ceil, resilience_days,
+                created_ts_utc, updated_ts_utc, coach_mode, income_smoothing_days, opening_balance,
+                budget_granularity, near_limit_ratio, record_near_limit, currency_code, minor_units,
+                memory_retention_limit, large_amount_multiplier, recommend_rounding, spending_frozen,
+                carryover_enabled, morning_start_hour, midday_start_hour, afternoon_start_hour,
+                evening_start_hour, night_start_hour, memory_recording_enabled, max_ceil_mode,
+                max_ceil_percent, week_start_day, low_buffer_days, exclude_fixed_from_daily
+              FROM config;
+            DROP TABLE config;
+            ALTER TABLE config_new RENAME TO config;",
+            default_profile = profiles::DEFAULT_PROFILE_ID
+        ))?;
+    }
+
+    if !table_has_column(conn, "no_spend_days", "profile_id")? {
+        conn.execute_batch(&format!(
+            "CREATE TABLE no_spend_days_new (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL DEFAULT {default_profile},
+              date_local TEXT NOT NULL,
+              created_ts_utc INTEGER NOT NULL,
+              UNIQUE(profile_id, date_local)
+            );
+            INSERT INTO no_spend_days_new (id, date_local, created_ts_utc)
+              SELECT id, date_local, created_ts_utc FROM no_spend_days;
+            DROP TABLE no_spend_days;
+            ALTER TABLE no_spend_days_new RENAME TO no_spend_days;",
+            default_profile = profiles::DEFAULT_PROFILE_ID
+        ))?;
+    }
+    Ok(())
+}
+
+// Setoran lama dikaitkan ke targetnya lewat deskripsi "Tabungan: <nama>" bila namanya unik;
+// sisanya tetap tanpa target dan mengurangi cadangan secara global seperti sebelumnya.
+fn migrate_v21_savings_goal_link(conn: &Connection) -> AnyResult<()> {
+    if !table_has_column(conn, "transactions", "savings_goal_id")? {
+        conn.execute(
+            "ALTER TABLE transactions ADD COLUMN savings_goal_id INTEGER",
+            [],
+        )?;
+    }
+    conn.execute(
+        "UPDATE transactions
+         SET savings_goal_id = (
+           SELECT MIN(g.id) FROM savings_goals g
+           WHERE 'Tabungan: ' || g.name = transactions.description
+             AND g.profile_id = transactions.profile_id
+           HAVING COUNT(*) = 1
+         )
+         WHERE source = 'savings' AND savings_goal_id IS NULL",
+        [],
+    )?;
+    Ok(())
+}
+
+pub(crate) fn insert_profile_config(conn: &Connection, profile_id: i64) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp_millis();
+    conn.execute(
+        "INSERT INTO config (profile_id, min_floor, max_ceil, resilience_days, created_ts_utc, updated_ts_utc)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+        params![
+            profile_id,
+            DEFAULT_MIN_FLOOR,
+            DEFAULT_MAX_CEIL,
+            DEFAULT_RESILIENCE_DAYS,
+            now
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+// Kolom config awal tidak punya DEFAULT di skema; nilainya ditetapkan di sini.
+const DEFAULT_MIN_FLOOR: i64 = 0;
+const DEFAULT_MAX_CEIL: i64 = 100_000;
+const DEFAULT_RESILIENCE_DAYS: i64 = 30;
+
+#[derive(Serialize)]
+pub struct ConfigDefaults {
+    pub min_floor: i64,
+    pub max_ceil: i64,
+    pub resilience_days: i64,
+}
+
+pub(crate) fn config_defaults() -> ConfigDefaults {
+    ConfigDefaults {
+        min_floor: DEFAULT_MIN_FLOOR,
+        max_ceil: DEFAULT_MAX_CEIL,
+        resilience_days: DEFAULT_RESILIENCE_DAYS,
+    }
+}
+
+// Semua nilai config profil aktif kembali ke nilai instalasi; kolom dari migrasi memakai
+// DEFAULT skemanya. id, profile_id, dan created_ts_utc dibiarkan.
+pub(crate) fn reset_config_row(conn: &Connection) -> AnyResult<()> {
+    let mut assignments = vec![
+        format!("min_floor = {}", DEFAULT_MIN_FLOOR),
+        format!("max_ceil = {}", DEFAULT_MAX_CEIL),
+        format!("resilience_days = {}", DEFAULT_RESILIENCE_DAYS),
+    ];
+    let mut stmt = conn.prepare("PRAGMA table_info(config)")?;
+    let mut rows = stmt.query([])?;
+    while let Some(row) = rows.next()? {
+        let name: String = row.get(1)?;
+        let default: Option<String> = row.get(4)?;
+        if let Some(default) = default.filter(|_| name != "profile_id") {
+            assignments.push(format!("{} = {}", name, default));
+        }
+    }
+    conn.execute(
+        &format!(
+            "UPDATE config SET {}, updated_ts_utc = ?1
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+            assignments.join(", ")
+        ),
+        [chrono::Utc::now().timestamp_millis()],
+    )?;
     Ok(())
 }
 
@@ -79,25 +605,25 @@ fn ensure_config_row(conn: &Connection) -> AnyResult<()> {
             conn.execute(
                 "INSERT INTO config (id, min_floor, max_ceil, resilience_days, burn_pool_ratio, coach_mode, created_ts_utc, updated_ts_utc)
                  VALUES (1, ?1, ?2, ?3, ?4, ?5, ?6, ?6)",
-                params![0_i64, 100_000_i64, 30_i64, 50_i64, "calm", now],
+                params![DEFAULT_MIN_FLOOR, DEFAULT_MAX_CEIL, DEFAULT_RESILIENCE_DAYS, 50_i64, "calm", now],
             )?;
         } else if table_has_column(conn, "config", "burn_pool_ratio")? {
             conn.execute(
                 "INSERT INTO config (id, min_floor, max_ceil, resilience_days, burn_pool_ratio, created_ts_utc, updated_ts_utc)
                  VALUES (1, ?1, ?2, ?3, ?4, ?5, ?5)",
-                params![0_i64, 100_000_i64, 30_i64, 50_i64, now],
+                params![DEFAULT_MIN_FLOOR, DEFAULT_MAX_CEIL, DEFAULT_RESILIENCE_DAYS, 50_i64, now],
             )?;
         } else if table_has_column(conn, "config", "coach_mode")? {
             conn.execute(
                 "INSERT INTO config (id, min_floor, max_ceil, resilience_days, coach_mode, created_ts_utc, updated_ts_utc)
                  VALUES (1, ?1, ?2, ?3, ?4, ?5, ?5)",
-                params![0_i64, 100_000_i64, 30_i64, "calm", now],
+                params![DEFAULT_MIN_FLOOR, DEFAULT_MAX_CEIL, DEFAULT_RESILIENCE_DAYS, "calm", now],
             )?;
         } else {
             conn.execute(
                 "INSERT INTO config (id, min_floor, max_ceil, resilience_days, created_ts_utc, updated_ts_utc)
                  VALUES (1, ?1, ?2, ?3, ?4, ?4)",
-                params![0_i64, 100_000_i64, 30_i64, now],
+                params![DEFAULT_MIN_FLOOR, DEFAULT_MAX_CEIL, DEFAULT_RESILIENCE_DAYS, now],
             )?;
         }
     }
@@ -111,6 +637,48 @@ fn ensure_config_columns(conn: &Connection) -> AnyResult<()> {
             [],
         )?;
     }
+    if !table_has_column(conn, "config", "income_smoothing_days")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN income_smoothing_days INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "opening_balance")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN opening_balance INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "budget_granularity")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN budget_granularity TEXT NOT NULL DEFAULT 'daily'",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "near_limit_ratio")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN near_limit_ratio INTEGER NOT NULL DEFAULT 80",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "record_near_limit")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN record_near_limit INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "currency_code")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN currency_code TEXT NOT NULL DEFAULT 'IDR'",
+            [],
+        )?;
+    }
+    if !table_has_column(conn, "config", "minor_units")? {
+        conn.execute(
+            "ALTER TABLE config ADD COLUMN minor_units INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
     conn.execute(
         "UPDATE config SET coach_mode = 'calm' WHERE coach_mode IS NULL OR coach_mode = ''",
         [],
@@ -134,6 +702,10 @@ fn ensure_transactions_columns(conn: &Connection) -> AnyResult<()> {
     if !table_has_column(conn, "transactions", "description")? {
         conn.execute("ALTER TABLE transactions ADD COLUMN description TEXT", [])?;
     }
+    // NULL berarti "uncategorized" supaya data lama tetap terbaca.
+    if !table_has_column(conn, "transactions", "category")? {
+        conn.execute("ALTER TABLE transactions ADD COLUMN category TEXT", [])?;
+    }
     conn.execute(
         "UPDATE transactions SET source = 'manual' WHERE source IS NULL OR source = ''",
         [],
@@ -160,6 +732,57 @@ fn ensure_coaching_memory_table(conn: &Connection) -> AnyResult<()> {
     Ok(())
 }
 
+fn ensure_no_spend_days_table(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS no_spend_days (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          date_local TEXT NOT NULL UNIQUE,
+          created_ts_utc INTEGER NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+fn ensure_savings_goals_table(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS savings_goals (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          name TEXT NOT NULL,
+          target_amount INTEGER NOT NULL,
+          saved_amount INTEGER NOT NULL DEFAULT 0,
+          created_ts_utc INTEGER NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+fn ensure_config_history_table(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS config_history (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          changed_ts_utc INTEGER NOT NULL,
+          change_source TEXT NOT NULL,
+          old_json TEXT NOT NULL,
+          new_json TEXT NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
+fn ensure_fixed_cost_action_log_table(conn: &Connection) -> AnyResult<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS fixed_cost_action_log (
+          id INTEGER PRIMARY KEY AUTOINCREMENT,
+          action_type TEXT NOT NULL,
+          fixed_cost_id INTEGER NOT NULL,
+          period_ym TEXT NOT NULL,
+          snapshot_json TEXT NOT NULL,
+          created_ts_utc INTEGER NOT NULL
+        );",
+    )?;
+    Ok(())
+}
+
 fn ensure_fixed_cost_columns(conn: &Connection) -> AnyResult<()> {
     if !table_has_column(conn, "fixed_costs", "paid_date_local")? {
         conn.execute(
@@ -173,6 +796,18 @@ fn ensure_fixed_cost_columns(conn: &Connection) -> AnyResult<()> {
     if !table_has_column(conn, "fixed_costs", "paid_tx_id")? {
         conn.execute("ALTER TABLE fixed_costs ADD COLUMN paid_tx_id INTEGER", [])?;
     }
+    // period_ym di fixed_cost_payments menyimpan kunci periode sesuai cadence
+    // ("2025-01" bulanan, "2025-W03" mingguan, "2025" tahunan).
+    if !table_has_column(conn, "fixed_costs", "cadence")? {
+        conn.execute(
+            "ALTER TABLE fixed_costs ADD COLUMN cadence TEXT NOT NULL DEFAULT 'monthly'",
+            [],
+        )?;
+    }
+    // Tanggal jatuh tempo (1-31) untuk biaya bulanan; NULL berarti tanpa jatuh tempo.
+    if !table_has_column(conn, "fixed_costs", "due_day")? {
+        conn.execute("ALTER TABLE fixed_costs ADD COLUMN due_day INTEGER", [])?;
+    }
     Ok(())
 }
 
@@ -189,6 +824,12 @@ fn ensure_fixed_cost_payments_columns(conn: &Connection) -> AnyResult<()> {
             [],
         )?;
     }
+    if !table_has_column(conn, "fixed_cost_payments", "is_partial")? {
+        conn.execute(
+            "ALTER TABLE fixed_cost_payments ADD COLUMN is_partial INTEGER NOT NULL DEFAULT 0",
+            [],
+        )?;
+    }
     conn.execute(
         "UPDATE fixed_cost_payments SET period_ym = substr(paid_date_local, 1, 7)
          WHERE (period_ym IS NULL OR period_ym = '') AND paid_date_local IS NOT NULL",
@@ -198,9 +839,21 @@ fn ensure_fixed_cost_payments_columns(conn: &Connection) -> AnyResult<()> {
 }
 
 fn ensure_fixed_cost_payments_index(conn: &Connection) -> AnyResult<()> {
+    // Cicilan (is_partial = 1) boleh banyak per periode; hanya pelunasan penuh yang unik.
+    let existing_sql: Option<String> = conn
+        .query_row(
+            "SELECT sql FROM sqlite_master
+             WHERE type = 'index' AND name = 'idx_fixed_cost_payments_period'",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    if existing_sql.is_some_and(|sql| !sql.contains("is_partial")) {
+        conn.execute("DROP INDEX idx_fixed_cost_payments_period", [])?;
+    }
     conn.execute(
         "CREATE UNIQUE INDEX IF NOT EXISTS idx_fixed_cost_payments_period
-         ON fixed_cost_payments(fixed_cost_id, period_ym)",
+         ON fixed_cost_payments(fixed_cost_id, period_ym) WHERE is_partial = 0",
         [],
     )?;
     Ok(())
@@ -221,6 +874,7 @@ fn ensure_fixed_cost_payments_nullable(conn: &Connection) -> AnyResult<()> {
           paid_date_local TEXT,
           paid_ts_utc INTEGER,
           tx_id INTEGER,
+          is_partial INTEGER NOT NULL DEFAULT 0,
           FOREIGN KEY(fixed_cost_id) REFERENCES fixed_costs(id)
         );
         INSERT INTO fixed_cost_payments_new (id, fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
@@ -285,6 +939,80 @@ fn column_is_not_null(conn: &Connection, table: &str, column: &str) -> AnyResult
 mod tests {
     use super::*;
 
+    fn user_version(conn: &Connection) -> i64 {
+        conn.query_row("PRAGMA user_version", [], |row| row.get(0))
+            .expect("user_version")
+    }
+
+    #[test]
+    fn migrates_v0_database_to_latest_version() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE TABLE transactions (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              ts_utc INTEGER NOT NULL,
+              date_local TEXT NOT NULL,
+              kind TEXT NOT NULL,
+              amount INTEGER NOT NULL
+            );
+            CREATE TABLE fixed_costs (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              name TEXT NOT NULL,
+              amount INTEGER NOT NULL,
+              is_active INTEGER NOT NULL DEFAULT 1,
+              paid_date_local TEXT,
+              paid_ts_utc INTEGER,
+              paid_tx_id INTEGER
+            );
+            INSERT INTO transactions (ts_utc, date_local, kind, amount)
+              VALUES (1, '2025-03-02', 'OUT', 250000);
+            INSERT INTO fixed_costs (name, amount, paid_date_local, paid_ts_utc, paid_tx_id)
+              VALUES ('Internet', 250000, '2025-03-02', 1, 1);",
+        )
+        .expect("create v0 schema");
+        assert_eq!(user_version(&conn), 0);
+
+        init_schema(&conn).expect("migrate");
+        assert_eq!(user_version(&conn), latest_schema_version());
+        assert!(table_has_column(&conn, "transactions", "category").expect("column"));
+        let (period_ym, source): (String, String) = conn
+            .query_row(
+                "SELECT p.period_ym, t.source FROM fixed_cost_payments p
+                 JOIN transactions t ON t.id = p.tx_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("legacy payment");
+        assert_eq!(period_ym, "2025-03");
+        assert_eq!(source, "fixed_cost");
+
+        init_schema(&conn).expect("re-run is a no-op");
+        assert_eq!(user_version(&conn), latest_schema_version());
+    }
+
+    #[test]
+    fn date_range_query_uses_date_kind_index() {
+        let conn = Connection::open_in_memory().expect("open");
+        init_schema(&conn).expect("init");
+        let plan = explain_query_plan(
+            &conn,
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE kind = 'OUT' AND date_local >= '2025-03-01' AND date_local <= '2025-03-07'",
+        )
+        .expect("plan");
+        assert!(plan
+            .iter()
+            .any(|row| row.detail.contains("idx_transactions_date_kind")));
+    }
+
+    #[test]
+    fn refuses_database_from_newer_app() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.pragma_update(None, "user_version", latest_schema_version() + 1)
+            .expect("set version");
+        assert!(init_schema(&conn).is_err());
+    }
+
     #[test]
     fn rebuild_fixed_cost_payments_when_paid_date_not_null() {
         let conn = Connection::open_in_memory().expect("open");
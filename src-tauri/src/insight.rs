@@ -1,9 +1,15 @@
-use chrono::{DateTime, Duration, Local, NaiveDate, Timelike};
-use rusqlite::{params, Connection, OptionalExtension};
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Timelike, Weekday};
+use rusqlite::{named_params, params, Connection, OptionalExtension};
 use serde::Serialize;
 use serde_json::json;
 
-use crate::{compute_pools_summary, PoolsSummary};
+use crate::clock::Clock;
+use crate::insight_copy::{self, Locale};
+use crate::recurring_income;
+use crate::{
+    compute_pools_summary_for_date, fetch_config, parse_date_local, period_key_for_date,
+    period_reference_date, Config, PoolsSummary,
+};
 
 #[derive(Serialize)]
 pub struct InsightDebugMeta {
@@ -23,9 +29,18 @@ pub struct CoachingInsight {
     pub debug_meta: Option<InsightDebugMeta>,
 }
 
+#[derive(Serialize)]
+pub struct InsightHistoryEntry {
+    pub date_local: String,
+    pub mode: String,
+    pub headline: String,
+    pub tags: Vec<String>,
+}
+
 struct TimeContext {
     now_local: DateTime<Local>,
     time_bucket: String,
+    weekday: Weekday,
     is_new_day_first_open: bool,
 }
 
@@ -33,6 +48,13 @@ struct CoachingMemoryEntry {
     date_local: String,
     mode: String,
     headline: String,
+    context: serde_json::Value,
+}
+
+impl CoachingMemoryEntry {
+    fn context_i64(&self, key: &str) -> Option<i64> {
+        self.context.get(key).and_then(|value| value.as_i64())
+    }
 }
 
 struct InsightInputs {
@@ -40,29 +62,142 @@ struct InsightInputs {
     tx_count_total: i64,
     tx_count_today: i64,
     total_out_7d: i64,
+    total_in_7d: i64,
     avg_out_7d: i64,
     days_with_tx_7d: i64,
     fixed_cost_unpaid_count_month: i64,
     fixed_cost_unpaid_amount_month: i64,
+    fixed_cost_overdue_count: i64,
+    no_spend_goal_today: bool,
+    near_limit_ratio: i64,
+    buffer_just_reached: bool,
 }
 
-fn rupiah(value: i64) -> String {
-    format!("Rp{}", value)
+pub(crate) const COACH_MODE_CALM: &str = "calm";
+pub(crate) const COACH_MODE_WATCHFUL: &str = "watchful";
+pub(crate) const COACH_MODE_STRICT: &str = "strict";
+
+// Mode strict memicu near_limit lebih awal dari near_limit_ratio bawaan.
+const STRICT_NEAR_LIMIT_RATIO: i64 = 60;
+
+// outpacing_income: pengeluaran 7 hari minimal 120% pemasukan, dan saldo belum dua kali
+// target penyangga.
+const OUTPACING_MARGIN_PERCENT: i64 = 120;
+const OUTPACING_BUFFER_MULTIPLE: i64 = 2;
+
+// Dipakai ringkasan dan aturan overspent_today/near_limit supaya UI dan coaching selalu sama.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SpendStatus {
+    Under,
+    Near,
+    Over,
 }
 
-fn today_local_string(now_local: DateTime<Local>) -> String {
-    now_local.format("%Y-%m-%d").to_string()
+// Mode strict menimpa rasio near-limit.
+pub(crate) fn effective_near_limit_ratio(near_limit_ratio: i64, coach_mode: &str) -> i64 {
+    if coach_mode == COACH_MODE_STRICT {
+        std::cmp::min(near_limit_ratio, STRICT_NEAR_LIMIT_RATIO)
+    } else {
+        near_limit_ratio
+    }
 }
 
-fn period_ym_from_date(date_local: &str) -> String {
-    date_local.get(0..7).unwrap_or(date_local).to_string()
+pub(crate) fn near_limit_threshold(recommended_spend_today: i64, ratio: i64) -> i64 {
+    recommended_spend_today * ratio / 100
 }
 
-fn date_range_last_7_days(today_local: &str) -> Result<(String, String), String> {
-    let today = NaiveDate::parse_from_str(today_local, "%Y-%m-%d")
-        .map_err(|err| format!("invalid date_local: {}", err))?;
+pub(crate) fn spend_status(
+    today_out: i64,
+    recommended_spend_today: i64,
+    near_limit_ratio: i64,
+) -> SpendStatus {
+    // Tanpa rekomendasi positif tidak ada batas yang bisa didekati atau dilewati.
+    if recommended_spend_today <= 0 {
+        SpendStatus::Under
+    } else if today_out > recommended_spend_today {
+        SpendStatus::Over
+    } else if today_out >= near_limit_threshold(recommended_spend_today, near_limit_ratio) {
+        SpendStatus::Near
+    } else {
+        SpendStatus::Under
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CoachMode {
+    Calm,
+    Watchful,
+    Strict,
+}
+
+impl CoachMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            CoachMode::Calm => COACH_MODE_CALM,
+            CoachMode::Watchful => COACH_MODE_WATCHFUL,
+            CoachMode::Strict => COACH_MODE_STRICT,
+        }
+    }
+}
+
+pub(crate) fn validate_coach_mode(coach_mode: &str) -> Result<(), String> {
+    match coach_mode {
+        COACH_MODE_CALM | COACH_MODE_WATCHFUL | COACH_MODE_STRICT => Ok(()),
+        _ => Err("coach_mode must be 'calm', 'watchful' or 'strict'".to_string()),
+    }
+}
+
+fn currency_symbol(currency_code: &str) -> String {
+    match currency_code {
+        "IDR" => "Rp".to_string(),
+        "USD" => "$".to_string(),
+        "EUR" => "€".to_string(),
+        "GBP" => "£".to_string(),
+        "JPY" => "¥".to_string(),
+        "SGD" => "S$".to_string(),
+        "MYR" => "RM".to_string(),
+        other => format!("{} ", other),
+    }
+}
+
+// Satu-satunya formatter uang untuk teks insight; `value` dalam satuan terkecil (minor_units).
+pub(crate) fn format_money(value: i64, config: &Config, locale: Locale) -> String {
+    let scale = 10_u64.pow(config.minor_units);
+    let abs = value.unsigned_abs();
+    let digits = (abs / scale).to_string();
+    let mut formatted = String::with_capacity(digits.len() + digits.len() / 3 + 8);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(locale.thousands_separator());
+        }
+        formatted.push(digit);
+    }
+    if config.minor_units > 0 {
+        formatted.push(locale.decimal_separator());
+        formatted.push_str(&format!(
+            "{:0width$}",
+            abs % scale,
+            width = config.minor_units as usize
+        ));
+    }
+    let sign = if value < 0 { "-" } else { "" };
+    format!(
+        "{}{}{}",
+        sign,
+        currency_symbol(&config.currency_code),
+        formatted
+    )
+}
+
+fn today_local_string(now_local: DateTime<Local>) -> String {
+    now_local.format("%Y-%m-%d").to_string()
+}
+
+// Jendela `days` hari yang berakhir di today (inklusif).
+fn date_range_last_days(today: NaiveDate, days: u32) -> Result<(String, String), String> {
     let start = today
-        .checked_sub_signed(Duration::days(6))
+        .checked_sub_signed(Duration::days(i64::from(days) - 1))
         .ok_or_else(|| "date underflow".to_string())?;
     Ok((
         start.format("%Y-%m-%d").to_string(),
@@ -70,19 +205,71 @@ fn date_range_last_7_days(today_local: &str) -> Result<(String, String), String>
     ))
 }
 
+#[derive(Serialize)]
+pub struct AverageSpend {
+    pub days: u32,
+    pub total_out: i64,
+    pub avg_per_day: i64,
+    pub avg_per_active_day: i64,
+    pub days_with_tx: i64,
+}
+
+// days_with_tx sama dengan angka yang dipakai aturan insight.
+pub fn average_spend(
+    conn: &Connection,
+    today: NaiveDate,
+    days: u32,
+) -> Result<AverageSpend, String> {
+    if days == 0 {
+        return Err("days must be > 0".to_string());
+    }
+    let (start, end) = date_range_last_days(today, days)?;
+    let total_out: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2",
+            params![start, end],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    let days_with_tx: i64 = conn
+        .query_row(
+            "SELECT COUNT(DISTINCT date_local) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local >= ?1 AND date_local <= ?2",
+            params![start, end],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(AverageSpend {
+        days,
+        total_out,
+        avg_per_day: total_out / i64::from(days),
+        avg_per_active_day: if days_with_tx > 0 {
+            total_out / days_with_tx
+        } else {
+            0
+        },
+        days_with_tx,
+    })
+}
+
 fn build_time_context(
+    config: &Config,
     now_local: DateTime<Local>,
     tx_count_today: i64,
     has_memory_today: bool,
 ) -> TimeContext {
-    let hour = now_local.hour();
-    let time_bucket = if (5..10).contains(&hour) {
+    let hour = i64::from(now_local.hour());
+    // Batas jam dijamin naik berurutan oleh set_time_buckets; sisanya malam.
+    let time_bucket = if (config.morning_start_hour..config.midday_start_hour).contains(&hour) {
         "morning"
-    } else if (10..15).contains(&hour) {
+    } else if (config.midday_start_hour..config.afternoon_start_hour).contains(&hour) {
         "midday"
-    } else if (15..18).contains(&hour) {
+    } else if (config.afternoon_start_hour..config.evening_start_hour).contains(&hour) {
         "afternoon"
-    } else if (18..22).contains(&hour) {
+    } else if (config.evening_start_hour..config.night_start_hour).contains(&hour) {
         "evening"
     } else {
         "night"
@@ -92,24 +279,50 @@ fn build_time_context(
     TimeContext {
         now_local,
         time_bucket,
+        weekday: now_local.weekday(),
         is_new_day_first_open: !has_memory_today && tx_count_today == 0,
     }
 }
 
+fn memory_from_row(row: &rusqlite::Row) -> rusqlite::Result<CoachingMemoryEntry> {
+    let context_json: Option<String> = row.get(3)?;
+    Ok(CoachingMemoryEntry {
+        date_local: row.get(0)?,
+        mode: row.get(1)?,
+        headline: row.get(2)?,
+        context: context_json
+            .and_then(|raw| serde_json::from_str(&raw).ok())
+            .unwrap_or(serde_json::Value::Null),
+    })
+}
+
+// Penyangga baru tercapai jika memori terakhir masih di bawah target.
+// Memori lama tanpa target_penyangga dibandingkan dengan target saat ini.
+fn buffer_just_reached(summary: &PoolsSummary, last_memory: Option<&CoachingMemoryEntry>) -> bool {
+    if summary.target_penyangga <= 0 || summary.net_balance < summary.target_penyangga {
+        return false;
+    }
+    let Some(memory) = last_memory else {
+        return false;
+    };
+    let Some(previous_balance) = memory.context_i64("net_balance") else {
+        return false;
+    };
+    let previous_target = memory
+        .context_i64("target_penyangga")
+        .unwrap_or(summary.target_penyangga);
+    previous_balance < previous_target
+}
+
 fn fetch_last_memory(conn: &Connection) -> Result<Option<CoachingMemoryEntry>, String> {
     conn.query_row(
-        "SELECT date_local, mode, headline
+        "SELECT date_local, mode, headline, context_json
          FROM coaching_memory
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
          ORDER BY ts_utc DESC
          LIMIT 1",
         [],
-        |row| {
-            Ok(CoachingMemoryEntry {
-                date_local: row.get(0)?,
-                mode: row.get(1)?,
-                headline: row.get(2)?,
-            })
-        },
+        memory_from_row,
     )
     .optional()
     .map_err(|err| err.to_string())
@@ -120,25 +333,38 @@ fn fetch_memory_for_date(
     date_local: &str,
 ) -> Result<Option<CoachingMemoryEntry>, String> {
     conn.query_row(
-        "SELECT date_local, mode, headline
+        "SELECT date_local, mode, headline, context_json
          FROM coaching_memory
-         WHERE date_local = ?1
+         WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local = ?1
          ORDER BY ts_utc DESC
          LIMIT 1",
         [date_local],
-        |row| {
-            Ok(CoachingMemoryEntry {
-                date_local: row.get(0)?,
-                mode: row.get(1)?,
-                headline: row.get(2)?,
-            })
-        },
+        memory_from_row,
     )
     .optional()
     .map_err(|err| err.to_string())
 }
 
+fn mode_transition_line(locale: Locale, previous_mode: &str, tone: &str) -> Option<&'static str> {
+    if previous_mode == "alert" && tone == "calm" {
+        return Some(insight_copy::text(locale, "continuity.alert_to_calm"));
+    }
+    if previous_mode == "calm" && tone == "alert" {
+        return Some(insight_copy::text(locale, "continuity.calm_to_alert"));
+    }
+    None
+}
+
+fn mode_line(mode: &str) -> &'static str {
+    if mode == "alert" {
+        "Hari yang ketat, dijaga pelan-pelan."
+    } else {
+        "Hari yang tenang."
+    }
+}
+
 fn build_continuity_line(
+    locale: Locale,
     time_context: &TimeContext,
     last_memory: Option<&CoachingMemoryEntry>,
     tone: &str,
@@ -146,32 +372,26 @@ fn build_continuity_line(
     if let Some(memory) = last_memory {
         let today_local = today_local_string(time_context.now_local);
         if memory.date_local != today_local {
-            if memory.mode == "alert" && tone == "calm" {
-                return Some(
-                    "Kemarin sempat ketat, hari ini kita mulai lagi pelan-pelan.".to_string(),
-                );
-            }
-            if memory.mode == "calm" && tone == "alert" {
-                return Some(
-                    "Hari ini lebih ketat dari kemarin. Kita jaga pelan-pelan.".to_string(),
-                );
+            if let Some(line) = mode_transition_line(locale, &memory.mode, tone) {
+                return Some(line.to_string());
             }
         }
     }
 
     if time_context.is_new_day_first_open {
-        let line = match time_context.time_bucket.as_str() {
-            "morning" => "Pagi ini kita mulai pelan-pelan.",
-            "night" => "Hari ini hampir selesai, besok kita mulai lagi.",
-            _ => "Hari ini kita mulai pelan-pelan.",
+        let key = match time_context.time_bucket.as_str() {
+            "morning" => "continuity.first_open.morning",
+            "night" => "continuity.first_open.night",
+            _ => "continuity.first_open.default",
         };
-        return Some(line.to_string());
+        return Some(insight_copy::text(locale, key).to_string());
     }
 
     None
 }
 
 fn build_memory_reflection(
+    locale: Locale,
     last_memory: Option<&CoachingMemoryEntry>,
     today_local: &str,
 ) -> Option<String> {
@@ -179,404 +399,1219 @@ fn build_memory_reflection(
     if memory.date_local == today_local {
         return None;
     }
-    Some(format!("Catatan terakhir: {}.", memory.headline))
+    Some(insight_copy::fill(
+        insight_copy::text(locale, "memory.reflection"),
+        std::slice::from_ref(&memory.headline),
+    ))
 }
 
-pub fn compute_coaching_insight(conn: &Connection) -> Result<CoachingInsight, String> {
-    compute_coaching_insight_with_time(conn, Local::now())
+// Lunas berarti pembayaran penuh atau cicilan yang totalnya sudah mencapai nominal template.
+pub(crate) fn fetch_unpaid_fixed_cost_totals(
+    conn: &Connection,
+    date_local: &str,
+) -> Result<(i64, i64), String> {
+    let (count, amount, _) = fetch_unpaid_fixed_cost_stats(conn, date_local)?;
+    Ok((count, amount))
+}
+
+// Satu baris per biaya tetap aktif yang belum lunas; dipakai insight dan
+// get_unpaid_fixed_costs supaya angkanya selalu sama.
+const UNPAID_FIXED_COSTS_SQL: &str = "SELECT * FROM (
+           SELECT fc.id, fc.name, fc.cadence, fc.amount, fc.due_day,
+                  (fc.cadence = 'monthly' AND fc.due_day IS NOT NULL AND fc.due_day < :day) AS overdue,
+                  (SELECT COALESCE(SUM(t.amount), 0) FROM fixed_cost_payments p
+                     JOIN transactions t ON t.id = p.tx_id
+                    WHERE p.fixed_cost_id = fc.id AND p.period_ym = fc.period_key) AS paid_total,
+                  EXISTS (
+                    SELECT 1 FROM fixed_cost_payments p
+                    WHERE p.fixed_cost_id = fc.id AND p.period_ym = fc.period_key
+                      AND p.is_partial = 0 AND p.tx_id IS NOT NULL
+                  ) AS fully_paid,
+                  EXISTS (
+                    SELECT 1 FROM fixed_cost_payments p
+                    WHERE p.fixed_cost_id = fc.id AND p.period_ym = fc.period_key AND p.is_skipped = 1
+                  ) AS skipped
+           FROM (
+             SELECT *, CASE cadence
+                 WHEN 'weekly' THEN :week_key
+                 WHEN 'yearly' THEN :year_key
+                 ELSE :month_key
+               END AS period_key
+             FROM fixed_costs
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND is_active = 1
+           ) fc
+         )
+         WHERE fully_paid = 0 AND skipped = 0 AND paid_total < amount";
+
+#[derive(Serialize)]
+pub struct UnpaidFixedCost {
+    pub id: i64,
+    pub name: String,
+    pub cadence: String,
+    pub amount: i64,
+    pub remaining: i64,
+    pub due_day: Option<u32>,
+    pub is_overdue: bool,
+}
+
+#[derive(Serialize)]
+pub struct UnpaidFixedCosts {
+    pub period_ym: String,
+    pub items: Vec<UnpaidFixedCost>,
+    pub total_remaining: i64,
+}
+
+fn query_unpaid_fixed_costs<T>(
+    conn: &Connection,
+    date: NaiveDate,
+    outer_select: &str,
+    map: impl FnMut(&rusqlite::Row) -> rusqlite::Result<T>,
+) -> Result<Vec<T>, String> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} FROM ({})",
+            outer_select, UNPAID_FIXED_COSTS_SQL
+        ))
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(
+            named_params! {
+                ":month_key": period_key_for_date("monthly", date),
+                ":week_key": period_key_for_date("weekly", date),
+                ":year_key": period_key_for_date("yearly", date),
+                ":day": date.day(),
+            },
+            map,
+        )
+        .map_err(|err| err.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())
+}
+
+fn fetch_unpaid_fixed_cost_stats(
+    conn: &Connection,
+    date_local: &str,
+) -> Result<(i64, i64, i64), String> {
+    let date = NaiveDate::parse_from_str(date_local, "%Y-%m-%d")
+        .map_err(|err| format!("invalid date_local: {}", err))?;
+    query_unpaid_fixed_costs(
+        conn,
+        date,
+        "SELECT COUNT(*), COALESCE(SUM(amount - paid_total), 0), COALESCE(SUM(overdue), 0)",
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    )?
+    .pop()
+    .ok_or_else(|| "unpaid fixed cost query returned no rows".to_string())
+}
+
+#[derive(Serialize)]
+pub struct FixedCostsTotal {
+    pub active_count: i64,
+    pub active_total: i64,
+    pub paid_this_month_total: i64,
+    pub unpaid_this_month_total: i64,
+}
+
+// Bagian unpaid memakai query insight yang sama, jadi paid + unpaid = active_total.
+pub fn fixed_costs_total(conn: &Connection, today: NaiveDate) -> Result<FixedCostsTotal, String> {
+    let (active_count, active_total): (i64, i64) = conn
+        .query_row(
+            "SELECT COUNT(*), COALESCE(SUM(amount), 0) FROM fixed_costs
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND is_active = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .map_err(|err| err.to_string())?;
+    let (_, unpaid_this_month_total, _) =
+        fetch_unpaid_fixed_cost_stats(conn, &today.format("%Y-%m-%d").to_string())?;
+    Ok(FixedCostsTotal {
+        active_count,
+        active_total,
+        paid_this_month_total: active_total - unpaid_this_month_total,
+        unpaid_this_month_total,
+    })
+}
+
+#[derive(Serialize)]
+pub struct MonthEndProjection {
+    // Saldo hari ini, termasuk transaksi hari ini.
+    pub current_net: i64,
+    // Pemasukan rutin aktif yang belum diterima bulan ini; pemasukan sekali tidak ditebak.
+    pub expected_remaining_income: i64,
+    // Sisa biaya tetap aktif untuk periode berjalan (sama dengan insight).
+    pub expected_remaining_fixed_costs: i64,
+    // current_net + pemasukan - biaya tetap; belanja harian tidak dikurangkan.
+    pub projected_month_end_net: i64,
+}
+
+pub fn project_month_end(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<MonthEndProjection, String> {
+    let current_net = compute_pools_summary_for_date(conn, today)?.net_balance;
+    let expected_remaining_income = recurring_income::expected_remaining_this_month(conn, today)?;
+    let (_, expected_remaining_fixed_costs, _) =
+        fetch_unpaid_fixed_cost_stats(conn, &today.format("%Y-%m-%d").to_string())?;
+    Ok(MonthEndProjection {
+        current_net,
+        expected_remaining_income,
+        expected_remaining_fixed_costs,
+        projected_month_end_net: current_net + expected_remaining_income
+            - expected_remaining_fixed_costs,
+    })
+}
+
+// Tanggal acuan periode mengikuti period_reference_date, sama seperti insight coaching.
+pub fn unpaid_fixed_costs(
+    conn: &Connection,
+    period_ym: Option<&str>,
+    today: NaiveDate,
+) -> Result<UnpaidFixedCosts, String> {
+    let date = match period_ym {
+        None => today,
+        Some(period_ym) => period_reference_date(period_ym, today)?,
+    };
+    let mut items = query_unpaid_fixed_costs(
+        conn,
+        date,
+        "SELECT id, name, cadence, amount, amount - paid_total, due_day, overdue",
+        |row| {
+            Ok(UnpaidFixedCost {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                cadence: row.get(2)?,
+                amount: row.get(3)?,
+                remaining: row.get(4)?,
+                due_day: row.get(5)?,
+                is_overdue: row.get::<_, i64>(6)? != 0,
+            })
+        },
+    )?;
+    items.sort_by_key(|item| item.id);
+    Ok(UnpaidFixedCosts {
+        period_ym: period_key_for_date("monthly", date),
+        total_remaining: items.iter().map(|item| item.remaining).sum(),
+        items,
+    })
+}
+
+pub fn export_coaching_log(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<String, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, mode, headline, context_json
+             FROM coaching_memory
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local >= ?1 AND date_local <= ?2
+             ORDER BY ts_utc ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![start_date, end_date], memory_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut lines = Vec::new();
+    let mut previous_mode: Option<String> = None;
+    for row in rows {
+        let entry = row.map_err(|err| err.to_string())?;
+        let sentence = previous_mode
+            .as_deref()
+            .and_then(|previous| mode_transition_line(Locale::Id, previous, &entry.mode))
+            .unwrap_or_else(|| mode_line(&entry.mode));
+        lines.push(format!(
+            "{} — {} {}",
+            entry.date_local, entry.headline, sentence
+        ));
+        previous_mode = Some(entry.mode);
+    }
+
+    Ok(lines.join("\n"))
+}
+
+// Satu heading ## per tanggal; mengembalikan dokumen dan jumlah entrinya.
+pub fn insights_markdown(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<(String, usize), String> {
+    parse_date_local(start_date)?;
+    parse_date_local(end_date)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, mode, headline
+             FROM coaching_memory
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND date_local >= ?1 AND date_local <= ?2
+             ORDER BY date_local ASC, ts_utc ASC, id ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut document = format!("# Jurnal coaching {} s/d {}\n", start_date, end_date);
+    let mut count = 0;
+    let mut current_date: Option<String> = None;
+    for row in rows {
+        let (date_local, mode, headline) = row.map_err(|err| err.to_string())?;
+        if current_date.as_deref() != Some(date_local.as_str()) {
+            document.push_str(&format!("\n## {}\n\n", date_local));
+            current_date = Some(date_local);
+        }
+        document.push_str(&format!("- {} _(mode: {})_\n", headline, mode));
+        count += 1;
+    }
+    if count == 0 {
+        document.push_str("\n_Tidak ada catatan coaching pada rentang ini._\n");
+    }
+    Ok((document, count))
+}
+
+pub fn insight_history(conn: &Connection, limit: u32) -> Result<Vec<InsightHistoryEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, mode, headline, tags
+             FROM coaching_memory
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY ts_utc DESC, id DESC
+             LIMIT ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([limit], |row| {
+            let tags: String = row.get(3)?;
+            Ok(InsightHistoryEntry {
+                date_local: row.get(0)?,
+                mode: row.get(1)?,
+                headline: row.get(2)?,
+                // Dipisah koma oleh build_memory_tags.
+                tags: tags
+                    .split(',')
+                    .filter(|tag| !tag.is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .map_err(|err| err.to_string())?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())
+}
+
+pub fn clear_coaching_memory(conn: &Connection) -> Result<usize, String> {
+    conn.execute(
+        "DELETE FROM coaching_memory WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        [],
+    )
+    .map_err(|err| err.to_string())
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verbosity {
+    Short,
+    Normal,
+    Detailed,
+}
+
+impl Verbosity {
+    // None tetap memakai daftar poin biasa.
+    pub fn parse(verbosity: Option<&str>) -> Result<Verbosity, String> {
+        match verbosity
+            .map(|value| value.trim().to_lowercase())
+            .as_deref()
+        {
+            None | Some("") | Some("normal") => Ok(Verbosity::Normal),
+            Some("short") => Ok(Verbosity::Short),
+            Some("detailed") => Ok(Verbosity::Detailed),
+            Some(other) => Err(format!(
+                "unsupported verbosity '{}': use 'short', 'normal' or 'detailed'",
+                other
+            )),
+        }
+    }
+}
+
+pub fn compute_coaching_insight(
+    conn: &Connection,
+    clock: &dyn Clock,
+    locale: Locale,
+    verbosity: Verbosity,
+) -> Result<CoachingInsight, String> {
+    compute_coaching_insight_with_time(conn, clock.now_local(), locale, verbosity)
+}
+
+// Sama dengan compute_coaching_insight, tapi tanpa menulis ke coaching_memory.
+pub fn preview_coaching_insight(
+    conn: &Connection,
+    clock: &dyn Clock,
+    locale: Locale,
+) -> Result<CoachingInsight, String> {
+    Ok(evaluate_insight(conn, clock.now_local(), locale)?.insight)
+}
+
+// Verbosity hanya mengubah tampilan bullet; memori tetap dicatat dari insight yang sama.
+fn apply_verbosity(
+    insight: &mut CoachingInsight,
+    verbosity: Verbosity,
+    config: &Config,
+    locale: Locale,
+    avg_out_7d: i64,
+) {
+    match verbosity {
+        Verbosity::Normal => {}
+        Verbosity::Short => insight.bullets.truncate(1),
+        Verbosity::Detailed => {
+            let extra = insight_copy::fill(
+                insight_copy::text(locale, "common.avg_out_7d"),
+                &[format_money(avg_out_7d, config, locale)],
+            );
+            if !insight.bullets.contains(&extra) {
+                insight.bullets.push(extra);
+            }
+        }
+    }
 }
 
 fn compute_coaching_insight_with_time(
     conn: &Connection,
     now_local: DateTime<Local>,
+    locale: Locale,
+    verbosity: Verbosity,
 ) -> Result<CoachingInsight, String> {
+    let EvaluatedInsight {
+        mut insight,
+        config,
+        inputs,
+        coach_mode,
+        last_memory,
+        today_local,
+    } = evaluate_insight(conn, now_local, locale)?;
+    maybe_record_memory(
+        conn,
+        &config,
+        &inputs,
+        &insight,
+        coach_mode.as_str(),
+        last_memory.as_ref(),
+        &today_local,
+    )?;
+    apply_verbosity(&mut insight, verbosity, &config, locale, inputs.avg_out_7d);
+
+    Ok(insight)
+}
+
+// Hasil pemilihan aturan beserta bahan yang dibutuhkan maybe_record_memory.
+struct EvaluatedInsight {
+    insight: CoachingInsight,
+    config: Config,
+    inputs: InsightInputs,
+    coach_mode: CoachMode,
+    last_memory: Option<CoachingMemoryEntry>,
+    today_local: String,
+}
+
+// Membaca memori, tidak pernah menulis.
+fn evaluate_insight(
+    conn: &Connection,
+    now_local: DateTime<Local>,
+    locale: Locale,
+) -> Result<EvaluatedInsight, String> {
     let today_local = today_local_string(now_local);
-    let summary = compute_pools_summary(conn)?;
+    let config = fetch_config(conn)?;
+    let summary = compute_pools_summary_for_date(conn, now_local.date_naive())?;
     let tx_count_total: i64 = conn
-        .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+        .query_row(
+            "SELECT COUNT(*) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+            [],
+            |row| row.get(0),
+        )
         .map_err(|err| err.to_string())?;
     let tx_count_today: i64 = conn
         .query_row(
-            "SELECT COUNT(*) FROM transactions WHERE date_local = ?1",
+            "SELECT COUNT(*) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local = ?1",
             [today_local.as_str()],
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
 
-    let (start_7d, end_7d) = date_range_last_7_days(&today_local)?;
-    let total_out_7d: i64 = conn
+    let AverageSpend {
+        total_out: total_out_7d,
+        avg_per_day: avg_out_7d,
+        days_with_tx: days_with_tx_7d,
+        ..
+    } = average_spend(conn, now_local.date_naive(), 7)?;
+    let (start_7d, end_7d) = date_range_last_days(now_local.date_naive(), 7)?;
+    let total_in_7d: i64 = conn
         .query_row(
             "SELECT COALESCE(SUM(amount), 0) FROM transactions
-             WHERE kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2",
-            params![start_7d, end_7d],
-            |row| row.get(0),
-        )
-        .map_err(|err| err.to_string())?;
-    let avg_out_7d = total_out_7d / 7;
-    let days_with_tx_7d: i64 = conn
-        .query_row(
-            "SELECT COUNT(DISTINCT date_local) FROM transactions
-             WHERE date_local >= ?1 AND date_local <= ?2",
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'IN' AND date_local >= ?1 AND date_local <= ?2",
             params![start_7d, end_7d],
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
 
-    let period_ym = period_ym_from_date(&today_local);
-    let fixed_cost_unpaid_count_month: i64 = conn
-        .query_row(
-            "SELECT COUNT(*) FROM fixed_costs fc
-             LEFT JOIN fixed_cost_payments p
-               ON p.fixed_cost_id = fc.id AND p.period_ym = ?1
-             WHERE fc.is_active = 1 AND p.tx_id IS NULL",
-            [period_ym.as_str()],
-            |row| row.get(0),
-        )
-        .map_err(|err| err.to_string())?;
-    let fixed_cost_unpaid_amount_month: i64 = conn
+    let (fixed_cost_unpaid_count_month, fixed_cost_unpaid_amount_month, fixed_cost_overdue_count) =
+        fetch_unpaid_fixed_cost_stats(conn, &today_local)?;
+
+    let no_spend_goal_today: i64 = conn
         .query_row(
-            "SELECT COALESCE(SUM(fc.amount), 0) FROM fixed_costs fc
-             LEFT JOIN fixed_cost_payments p
-               ON p.fixed_cost_id = fc.id AND p.period_ym = ?1
-             WHERE fc.is_active = 1 AND p.tx_id IS NULL",
-            [period_ym.as_str()],
+            "SELECT COUNT(*) FROM no_spend_days
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local = ?1",
+            [today_local.as_str()],
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
 
-    let auto_mode = if summary.net_balance < summary.target_penyangga {
-        "watchful".to_string()
+    // 'calm'/'watchful' tetap otomatis mengikuti penyangga; hanya 'strict' yang dipaksa.
+    let coach_mode = if config.coach_mode == COACH_MODE_STRICT {
+        CoachMode::Strict
+    } else if summary.net_balance < summary.target_penyangga {
+        CoachMode::Watchful
+    } else {
+        CoachMode::Calm
+    };
+    // Perekaman memori dimatikan: memori lama juga tidak dibaca.
+    let (last_memory, has_memory_today) = if config.memory_recording_enabled {
+        (
+            fetch_last_memory(conn)?,
+            fetch_memory_for_date(conn, &today_local)?.is_some(),
+        )
     } else {
-        "calm".to_string()
+        (None, false)
     };
-    let last_memory = fetch_last_memory(conn)?;
-    let has_memory_today = fetch_memory_for_date(conn, &today_local)?.is_some();
-    let time_context = build_time_context(now_local, tx_count_today, has_memory_today);
+    let time_context = build_time_context(&config, now_local, tx_count_today, has_memory_today);
+    let buffer_just_reached = buffer_just_reached(&summary, last_memory.as_ref());
 
     let inputs = InsightInputs {
         summary,
         tx_count_total,
         tx_count_today,
         total_out_7d,
+        total_in_7d,
         avg_out_7d,
         days_with_tx_7d,
         fixed_cost_unpaid_count_month,
         fixed_cost_unpaid_amount_month,
+        fixed_cost_overdue_count,
+        no_spend_goal_today: no_spend_goal_today > 0,
+        near_limit_ratio: config.near_limit_ratio,
+        buffer_just_reached,
     };
-    let mut insight = select_insight_rule(&inputs, &auto_mode, &time_context);
-    insight.continuity_line =
-        build_continuity_line(&time_context, last_memory.as_ref(), &insight.tone);
-    insight.memory_reflection = build_memory_reflection(last_memory.as_ref(), &today_local);
-    insight.coach_mode = auto_mode.clone();
+    let mut insight = select_insight_rule(&inputs, &config, coach_mode, locale, &time_context);
+    if config.memory_recording_enabled {
+        insight.continuity_line =
+            build_continuity_line(locale, &time_context, last_memory.as_ref(), &insight.tone);
+        insight.memory_reflection =
+            build_memory_reflection(locale, last_memory.as_ref(), &today_local);
+    }
+    insight.coach_mode = coach_mode.as_str().to_string();
+
+    Ok(EvaluatedInsight {
+        insight,
+        config,
+        inputs,
+        coach_mode,
+        last_memory,
+        today_local,
+    })
+}
 
-    maybe_record_memory(
-        conn,
-        &inputs,
-        &insight,
-        &auto_mode,
-        last_memory.as_ref(),
-        &today_local,
-    )?;
+struct RuleContext<'a> {
+    inputs: &'a InsightInputs,
+    config: &'a Config,
+    mode: CoachMode,
+    locale: Locale,
+    time_context: &'a TimeContext,
+}
 
-    Ok(insight)
+impl RuleContext<'_> {
+    fn copy(&self, key: &str, args: &[String]) -> String {
+        insight_copy::fill(insight_copy::text(self.locale, key), args)
+    }
+
+    fn money(&self, value: i64) -> String {
+        format_money(value, self.config, self.locale)
+    }
+
+    // Varian next_step per mode disimpan sebagai `<prefix>.<mode>`.
+    fn mode_key(&self, prefix: &str) -> String {
+        format!("{}.{}", prefix, self.mode.as_str())
+    }
+}
+
+struct RuleOutput {
+    status_title: String,
+    bullets: Vec<String>,
+    next_step: String,
+    key_numbers: Vec<i64>,
+}
+
+// Tone diambil dari katalog, bukan dari fungsi aturan, supaya list_insight_rules
+// selalu sama dengan yang benar-benar ditampilkan.
+struct InsightRule {
+    rule_id: &'static str,
+    title: &'static str,
+    description: &'static str,
+    tone: &'static str,
+    depends_on: &'static [&'static str],
+    evaluate: fn(&RuleContext) -> Option<RuleOutput>,
+}
+
+#[derive(Serialize)]
+pub struct InsightRuleInfo {
+    pub rule_id: String,
+    pub title: String,
+    pub description: String,
+    pub tone: String,
+    pub depends_on: Vec<String>,
+    pub default_priority: i64,
+}
+
+// Urutan di sini adalah prioritas evaluasi; aturan pertama yang cocok dipakai.
+// "normal" selalu cocok sehingga harus tetap di posisi terakhir.
+const INSIGHT_RULES: &[InsightRule] = &[
+    InsightRule {
+        rule_id: "frozen",
+        title: "Belanja dibekukan",
+        description: "Mode beku aktif; rekomendasi belanja dipaksa Rp0 apa pun kondisi penyangga.",
+        tone: "alert",
+        depends_on: &["spending_frozen"],
+        evaluate: rule_frozen,
+    },
+    InsightRule {
+        rule_id: "onboarding",
+        title: "Awal pencatatan",
+        description: "Total transaksi masih di bawah 5; fokus membangun ritme mencatat.",
+        tone: "calm",
+        depends_on: &["tx_count_total < 5"],
+        evaluate: rule_onboarding,
+    },
+    InsightRule {
+        rule_id: "deficit",
+        title: "Saldo minus",
+        description: "Saldo bersih di bawah nol; rekomendasi belanja ditahan di Rp0.",
+        tone: "alert",
+        depends_on: &["net_balance < 0", "opening_balance"],
+        evaluate: rule_deficit,
+    },
+    InsightRule {
+        rule_id: "overspent_today",
+        title: "Melewati batas hari ini",
+        description: "Pengeluaran hari ini sudah melebihi rekomendasi harian.",
+        tone: "alert",
+        depends_on: &[
+            "today_out > recommended_spend_today",
+            "min_floor",
+            "max_ceil",
+            "resilience_days",
+            "budget_granularity",
+        ],
+        evaluate: rule_overspent_today,
+    },
+    InsightRule {
+        rule_id: "buffer_reached",
+        title: "Penyangga tercapai",
+        description: "Saldo bersih baru saja menembus target penyangga sejak memori terakhir.",
+        tone: "calm",
+        depends_on: &[
+            "net_balance >= target_penyangga",
+            "coaching_memory (net_balance < target_penyangga)",
+            "resilience_days",
+        ],
+        evaluate: rule_buffer_reached,
+    },
+    InsightRule {
+        rule_id: "no_spend_day",
+        title: "Hari tanpa belanja",
+        description: "Hari ini ditandai tanpa belanja dan belum ada pengeluaran.",
+        tone: "calm",
+        depends_on: &["no_spend_days (today)", "today_out == 0"],
+        evaluate: rule_no_spend_day,
+    },
+    InsightRule {
+        rule_id: "weekly_recap",
+        title: "Rekap mingguan",
+        description: "Pembukaan pertama di hari awal minggu; rangkuman pengeluaran 7 hari terakhir.",
+        tone: "calm",
+        depends_on: &[
+            "first open on week_start_day",
+            "total_out_7d",
+            "avg_out_7d",
+            "days_with_tx_7d",
+        ],
+        evaluate: rule_weekly_recap,
+    },
+    InsightRule {
+        rule_id: "no_tx_today",
+        title: "Belum ada catatan",
+        description: "Belum ada transaksi tercatat hari ini.",
+        tone: "calm",
+        depends_on: &["tx_count_today == 0"],
+        evaluate: rule_no_tx_today,
+    },
+    InsightRule {
+        rule_id: "fixed_cost_unpaid",
+        title: "Biaya tetap belum lunas",
+        description: "Masih ada biaya tetap aktif yang belum dibayar di periode ini.",
+        tone: "calm",
+        depends_on: &["fixed_costs unpaid this period", "due_day"],
+        evaluate: rule_fixed_cost_unpaid,
+    },
+    InsightRule {
+        rule_id: "low_buffer",
+        title: "Penyangga belum aman",
+        description:
+            "Saldo di bawah target penyangga dan ketahanan low_buffer_days hari (bawaan 7) atau kurang.",
+        tone: "alert",
+        depends_on: &[
+            "net_balance < target_penyangga",
+            "hari_ketahanan_stop_pemasukan <= low_buffer_days",
+            "low_buffer_days",
+            "min_floor",
+            "resilience_days",
+        ],
+        evaluate: rule_low_buffer,
+    },
+    InsightRule {
+        rule_id: "outpacing_income",
+        title: "Pengeluaran melampaui pemasukan",
+        description:
+            "Pengeluaran 7 hari terakhir minimal 120% pemasukan dan saldo belum dua kali target penyangga.",
+        tone: "alert",
+        depends_on: &[
+            "total_out_7d > total_in_7d",
+            "net_balance < 2 * target_penyangga",
+        ],
+        evaluate: rule_outpacing_income,
+    },
+    InsightRule {
+        rule_id: "near_limit",
+        title: "Hampir menyentuh batas",
+        description:
+            "Pengeluaran hari ini mencapai near_limit_ratio (bawaan 80%, mode strict 60%) dari rekomendasi.",
+        tone: "calm",
+        depends_on: &[
+            "today_out >= recommended_spend_today * near_limit_ratio",
+            "near_limit_ratio",
+            "coach_mode",
+        ],
+        evaluate: rule_near_limit,
+    },
+    InsightRule {
+        rule_id: "consistency_praise",
+        title: "Konsisten mencatat",
+        description: "Ada transaksi di setidaknya 6 dari 7 hari terakhir.",
+        tone: "calm",
+        depends_on: &["days_with_tx_7d >= 6"],
+        evaluate: rule_consistency_praise,
+    },
+    InsightRule {
+        rule_id: "normal",
+        title: "Kondisi stabil",
+        description: "Tidak ada aturan lain yang cocok; ringkasan kondisi harian.",
+        tone: "calm",
+        depends_on: &[],
+        evaluate: rule_normal,
+    },
+];
+
+pub fn list_insight_rules() -> Vec<InsightRuleInfo> {
+    INSIGHT_RULES
+        .iter()
+        .zip(1..)
+        .map(|(rule, priority)| InsightRuleInfo {
+            rule_id: rule.rule_id.to_string(),
+            title: rule.title.to_string(),
+            description: rule.description.to_string(),
+            tone: rule.tone.to_string(),
+            depends_on: rule
+                .depends_on
+                .iter()
+                .map(|item| item.to_string())
+                .collect(),
+            default_priority: priority,
+        })
+        .collect()
 }
 
 fn select_insight_rule(
     inputs: &InsightInputs,
-    coach_mode: &str,
+    config: &Config,
+    coach_mode: CoachMode,
+    locale: Locale,
     time_context: &TimeContext,
 ) -> CoachingInsight {
+    let ctx = RuleContext {
+        inputs,
+        config,
+        mode: coach_mode,
+        locale,
+        time_context,
+    };
+
+    let (rule_id, rule_tone, output) = INSIGHT_RULES
+        .iter()
+        .find_map(|rule| (rule.evaluate)(&ctx).map(|output| (rule.rule_id, rule.tone, output)))
+        .unwrap_or_else(|| ("normal", "calm", normal_output(&ctx)));
+
     let summary = &inputs.summary;
-    let watchful = coach_mode == "watchful";
+    let tone = if coach_mode == CoachMode::Strict
+        && summary.today_out > 0
+        && summary.net_balance < summary.target_penyangga
+    {
+        "alert"
+    } else {
+        rule_tone
+    };
 
-    if inputs.tx_count_total < 5 {
-        return CoachingInsight {
-            status_title: format!(
-                "Baru {} transaksi, pelan-pelan bangun ritme.",
-                inputs.tx_count_total
-            ),
-            bullets: vec![
-                format!(
-                    "Total catatan saat ini {} transaksi.",
-                    inputs.tx_count_total
-                ),
-                format!(
-                    "Rekomendasi hari ini {}.",
-                    rupiah(summary.recommended_spend_today)
-                ),
-            ],
-            next_step: "Langkah kecil: catat 1 transaksi hari ini agar ritme terasa.".to_string(),
-            tone: "calm".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "onboarding".to_string(),
-                key_numbers: vec![inputs.tx_count_total, summary.recommended_spend_today],
-            }),
-        };
+    CoachingInsight {
+        status_title: output.status_title,
+        bullets: output.bullets,
+        next_step: output.next_step,
+        tone: tone.to_string(),
+        coach_mode: coach_mode.as_str().to_string(),
+        continuity_line: None,
+        memory_reflection: None,
+        debug_meta: Some(InsightDebugMeta {
+            rule_id: rule_id.to_string(),
+            key_numbers: output.key_numbers,
+        }),
     }
+}
 
-    if summary.recommended_spend_today > 0 && summary.today_out > summary.recommended_spend_today {
-        let next_step = if watchful {
-            "Jika bisa, hentikan pengeluaran tambahan sampai besok.".to_string()
-        } else {
-            format!(
-                "Hari ini aman kalau tahan belanja tambahan; besok reset dengan target {}.",
-                rupiah(summary.recommended_spend_today)
-            )
-        };
-        return CoachingInsight {
-            status_title: format!(
-                "Hari ini melewati batas {}.",
-                rupiah(summary.recommended_spend_today)
+fn rule_onboarding(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if inputs.tx_count_total >= 5 {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "onboarding.status_title",
+            &[inputs.tx_count_total.to_string()],
+        ),
+        bullets: vec![
+            ctx.copy("onboarding.tx_total", &[inputs.tx_count_total.to_string()]),
+            ctx.copy(
+                "common.recommended_today",
+                &[ctx.money(summary.recommended_spend_today)],
             ),
-            bullets: vec![
-                format!("Pengeluaran hari ini {}.", rupiah(summary.today_out)),
-                format!("Sisa hari ini {}.", rupiah(summary.today_remaining)),
-            ],
-            next_step,
-            tone: "alert".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "overspent_today".to_string(),
-                key_numbers: vec![
-                    summary.today_out,
-                    summary.recommended_spend_today,
-                    summary.today_remaining,
-                ],
-            }),
-        };
+        ],
+        next_step: ctx.copy("onboarding.next_step", &[]),
+        key_numbers: vec![inputs.tx_count_total, summary.recommended_spend_today],
+    })
+}
+
+fn rule_frozen(ctx: &RuleContext) -> Option<RuleOutput> {
+    let summary = &ctx.inputs.summary;
+    if !ctx.config.spending_frozen {
+        return None;
     }
+    Some(RuleOutput {
+        status_title: ctx.copy("frozen.status_title", &[]),
+        bullets: vec![
+            ctx.copy("common.today_out", &[ctx.money(summary.today_out)]),
+            ctx.copy("frozen.held", &[ctx.money(0)]),
+        ],
+        next_step: ctx.copy("frozen.next_step", &[]),
+        key_numbers: vec![summary.today_out, summary.net_balance],
+    })
+}
 
-    if inputs.tx_count_today == 0 {
-        return CoachingInsight {
-            status_title: "Belum ada catatan hari ini, 0 transaksi.".to_string(),
-            bullets: vec![
-                format!(
-                    "Rekomendasi hari ini {}.",
-                    rupiah(summary.recommended_spend_today)
-                ),
-                format!("Pengeluaran hari ini {}.", rupiah(summary.today_out)),
-            ],
-            next_step: time_bucket_no_tx_next_step(time_context),
-            tone: "calm".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "no_tx_today".to_string(),
-                key_numbers: vec![
-                    inputs.tx_count_today,
-                    summary.recommended_spend_today,
-                    summary.today_out,
-                ],
-            }),
-        };
+fn rule_deficit(ctx: &RuleContext) -> Option<RuleOutput> {
+    let summary = &ctx.inputs.summary;
+    if !summary.net_balance_negative {
+        return None;
     }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "deficit.status_title",
+            &[ctx.money(summary.net_balance.abs())],
+        ),
+        bullets: vec![
+            ctx.copy(
+                "deficit.out_vs_in",
+                &[ctx.money(summary.total_out), ctx.money(summary.total_in)],
+            ),
+            ctx.copy("deficit.held", &[ctx.money(0)]),
+        ],
+        next_step: ctx.copy("deficit.next_step", &[]),
+        key_numbers: vec![summary.net_balance, summary.total_in, summary.total_out],
+    })
+}
 
-    if inputs.fixed_cost_unpaid_count_month > 0 {
-        return CoachingInsight {
-            status_title: format!(
-                "Ada {} biaya tetap belum lunas bulan ini.",
-                inputs.fixed_cost_unpaid_count_month
+fn rule_overspent_today(ctx: &RuleContext) -> Option<RuleOutput> {
+    let summary = &ctx.inputs.summary;
+    let ratio = effective_near_limit_ratio(ctx.inputs.near_limit_ratio, ctx.mode.as_str());
+    let available = summary.recommended_spend_today + summary.carried_over;
+    if spend_status(summary.today_out, available, ratio) != SpendStatus::Over {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "overspent_today.status_title",
+            &[ctx.money(summary.recommended_spend_today)],
+        ),
+        bullets: vec![
+            ctx.copy("common.today_out", &[ctx.money(summary.today_out)]),
+            ctx.copy(
+                "overspent_today.remaining",
+                &[ctx.money(summary.today_remaining)],
             ),
-            bullets: vec![
-                format!(
-                    "Total belum lunas {}.",
-                    rupiah(inputs.fixed_cost_unpaid_amount_month)
-                ),
-                format!("Saldo bersih {}.", rupiah(summary.net_balance)),
-            ],
-            next_step: "Langkah kecil: pilih 1 biaya tetap yang paling dekat jatuh tempo."
-                .to_string(),
-            tone: "calm".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "fixed_cost_unpaid".to_string(),
-                key_numbers: vec![
-                    inputs.fixed_cost_unpaid_count_month,
-                    inputs.fixed_cost_unpaid_amount_month,
-                    summary.net_balance,
+        ],
+        next_step: ctx.copy(
+            &ctx.mode_key("overspent_today.next_step"),
+            &[ctx.money(summary.recommended_spend_today)],
+        ),
+        key_numbers: vec![
+            summary.today_out,
+            summary.recommended_spend_today,
+            summary.today_remaining,
+        ],
+    })
+}
+
+fn rule_buffer_reached(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if !inputs.buffer_just_reached {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy("buffer_reached.status_title", &[]),
+        bullets: vec![
+            ctx.copy(
+                "buffer_reached.balance_vs_target",
+                &[
+                    ctx.money(summary.net_balance),
+                    ctx.money(summary.target_penyangga),
                 ],
-            }),
-        };
+            ),
+            ctx.copy(
+                "common.recommended_today",
+                &[ctx.money(summary.recommended_spend_today)],
+            ),
+        ],
+        next_step: ctx.copy("buffer_reached.next_step", &[]),
+        key_numbers: vec![summary.net_balance, summary.target_penyangga],
+    })
+}
+
+fn rule_no_spend_day(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if !inputs.no_spend_goal_today || summary.today_out != 0 {
+        return None;
     }
+    Some(RuleOutput {
+        status_title: ctx.copy("no_spend_day.status_title", &[]),
+        bullets: vec![
+            ctx.copy("no_spend_day.nothing_spent", &[]),
+            ctx.copy(
+                "no_spend_day.untouched",
+                &[ctx.money(summary.recommended_spend_today)],
+            ),
+        ],
+        next_step: ctx.copy("no_spend_day.next_step", &[]),
+        key_numbers: vec![summary.today_out, summary.recommended_spend_today],
+    })
+}
 
-    if summary.target_penyangga > 0
-        && summary.net_balance < summary.target_penyangga
-        && summary.hari_ketahanan_stop_pemasukan <= 7
-    {
-        let next_step = if watchful {
-            format!(
-                "Prioritaskan kebutuhan inti; jaga pengeluaran di bawah {}.",
-                rupiah(summary.recommended_spend_today)
+fn rule_no_tx_today(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if inputs.tx_count_today != 0 {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy("no_tx_today.status_title", &[]),
+        bullets: vec![
+            ctx.copy(
+                "common.recommended_today",
+                &[ctx.money(summary.recommended_spend_today)],
+            ),
+            ctx.copy("common.today_out", &[ctx.money(summary.today_out)]),
+        ],
+        next_step: time_bucket_no_tx_next_step(ctx),
+        key_numbers: vec![
+            inputs.tx_count_today,
+            summary.recommended_spend_today,
+            summary.today_out,
+        ],
+    })
+}
+
+fn rule_fixed_cost_unpaid(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if inputs.fixed_cost_unpaid_count_month <= 0 {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "fixed_cost_unpaid.status_title",
+            &[inputs.fixed_cost_unpaid_count_month.to_string()],
+        ),
+        bullets: vec![
+            ctx.copy(
+                "fixed_cost_unpaid.unpaid_total",
+                &[ctx.money(inputs.fixed_cost_unpaid_amount_month)],
+            ),
+            ctx.copy(
+                "fixed_cost_unpaid.net_balance",
+                &[ctx.money(summary.net_balance)],
+            ),
+        ],
+        // Yang sudah lewat jatuh tempo didahulukan.
+        next_step: if inputs.fixed_cost_overdue_count > 0 {
+            ctx.copy(
+                "fixed_cost_unpaid.next_step.overdue",
+                &[inputs.fixed_cost_overdue_count.to_string()],
             )
         } else {
-            format!(
-                "Hari ini aman kalau jaga pengeluaran di bawah {}.",
-                rupiah(summary.recommended_spend_today)
-            )
-        };
-        return CoachingInsight {
-            status_title: format!(
-                "Penyangga belum aman, ketahanan {} hari.",
-                summary.hari_ketahanan_stop_pemasukan
-            ),
-            bullets: vec![
-                format!(
-                    "Saldo bersih {} vs target {}.",
-                    rupiah(summary.net_balance),
-                    rupiah(summary.target_penyangga)
-                ),
-                format!(
-                    "Rekomendasi hari ini {}.",
-                    rupiah(summary.recommended_spend_today)
-                ),
-            ],
-            next_step,
-            tone: "alert".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "low_buffer".to_string(),
-                key_numbers: vec![
-                    summary.net_balance,
-                    summary.target_penyangga,
-                    summary.hari_ketahanan_stop_pemasukan,
+            ctx.copy("fixed_cost_unpaid.next_step", &[])
+        },
+        key_numbers: vec![
+            inputs.fixed_cost_unpaid_count_month,
+            inputs.fixed_cost_unpaid_amount_month,
+            summary.net_balance,
+        ],
+    })
+}
+
+fn rule_low_buffer(ctx: &RuleContext) -> Option<RuleOutput> {
+    let summary = &ctx.inputs.summary;
+    if summary.target_penyangga <= 0
+        || summary.net_balance >= summary.target_penyangga
+        || summary.hari_ketahanan_stop_pemasukan > ctx.config.low_buffer_days
+    {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "low_buffer.status_title",
+            &[summary.hari_ketahanan_stop_pemasukan.to_string()],
+        ),
+        bullets: vec![
+            ctx.copy(
+                "low_buffer.balance_vs_target",
+                &[
+                    ctx.money(summary.net_balance),
+                    ctx.money(summary.target_penyangga),
                 ],
-            }),
-        };
+            ),
+            ctx.copy(
+                "common.recommended_today",
+                &[ctx.money(summary.recommended_spend_today)],
+            ),
+        ],
+        next_step: ctx.copy(
+            &ctx.mode_key("low_buffer.next_step"),
+            &[ctx.money(summary.recommended_spend_today)],
+        ),
+        key_numbers: vec![
+            summary.net_balance,
+            summary.target_penyangga,
+            summary.hari_ketahanan_stop_pemasukan,
+            ctx.config.low_buffer_days,
+        ],
+    })
+}
+
+fn rule_near_limit(ctx: &RuleContext) -> Option<RuleOutput> {
+    let summary = &ctx.inputs.summary;
+    let ratio = effective_near_limit_ratio(ctx.inputs.near_limit_ratio, ctx.mode.as_str());
+    let available = summary.recommended_spend_today + summary.carried_over;
+    // near_limit juga berlaku saat sudah lewat; overspent_today hanya lebih dulu di urutan aturan.
+    if spend_status(summary.today_out, available, ratio) == SpendStatus::Under {
+        return None;
     }
+    let threshold = near_limit_threshold(available, ratio);
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "near_limit.status_title",
+            &[ctx.money(summary.recommended_spend_today)],
+        ),
+        bullets: vec![
+            ctx.copy("near_limit.used", &[ctx.money(summary.today_out)]),
+            ctx.copy(
+                "near_limit.left",
+                &[ctx.money(summary.today_remaining_clamped)],
+            ),
+        ],
+        next_step: ctx.copy(
+            &ctx.mode_key("near_limit.next_step"),
+            &[ctx.money(summary.today_remaining_clamped)],
+        ),
+        key_numbers: vec![
+            summary.today_out,
+            summary.recommended_spend_today,
+            summary.today_remaining_clamped,
+            threshold,
+        ],
+    })
+}
 
-    if summary.recommended_spend_today > 0
-        && summary.today_out >= (summary.recommended_spend_today * 8) / 10
+fn rule_weekly_recap(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let time_context = ctx.time_context;
+    if time_context.weekday.num_days_from_sunday() != ctx.config.week_start_day
+        || !time_context.is_new_day_first_open
     {
-        let next_step = if watchful {
-            format!(
-                "Tekan belanja tambahan; sisa aman {} untuk hari ini.",
-                rupiah(summary.today_remaining_clamped)
-            )
-        } else {
-            format!(
-                "Langkah kecil: kalau perlu belanja lagi, pilih yang paling penting di bawah {}.",
-                rupiah(summary.today_remaining_clamped)
-            )
-        };
-        return CoachingInsight {
-            status_title: format!(
-                "Hampir menyentuh batas {}.",
-                rupiah(summary.recommended_spend_today)
-            ),
-            bullets: vec![
-                format!("Sudah terpakai {} hari ini.", rupiah(summary.today_out)),
-                format!(
-                    "Sisa {} untuk hari ini.",
-                    rupiah(summary.today_remaining_clamped)
-                ),
-            ],
-            next_step,
-            tone: "calm".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "near_limit".to_string(),
-                key_numbers: vec![
-                    summary.today_out,
-                    summary.recommended_spend_today,
-                    summary.today_remaining_clamped,
-                ],
-            }),
-        };
+        return None;
     }
+    Some(RuleOutput {
+        status_title: ctx.copy("weekly_recap.status_title", &[]),
+        bullets: vec![
+            ctx.copy(
+                "consistency_praise.total_7d",
+                &[ctx.money(inputs.total_out_7d)],
+            ),
+            ctx.copy("common.avg_out_7d", &[ctx.money(inputs.avg_out_7d)]),
+            ctx.copy(
+                "weekly_recap.days_with_tx",
+                &[inputs.days_with_tx_7d.to_string()],
+            ),
+        ],
+        next_step: ctx.copy("weekly_recap.next_step", &[]),
+        key_numbers: vec![
+            inputs.total_out_7d,
+            inputs.avg_out_7d,
+            inputs.days_with_tx_7d,
+        ],
+    })
+}
 
-    if inputs.days_with_tx_7d >= 6 {
-        return CoachingInsight {
-            status_title: format!("Kamu konsisten {} dari 7 hari.", inputs.days_with_tx_7d),
-            bullets: vec![
-                format!("Total pengeluaran 7 hari {}.", rupiah(inputs.total_out_7d)),
-                format!(
-                    "Rata-rata pengeluaran 7 hari {} per hari.",
-                    rupiah(inputs.avg_out_7d)
-                ),
-                format!("Total transaksi tercatat {}.", inputs.tx_count_total),
-            ],
-            next_step: "Pertahankan: cukup 1 catatan per hari selama 2 hari lagi.".to_string(),
-            tone: "calm".to_string(),
-            coach_mode: coach_mode.to_string(),
-            continuity_line: None,
-            memory_reflection: None,
-            debug_meta: Some(InsightDebugMeta {
-                rule_id: "consistency_praise".to_string(),
-                key_numbers: vec![inputs.days_with_tx_7d, inputs.avg_out_7d],
-            }),
-        };
+fn rule_outpacing_income(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    let summary = &inputs.summary;
+    if inputs.total_out_7d <= 0
+        || inputs.total_out_7d * 100 < inputs.total_in_7d * OUTPACING_MARGIN_PERCENT
+        || summary.net_balance >= summary.target_penyangga * OUTPACING_BUFFER_MULTIPLE
+    {
+        return None;
     }
+    Some(RuleOutput {
+        status_title: ctx.copy("outpacing_income.status_title", &[]),
+        bullets: vec![
+            ctx.copy(
+                "outpacing_income.in_vs_out",
+                &[
+                    ctx.money(inputs.total_in_7d),
+                    ctx.money(inputs.total_out_7d),
+                ],
+            ),
+            ctx.copy("common.avg_out_7d", &[ctx.money(inputs.avg_out_7d)]),
+        ],
+        next_step: ctx.copy(
+            &ctx.mode_key("outpacing_income.next_step"),
+            &[ctx.money(summary.recommended_spend_today)],
+        ),
+        key_numbers: vec![inputs.total_in_7d, inputs.total_out_7d],
+    })
+}
 
-    CoachingInsight {
-        status_title: format!(
-            "Kondisi hari ini cukup stabil, saldo {}.",
-            rupiah(summary.net_balance)
+fn rule_consistency_praise(ctx: &RuleContext) -> Option<RuleOutput> {
+    let inputs = ctx.inputs;
+    if inputs.days_with_tx_7d < 6 {
+        return None;
+    }
+    Some(RuleOutput {
+        status_title: ctx.copy(
+            "consistency_praise.status_title",
+            &[inputs.days_with_tx_7d.to_string()],
         ),
         bullets: vec![
-            format!(
-                "Dana fleksibel {} di atas penyangga.",
-                rupiah(summary.dana_fleksibel)
+            ctx.copy(
+                "consistency_praise.total_7d",
+                &[ctx.money(inputs.total_out_7d)],
+            ),
+            ctx.copy("common.avg_out_7d", &[ctx.money(inputs.avg_out_7d)]),
+            ctx.copy(
+                "consistency_praise.tx_total",
+                &[inputs.tx_count_total.to_string()],
             ),
-            format!(
-                "Rekomendasi hari ini {}.",
-                rupiah(summary.recommended_spend_today)
+        ],
+        next_step: ctx.copy("consistency_praise.next_step", &[]),
+        key_numbers: vec![inputs.days_with_tx_7d, inputs.avg_out_7d],
+    })
+}
+
+fn rule_normal(ctx: &RuleContext) -> Option<RuleOutput> {
+    Some(normal_output(ctx))
+}
+
+fn normal_output(ctx: &RuleContext) -> RuleOutput {
+    let summary = &ctx.inputs.summary;
+    RuleOutput {
+        status_title: ctx.copy("normal.status_title", &[ctx.money(summary.net_balance)]),
+        bullets: vec![
+            ctx.copy("normal.flexible", &[ctx.money(summary.dana_fleksibel)]),
+            ctx.copy(
+                "common.recommended_today",
+                &[ctx.money(summary.recommended_spend_today)],
             ),
         ],
-        next_step: format!(
-            "Langkah kecil: belanja aman jika tetap di bawah {}.",
-            rupiah(summary.recommended_spend_today)
+        next_step: ctx.copy(
+            "normal.next_step",
+            &[ctx.money(summary.recommended_spend_today)],
         ),
-        tone: "calm".to_string(),
-        coach_mode: coach_mode.to_string(),
-        continuity_line: None,
-        memory_reflection: None,
-        debug_meta: Some(InsightDebugMeta {
-            rule_id: "normal".to_string(),
-            key_numbers: vec![summary.net_balance, summary.recommended_spend_today],
-        }),
+        key_numbers: vec![summary.net_balance, summary.recommended_spend_today],
     }
 }
 
-fn time_bucket_no_tx_next_step(time_context: &TimeContext) -> String {
-    match time_context.time_bucket.as_str() {
-        "morning" => "Kalau ada satu catatan kecil pagi ini, ritmenya lebih terasa.".to_string(),
-        "night" => "Hari ini sudah hampir selesai; besok kita mulai lagi.".to_string(),
-        _ => "Langkah kecil: catat 1 transaksi pertama hari ini.".to_string(),
-    }
+fn time_bucket_no_tx_next_step(ctx: &RuleContext) -> String {
+    let key = match ctx.time_context.time_bucket.as_str() {
+        "morning" => "no_tx_today.next_step.morning",
+        "night" => "no_tx_today.next_step.night",
+        _ => "no_tx_today.next_step.default",
+    };
+    ctx.copy(key, &[])
 }
 
 fn maybe_record_memory(
     conn: &Connection,
+    config: &Config,
     inputs: &InsightInputs,
     insight: &CoachingInsight,
     coach_mode: &str,
     last_memory: Option<&CoachingMemoryEntry>,
     today_local: &str,
 ) -> Result<(), String> {
+    if !config.memory_recording_enabled {
+        return Ok(());
+    }
     let existing_today = fetch_memory_for_date(conn, today_local)?;
     let tone_changed = last_memory
         .map(|entry| entry.mode != insight.tone)
         .unwrap_or(false);
-    let overspent = insight
+    let rule_id = insight
         .debug_meta
         .as_ref()
-        .map(|meta| meta.rule_id == "overspent_today")
-        .unwrap_or(false);
+        .map(|meta| meta.rule_id.as_str())
+        .unwrap_or("");
+    let overspent = rule_id == "overspent_today";
+    let buffer_reached = rule_id == "buffer_reached";
+    let near_limit = config.record_near_limit && rule_id == "near_limit";
     let streak_milestone = inputs.days_with_tx_7d == 3 || inputs.days_with_tx_7d == 7;
     let first_tx_today = inputs.tx_count_today == 1;
-    let event_significant = overspent || streak_milestone || first_tx_today || tone_changed;
+    let event_significant = overspent
+        || buffer_reached
+        || near_limit
+        || streak_milestone
+        || first_tx_today
+        || tone_changed;
 
     if existing_today.is_some() && !event_significant {
         return Ok(());
@@ -587,6 +1622,7 @@ fn maybe_record_memory(
         "recommended_spend_today": inputs.summary.recommended_spend_today,
         "today_out": inputs.summary.today_out,
         "net_balance": inputs.summary.net_balance,
+        "target_penyangga": inputs.summary.target_penyangga,
         "hari_ketahanan": inputs.summary.hari_ketahanan_stop_pemasukan,
         "unpaid_count": inputs.fixed_cost_unpaid_count_month,
         "mode": coach_mode,
@@ -594,8 +1630,9 @@ fn maybe_record_memory(
     .to_string();
 
     conn.execute(
-        "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags, context_json)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        "INSERT INTO coaching_memory
+            (ts_utc, date_local, mode, headline, tags, context_json, profile_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, (SELECT active_profile_id FROM app_state))",
         params![
             chrono::Utc::now().timestamp_millis(),
             today_local,
@@ -607,7 +1644,7 @@ fn maybe_record_memory(
     )
     .map_err(|err| err.to_string())?;
 
-    trim_memory(conn, 200)?;
+    trim_memory(conn, config.memory_retention_limit)?;
     Ok(())
 }
 
@@ -635,8 +1672,10 @@ fn build_memory_tags(
 fn trim_memory(conn: &Connection, limit: i64) -> Result<(), String> {
     conn.execute(
         "DELETE FROM coaching_memory
-         WHERE id NOT IN (
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND id NOT IN (
             SELECT id FROM coaching_memory
+            WHERE profile_id = (SELECT active_profile_id FROM app_state)
             ORDER BY ts_utc DESC
             LIMIT ?1
          )",
@@ -654,62 +1693,12 @@ mod tests {
 
     fn setup_conn(min_floor: i64, max_ceil: i64, resilience_days: i64) -> Connection {
         let conn = Connection::open_in_memory().expect("open in-memory");
-        conn.execute_batch(
-            "PRAGMA foreign_keys = ON;
-            CREATE TABLE config (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                min_floor INTEGER NOT NULL,
-                max_ceil INTEGER NOT NULL,
-                resilience_days INTEGER NOT NULL,
-                coach_mode TEXT NOT NULL,
-                created_ts_utc INTEGER NOT NULL,
-                updated_ts_utc INTEGER NOT NULL
-            );
-            CREATE TABLE transactions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                ts_utc INTEGER NOT NULL,
-                date_local TEXT NOT NULL,
-                kind TEXT NOT NULL,
-                amount INTEGER NOT NULL,
-                source TEXT NOT NULL DEFAULT 'manual',
-                fixed_cost_id INTEGER
-            );
-            CREATE TABLE fixed_costs (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                amount INTEGER NOT NULL,
-                is_active INTEGER NOT NULL DEFAULT 1,
-                paid_date_local TEXT,
-                paid_ts_utc INTEGER,
-                paid_tx_id INTEGER
-            );
-            CREATE TABLE fixed_cost_payments (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                fixed_cost_id INTEGER NOT NULL,
-                period_ym TEXT NOT NULL,
-                paid_date_local TEXT,
-                paid_ts_utc INTEGER,
-                tx_id INTEGER,
-                FOREIGN KEY(fixed_cost_id) REFERENCES fixed_costs(id)
-            );
-            CREATE TABLE coaching_memory (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                ts_utc INTEGER NOT NULL,
-                date_local TEXT NOT NULL,
-                mode TEXT NOT NULL,
-                headline TEXT NOT NULL,
-                tags TEXT NOT NULL,
-                context_json TEXT
-            );",
-        )
-        .expect("create schema");
-        let now = chrono::Utc::now().timestamp_millis();
+        crate::db::init_schema(&conn).expect("init schema");
         conn.execute(
-            "INSERT INTO config (id, min_floor, max_ceil, resilience_days, coach_mode, created_ts_utc, updated_ts_utc)
-             VALUES (1, ?1, ?2, ?3, 'calm', ?4, ?4)",
-            params![min_floor, max_ceil, resilience_days, now],
+            "UPDATE config SET min_floor = ?1, max_ceil = ?2, resilience_days = ?3 WHERE id = 1",
+            params![min_floor, max_ceil, resilience_days],
         )
-        .expect("insert config");
+        .expect("update config");
         conn
     }
 
@@ -742,7 +1731,36 @@ mod tests {
             .with_ymd_and_hms(date.year(), date.month(), date.day(), hour, 0, 0)
             .single()
             .expect("dt");
-        compute_coaching_insight_with_time(conn, dt).expect("insight")
+        compute_coaching_insight_with_time(conn, dt, Locale::Id, Verbosity::Normal)
+            .expect("insight")
+    }
+
+    #[test]
+    fn format_money_groups_thousands_with_dots() {
+        let conn = setup_conn(100, 1000, 10);
+        let config = fetch_config(&conn).expect("config");
+        let rupiah = |value| format_money(value, &config, Locale::Id);
+        assert_eq!(rupiah(0), "Rp0");
+        assert_eq!(rupiah(950), "Rp950");
+        assert_eq!(rupiah(1_500), "Rp1.500");
+        assert_eq!(rupiah(1_500_000), "Rp1.500.000");
+        assert_eq!(rupiah(123_456_789), "Rp123.456.789");
+        assert_eq!(rupiah(-1_000), "-Rp1.000");
+        assert_eq!(format_money(1_500_000, &config, Locale::En), "Rp1,500,000");
+    }
+
+    #[test]
+    fn format_money_applies_minor_units() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "UPDATE config SET currency_code = 'USD', minor_units = 2 WHERE id = 1",
+            [],
+        )
+        .expect("set currency");
+        let config = fetch_config(&conn).expect("config");
+        assert_eq!(format_money(123_450, &config, Locale::En), "$1,234.50");
+        assert_eq!(format_money(-5, &config, Locale::En), "-$0.05");
+        assert_eq!(format_money(123_450, &config, Locale::Id), "$1.234,50");
     }
 
     #[test]
@@ -754,6 +1772,22 @@ mod tests {
         assert_eq!(insight.debug_meta.unwrap().rule_id, "onboarding");
     }
 
+    #[test]
+    fn list_insight_rules_matches_priority_order() {
+        let rules = list_insight_rules();
+        let ids: Vec<&str> = rules.iter().map(|rule| rule.rule_id.as_str()).collect();
+        assert_eq!(ids.first(), Some(&"frozen"));
+        assert_eq!(ids.last(), Some(&"normal"));
+        assert_eq!(rules[3].rule_id, "overspent_today");
+        assert_eq!(rules[3].default_priority, 4);
+        assert_eq!(rules[3].tone, "alert");
+        assert_eq!(rules[1].depends_on, vec!["tx_count_total < 5"]);
+        let mut unique = ids.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len());
+    }
+
     #[test]
     fn rule_overspent_today() {
         let conn = setup_conn(100, 1000, 10);
@@ -769,6 +1803,200 @@ mod tests {
         assert_eq!(insight.tone, "alert");
     }
 
+    #[test]
+    fn rule_buffer_reached_fires_once_after_crossing_target() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 5..=9 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 100);
+        }
+        let before = compute_for(&conn, "2025-05-10", 9);
+        assert_ne!(before.debug_meta.unwrap().rule_id, "buffer_reached");
+
+        insert_tx(&conn, "2025-05-10", "IN", 1000);
+        let reached = compute_for(&conn, "2025-05-10", 12);
+        assert_eq!(reached.debug_meta.unwrap().rule_id, "buffer_reached");
+        assert_eq!(reached.tone, "calm");
+
+        let after = compute_for(&conn, "2025-05-10", 15);
+        assert_ne!(after.debug_meta.unwrap().rule_id, "buffer_reached");
+    }
+
+    #[test]
+    fn verbosity_trims_or_extends_bullets() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 5..=9 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 100);
+        }
+        insert_tx(&conn, "2025-05-09", "OUT", 70);
+        let dt = Local
+            .with_ymd_and_hms(2025, 5, 10, 12, 0, 0)
+            .single()
+            .expect("dt");
+        let with = |verbosity| {
+            compute_coaching_insight_with_time(&conn, dt, Locale::Id, verbosity).expect("insight")
+        };
+
+        let normal = with(Verbosity::Normal);
+        assert!(normal.bullets.len() > 1);
+        assert_eq!(with(Verbosity::Short).bullets, normal.bullets[..1].to_vec());
+        let detailed = with(Verbosity::Detailed);
+        assert_eq!(detailed.bullets[..normal.bullets.len()], normal.bullets[..]);
+        assert_eq!(
+            detailed.bullets.last().map(String::as_str),
+            Some("Rata-rata pengeluaran 7 hari Rp10 per hari.")
+        );
+        assert!(Verbosity::parse(Some("loud")).is_err());
+    }
+
+    fn today_insight_for_mode(
+        coach_mode: &str,
+        resilience_days: i64,
+        income: i64,
+        today_out: i64,
+    ) -> CoachingInsight {
+        let conn = setup_conn(1_000, 10_000, resilience_days);
+        conn.execute(
+            "UPDATE config SET coach_mode = ?1 WHERE id = 1",
+            [coach_mode],
+        )
+        .expect("set coach mode");
+        let now = Local::now();
+        for days_ago in 1..=5 {
+            let date = (now - Duration::days(days_ago))
+                .format("%Y-%m-%d")
+                .to_string();
+            insert_tx(&conn, &date, "IN", income / 5);
+        }
+        insert_tx(&conn, &today_local_string(now), "OUT", today_out);
+        compute_coaching_insight_with_time(&conn, now, Locale::Id, Verbosity::Normal)
+            .expect("insight")
+    }
+
+    #[test]
+    fn strict_mode_changes_overspent_copy() {
+        let calm = today_insight_for_mode("calm", 10, 100_000, 20_000);
+        let strict = today_insight_for_mode("strict", 10, 100_000, 20_000);
+        assert_eq!(calm.debug_meta.unwrap().rule_id, "overspent_today");
+        assert_eq!(strict.debug_meta.unwrap().rule_id, "overspent_today");
+        assert_eq!(calm.coach_mode, "calm");
+        assert_eq!(strict.coach_mode, "strict");
+        assert_ne!(calm.next_step, strict.next_step);
+        assert!(strict.next_step.starts_with("Stop belanja"));
+    }
+
+    #[test]
+    fn english_locale_translates_rule_copy() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-10", "IN", 1000);
+
+        let date = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
+        let dt = Local
+            .with_ymd_and_hms(date.year(), date.month(), date.day(), 9, 0, 0)
+            .single()
+            .expect("dt");
+        let insight = compute_coaching_insight_with_time(&conn, dt, Locale::En, Verbosity::Normal)
+            .expect("insight");
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "onboarding");
+        assert_eq!(
+            insight.status_title,
+            "Only 1 transactions so far; build the habit slowly."
+        );
+        assert!(insight.bullets[1].starts_with("Today's recommendation is Rp"));
+    }
+
+    #[test]
+    fn strict_mode_lowers_near_limit_to_sixty_percent() {
+        // Rekomendasi Rp8000; Rp6000 = 75%, di bawah 80% bawaan.
+        let calm = today_insight_for_mode("calm", 10, 100_000, 6_000);
+        let strict = today_insight_for_mode("strict", 10, 100_000, 6_000);
+        assert_ne!(calm.debug_meta.unwrap().rule_id, "near_limit");
+        let meta = strict.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "near_limit");
+        assert_eq!(meta.key_numbers.last(), Some(&4_800));
+    }
+
+    #[test]
+    fn strict_mode_alerts_when_spending_below_buffer() {
+        // Saldo 9000 < target 30000, ketahanan 9 hari sehingga low_buffer tidak aktif.
+        let watchful = today_insight_for_mode("calm", 30, 10_000, 1_000);
+        let strict = today_insight_for_mode("strict", 30, 10_000, 1_000);
+        assert_eq!(watchful.coach_mode, "watchful");
+        assert_eq!(watchful.tone, "calm");
+        assert_eq!(strict.tone, "alert");
+    }
+
+    #[test]
+    fn rule_low_buffer_follows_low_buffer_days() {
+        // Target 30000, saldo 9000: ketahanan 9 hari.
+        let conn = setup_conn(1_000, 10_000, 30);
+        for day in 1..=5 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 2_000);
+        }
+        insert_tx(&conn, "2025-05-10", "OUT", 1_000);
+
+        let default = compute_for(&conn, "2025-05-10", 12);
+        assert_ne!(default.debug_meta.unwrap().rule_id, "low_buffer");
+
+        conn.execute("UPDATE config SET low_buffer_days = 10 WHERE id = 1", [])
+            .expect("low buffer days");
+        let cautious = compute_for(&conn, "2025-05-10", 13);
+        let meta = cautious.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "low_buffer");
+        assert_eq!(meta.key_numbers, vec![9_000, 30_000, 9, 10]);
+    }
+
+    #[test]
+    fn rule_fixed_cost_unpaid_prioritizes_overdue() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 1..=5 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 200);
+        }
+        insert_tx(&conn, "2025-05-10", "OUT", 10);
+        let rent = insert_fixed_cost(&conn, "Sewa", 500);
+        let internet = insert_fixed_cost(&conn, "Internet", 100);
+        conn.execute(
+            "UPDATE fixed_costs SET due_day = 20 WHERE id = ?1",
+            [internet],
+        )
+        .expect("due day");
+
+        let before = compute_for(&conn, "2025-05-10", 14);
+        assert_eq!(before.debug_meta.unwrap().rule_id, "fixed_cost_unpaid");
+        assert!(before.next_step.starts_with("Langkah kecil: pilih"));
+
+        conn.execute("UPDATE fixed_costs SET due_day = 5 WHERE id = ?1", [rent])
+            .expect("due day");
+        let overdue = compute_for(&conn, "2025-05-10", 14);
+        assert!(overdue.next_step.contains("1 biaya tetap"));
+        assert!(overdue.next_step.contains("jatuh tempo"));
+    }
+
+    #[test]
+    fn rule_frozen_overrides_other_rules() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-10", "IN", 2000);
+        conn.execute("UPDATE config SET spending_frozen = 1 WHERE id = 1", [])
+            .expect("freeze");
+
+        let insight = compute_for(&conn, "2025-05-10", 12);
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "frozen");
+        assert_eq!(insight.tone, "alert");
+    }
+
+    #[test]
+    fn rule_deficit_when_out_exceeds_in() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-06", "IN", 500);
+        insert_tx(&conn, "2025-05-07", "OUT", 300);
+        insert_tx(&conn, "2025-05-08", "OUT", 300);
+        insert_tx(&conn, "2025-05-09", "OUT", 100);
+        insert_tx(&conn, "2025-05-10", "OUT", 50);
+
+        let insight = compute_for(&conn, "2025-05-10", 12);
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "deficit");
+        assert_eq!(insight.tone, "alert");
+    }
+
     #[test]
     fn rule_no_tx_today() {
         let conn = setup_conn(100, 1000, 10);
@@ -780,6 +2008,43 @@ mod tests {
         assert_eq!(insight.debug_meta.unwrap().rule_id, "no_tx_today");
     }
 
+    #[test]
+    fn time_bucket_follows_configured_hours() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 1..=5 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 200);
+        }
+        let morning = compute_for(&conn, "2025-05-10", 8);
+        assert_eq!(
+            morning.next_step,
+            insight_copy::text(Locale::Id, "no_tx_today.next_step.morning")
+        );
+
+        conn.execute("UPDATE config SET morning_start_hour = 9 WHERE id = 1", [])
+            .expect("shift morning");
+        let early = compute_for(&conn, "2025-05-10", 8);
+        assert_eq!(
+            early.next_step,
+            insight_copy::text(Locale::Id, "no_tx_today.next_step.night")
+        );
+    }
+
+    #[test]
+    fn rule_no_spend_day_when_goal_set_and_nothing_spent() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 1..=5 {
+            insert_tx(&conn, &format!("2025-05-0{}", day), "IN", 200);
+        }
+        conn.execute(
+            "INSERT INTO no_spend_days (date_local, created_ts_utc) VALUES ('2025-05-10', 1)",
+            [],
+        )
+        .expect("insert no spend day");
+
+        let insight = compute_for(&conn, "2025-05-10", 9);
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "no_spend_day");
+    }
+
     #[test]
     fn rule_fixed_cost_unpaid() {
         let conn = setup_conn(100, 1000, 10);
@@ -793,6 +2058,27 @@ mod tests {
         assert_eq!(insight.debug_meta.unwrap().rule_id, "fixed_cost_unpaid");
     }
 
+    #[test]
+    fn rule_outpacing_income_when_week_spends_more_than_it_earns() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-01", "IN", 2700);
+        insert_tx(&conn, "2025-05-08", "IN", 200);
+        for day in ["2025-05-05", "2025-05-07", "2025-05-09"] {
+            insert_tx(&conn, day, "OUT", 300);
+        }
+        insert_tx(&conn, "2025-05-10", "OUT", 50);
+
+        let insight = compute_for(&conn, "2025-05-10", 12);
+        assert_eq!(insight.tone, "alert");
+        let meta = insight.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "outpacing_income");
+        assert_eq!(meta.key_numbers, vec![200, 950]);
+
+        insert_tx(&conn, "2025-05-09", "IN", 1000);
+        let covered = compute_for(&conn, "2025-05-10", 13);
+        assert_ne!(covered.debug_meta.unwrap().rule_id, "outpacing_income");
+    }
+
     #[test]
     fn rule_consistency_praise() {
         let conn = setup_conn(100, 1000, 10);
@@ -808,6 +2094,91 @@ mod tests {
         assert_eq!(insight.debug_meta.unwrap().rule_id, "consistency_praise");
     }
 
+    #[test]
+    fn rule_weekly_recap_on_first_monday_open() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-08", "IN", 2000);
+        insert_tx(&conn, "2025-05-09", "OUT", 300);
+        insert_tx(&conn, "2025-05-10", "OUT", 200);
+        insert_tx(&conn, "2025-05-11", "OUT", 100);
+        insert_tx(&conn, "2025-05-11", "OUT", 100);
+
+        // 2025-05-12 adalah Senin; 2025-05-13 Selasa.
+        let tuesday = compute_for(&conn, "2025-05-13", 9);
+        assert_ne!(tuesday.debug_meta.unwrap().rule_id, "weekly_recap");
+
+        conn.execute("DELETE FROM coaching_memory", [])
+            .expect("clear memory");
+        let monday = compute_for(&conn, "2025-05-12", 9);
+        let meta = monday.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "weekly_recap");
+        assert_eq!(meta.key_numbers, vec![700, 100, 4]);
+
+        let second_open = compute_for(&conn, "2025-05-12", 10);
+        assert_ne!(second_open.debug_meta.unwrap().rule_id, "weekly_recap");
+    }
+
+    #[test]
+    fn rule_weekly_recap_follows_week_start_day() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute("UPDATE config SET week_start_day = 0 WHERE id = 1", [])
+            .expect("week starts sunday");
+        insert_tx(&conn, "2025-05-07", "IN", 2000);
+        insert_tx(&conn, "2025-05-08", "OUT", 300);
+        insert_tx(&conn, "2025-05-09", "OUT", 200);
+        insert_tx(&conn, "2025-05-10", "OUT", 100);
+        insert_tx(&conn, "2025-05-10", "OUT", 100);
+
+        let monday = compute_for(&conn, "2025-05-12", 9);
+        assert_ne!(monday.debug_meta.unwrap().rule_id, "weekly_recap");
+        conn.execute("DELETE FROM coaching_memory", [])
+            .expect("clear memory");
+        let sunday = compute_for(&conn, "2025-05-11", 9);
+        assert_eq!(sunday.debug_meta.unwrap().rule_id, "weekly_recap");
+    }
+
+    #[test]
+    fn opening_balance_stays_out_of_7d_averages() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "UPDATE config SET opening_balance = 1000000 WHERE id = 1",
+            [],
+        )
+        .expect("opening balance");
+        for day in 1..=3 {
+            insert_tx(&conn, &format!("2025-04-0{}", day), "IN", 100);
+        }
+        insert_tx(&conn, "2025-05-09", "OUT", 700);
+        insert_tx(&conn, "2025-05-10", "IN", 300);
+
+        // 2025-05-12 Senin, pembukaan pertama: rekap memakai angka 7 hari mentah.
+        let insight = compute_for(&conn, "2025-05-12", 9);
+        let meta = insight.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "weekly_recap");
+        assert_eq!(meta.key_numbers, vec![700, 100, 2]);
+    }
+
+    #[test]
+    fn average_spend_divides_by_window_and_active_days() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-01", "OUT", 5000);
+        insert_tx(&conn, "2025-05-08", "OUT", 600);
+        insert_tx(&conn, "2025-05-08", "OUT", 300);
+        insert_tx(&conn, "2025-05-10", "IN", 2000);
+        let today = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
+
+        let average = average_spend(&conn, today, 3).expect("average");
+        assert_eq!(average.total_out, 900);
+        assert_eq!(average.avg_per_day, 300);
+        assert_eq!(average.days_with_tx, 2);
+        assert_eq!(average.avg_per_active_day, 450);
+        assert_eq!(
+            average_spend(&conn, today, 10).expect("10d").total_out,
+            5900
+        );
+        assert!(average_spend(&conn, today, 0).is_err());
+    }
+
     #[test]
     fn rule_normal() {
         let conn = setup_conn(100, 1000, 10);
@@ -868,6 +2239,199 @@ mod tests {
         assert_eq!(count, 1);
     }
 
+    #[test]
+    fn insight_history_newest_first_with_split_tags() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute_batch(
+            "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+             VALUES (1, '2025-05-09', 'calm', 'Kemarin tenang.', '');
+             INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+             VALUES (2, '2025-05-10', 'alert', 'Lewat batas.', 'overspent_today,alert');",
+        )
+        .expect("insert memory");
+
+        let history = insight_history(&conn, 10).expect("history");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].date_local, "2025-05-10");
+        assert_eq!(history[0].tags, vec!["overspent_today", "alert"]);
+        assert!(history[1].tags.is_empty());
+
+        assert_eq!(insight_history(&conn, 1).expect("history").len(), 1);
+    }
+
+    #[test]
+    fn disabled_memory_recording_neither_writes_nor_reads() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-10", "IN", 2000);
+        conn.execute(
+            "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+             VALUES (1, '2025-05-09', 'alert', 'Kemarin lewat batas.', 'alert')",
+            [],
+        )
+        .expect("insert memory");
+        conn.execute(
+            "UPDATE config SET memory_recording_enabled = 0 WHERE id = 1",
+            [],
+        )
+        .expect("disable recording");
+
+        let insight = compute_for(&conn, "2025-05-10", 8);
+        assert!(insight.continuity_line.is_none());
+        assert!(insight.memory_reflection.is_none());
+        assert_eq!(insight_history(&conn, 10).expect("history").len(), 1);
+    }
+
+    #[test]
+    fn insights_markdown_groups_entries_by_date() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute_batch(
+            "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags) VALUES
+               (1, '2025-05-09', 'alert', 'Kemarin lewat batas.', 'alert'),
+               (2, '2025-05-10', 'calm', 'Penyangga aman.', 'calm'),
+               (3, '2025-06-01', 'calm', 'Di luar rentang.', 'calm');",
+        )
+        .expect("insert memory");
+
+        let (markdown, count) =
+            insights_markdown(&conn, "2025-05-01", "2025-05-31").expect("markdown");
+        assert_eq!(count, 2);
+        assert_eq!(
+            markdown,
+            "# Jurnal coaching 2025-05-01 s/d 2025-05-31\n\
+             \n## 2025-05-09\n\n- Kemarin lewat batas. _(mode: alert)_\n\
+             \n## 2025-05-10\n\n- Penyangga aman. _(mode: calm)_\n"
+        );
+
+        let (empty, count) = insights_markdown(&conn, "2025-07-01", "2025-07-31").expect("empty");
+        assert_eq!(count, 0);
+        assert!(empty.ends_with("_Tidak ada catatan coaching pada rentang ini._\n"));
+    }
+
+    #[test]
+    fn cleared_memory_behaves_like_first_run() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-10", "IN", 2000);
+        conn.execute(
+            "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+             VALUES (1, '2025-05-09', 'alert', 'Kemarin lewat batas.', 'alert')",
+            [],
+        )
+        .expect("insert memory");
+
+        assert_eq!(clear_coaching_memory(&conn).expect("clear"), 1);
+        assert!(insight_history(&conn, 10).expect("history").is_empty());
+
+        let insight = compute_for(&conn, "2025-05-10", 12);
+        assert!(insight.memory_reflection.is_none());
+        compute_coaching_insight(
+            &conn,
+            &crate::clock::SystemClock,
+            Locale::Id,
+            Verbosity::Normal,
+        )
+        .expect("insight after clear");
+    }
+
+    fn setup_near_limit_day(conn: &Connection, today: NaiveDate) {
+        let today_local = today.format("%Y-%m-%d").to_string();
+        for days_back in 1..=3 {
+            let date_local = (today - Duration::days(days_back))
+                .format("%Y-%m-%d")
+                .to_string();
+            insert_tx(conn, &date_local, "IN", 10);
+        }
+        insert_tx(conn, &today_local, "IN", 1970);
+        insert_tx(conn, &today_local, "OUT", 90);
+        conn.execute(
+            "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+             VALUES (1, ?1, 'calm', 'Pagi yang tenang.', 'normal')",
+            [today_local.as_str()],
+        )
+        .expect("insert memory");
+    }
+
+    #[test]
+    fn near_limit_not_recorded_by_default() {
+        let conn = setup_conn(100, 1000, 10);
+        let today = Local::now().date_naive();
+        setup_near_limit_day(&conn, today);
+
+        let insight = compute_for(&conn, &today.format("%Y-%m-%d").to_string(), 12);
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "near_limit");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM coaching_memory", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn near_limit_recorded_when_flag_on() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute("UPDATE config SET record_near_limit = 1 WHERE id = 1", [])
+            .expect("enable flag");
+        let today = Local::now().date_naive();
+        setup_near_limit_day(&conn, today);
+
+        let insight = compute_for(&conn, &today.format("%Y-%m-%d").to_string(), 12);
+        assert_eq!(insight.debug_meta.unwrap().rule_id, "near_limit");
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM coaching_memory WHERE tags LIKE 'near_limit%'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count");
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn near_limit_uses_configured_ratio() {
+        let conn = setup_conn(100, 1000, 10);
+        conn.execute("UPDATE config SET near_limit_ratio = 95 WHERE id = 1", [])
+            .expect("raise ratio");
+        let today = Local::now().date_naive();
+        setup_near_limit_day(&conn, today);
+        let today_local = today.format("%Y-%m-%d").to_string();
+
+        let insight = compute_for(&conn, &today_local, 12);
+        assert_ne!(insight.debug_meta.unwrap().rule_id, "near_limit");
+
+        conn.execute("UPDATE config SET near_limit_ratio = 50 WHERE id = 1", [])
+            .expect("lower ratio");
+        let insight = compute_for(&conn, &today_local, 12);
+        let meta = insight.debug_meta.unwrap();
+        assert_eq!(meta.rule_id, "near_limit");
+        assert_eq!(meta.key_numbers.last(), Some(&50));
+    }
+
+    #[test]
+    fn export_coaching_log_is_chronological_prose() {
+        let conn = setup_conn(100, 1000, 10);
+        for (ts, date_local, mode, headline) in [
+            (3, "2025-05-12", "calm", "Kondisi stabil."),
+            (1, "2025-05-10", "calm", "Baru mulai."),
+            (2, "2025-05-11", "alert", "Hari ini melewati batas."),
+            (4, "2025-06-01", "calm", "Di luar rentang."),
+        ] {
+            conn.execute(
+                "INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+                 VALUES (?1, ?2, ?3, ?4, '')",
+                params![ts, date_local, mode, headline],
+            )
+            .expect("insert memory");
+        }
+
+        let log = export_coaching_log(&conn, "2025-05-01", "2025-05-31").expect("log");
+        let lines: Vec<&str> = log.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("2025-05-10 — Baru mulai."));
+        assert!(lines[1].contains("lebih ketat dari kemarin"));
+        assert!(lines[2].contains("Kemarin sempat ketat"));
+
+        let empty = export_coaching_log(&conn, "2024-01-01", "2024-01-31").expect("empty");
+        assert_eq!(empty, "");
+    }
+
     #[test]
     fn memory_added_on_overspent() {
         let conn = setup_conn(100, 1000, 10);
@@ -884,4 +2448,22 @@ mod tests {
             .expect("count");
         assert_eq!(count, 1);
     }
+
+    #[test]
+    fn preview_matches_compute_without_recording() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "2025-05-10", "IN", 2000);
+        insert_tx(&conn, "2025-05-10", "OUT", 200);
+
+        let clock = crate::clock::FixedClock::at(2025, 5, 10);
+        let preview = preview_coaching_insight(&conn, &clock, Locale::Id).expect("preview");
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM coaching_memory", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 0);
+
+        let actual = compute_for(&conn, "2025-05-10", 12);
+        assert_eq!(preview.status_title, actual.status_title);
+        assert_eq!(preview.next_step, actual.next_step);
+    }
 }
@@ -0,0 +1,432 @@
+//! Insight copy per locale. Keys are `<rule_id>.<field>`; placeholders are `{0}`, `{1}`, ...
+//! Adding a language means adding a `Locale` variant and one column to `COPY`.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Locale {
+    Id,
+    En,
+}
+
+impl Locale {
+    // None tetap memakai bahasa Indonesia.
+    pub fn parse(locale: Option<&str>) -> Result<Locale, String> {
+        match locale.map(|value| value.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("id") => Ok(Locale::Id),
+            Some("en") => Ok(Locale::En),
+            Some(other) => Err(format!("unsupported locale '{}': use 'id' or 'en'", other)),
+        }
+    }
+
+    pub fn thousands_separator(self) -> char {
+        match self {
+            Locale::Id => '.',
+            Locale::En => ',',
+        }
+    }
+
+    pub fn decimal_separator(self) -> char {
+        match self {
+            Locale::Id => ',',
+            Locale::En => '.',
+        }
+    }
+}
+
+struct CopyEntry {
+    key: &'static str,
+    id: &'static str,
+    en: &'static str,
+}
+
+const COPY: &[CopyEntry] = &[
+    CopyEntry {
+        key: "common.recommended_today",
+        id: "Rekomendasi hari ini {0}.",
+        en: "Today's recommendation is {0}.",
+    },
+    CopyEntry {
+        key: "common.today_out",
+        id: "Pengeluaran hari ini {0}.",
+        en: "Spent today: {0}.",
+    },
+    CopyEntry {
+        key: "common.avg_out_7d",
+        id: "Rata-rata pengeluaran 7 hari {0} per hari.",
+        en: "7-day average spending: {0} per day.",
+    },
+    CopyEntry {
+        key: "onboarding.status_title",
+        id: "Baru {0} transaksi, pelan-pelan bangun ritme.",
+        en: "Only {0} transactions so far; build the habit slowly.",
+    },
+    CopyEntry {
+        key: "onboarding.tx_total",
+        id: "Total catatan saat ini {0} transaksi.",
+        en: "You have {0} transactions recorded.",
+    },
+    CopyEntry {
+        key: "onboarding.next_step",
+        id: "Langkah kecil: catat 1 transaksi hari ini agar ritme terasa.",
+        en: "Small step: log 1 transaction today to feel the rhythm.",
+    },
+    CopyEntry {
+        key: "frozen.status_title",
+        id: "Belanja sedang dibekukan.",
+        en: "Spending is frozen.",
+    },
+    CopyEntry {
+        key: "frozen.held",
+        id: "Rekomendasi belanja ditahan di {0} selama mode beku aktif.",
+        en: "The spending recommendation is held at {0} while the freeze is on.",
+    },
+    CopyEntry {
+        key: "frozen.next_step",
+        id: "Tahan semua belanja yang tidak mendesak; cabut mode beku saat kondisi sudah lega.",
+        en: "Hold off on anything non-urgent; lift the freeze once things ease up.",
+    },
+    CopyEntry {
+        key: "deficit.status_title",
+        id: "Saldo bersih sedang minus {0}.",
+        en: "Net balance is negative by {0}.",
+    },
+    CopyEntry {
+        key: "deficit.out_vs_in",
+        id: "Pengeluaran tercatat {0} dari pemasukan {1}.",
+        en: "Recorded spending {0} against income {1}.",
+    },
+    CopyEntry {
+        key: "deficit.held",
+        id: "Rekomendasi belanja ditahan di {0} sampai saldo kembali positif.",
+        en: "The spending recommendation is held at {0} until the balance is positive again.",
+    },
+    CopyEntry {
+        key: "deficit.next_step",
+        id: "Langkah kecil: tunda belanja yang bisa ditunda dan catat pemasukan berikutnya begitu masuk.",
+        en: "Small step: postpone what can wait and log your next income as soon as it arrives.",
+    },
+    CopyEntry {
+        key: "overspent_today.status_title",
+        id: "Hari ini melewati batas {0}.",
+        en: "Today went past the {0} limit.",
+    },
+    CopyEntry {
+        key: "overspent_today.remaining",
+        id: "Sisa hari ini {0}.",
+        en: "Remaining today: {0}.",
+    },
+    CopyEntry {
+        key: "overspent_today.next_step.calm",
+        id: "Hari ini aman kalau tahan belanja tambahan; besok reset dengan target {0}.",
+        en: "Today stays okay if you hold off on extra spending; tomorrow resets to {0}.",
+    },
+    CopyEntry {
+        key: "overspent_today.next_step.watchful",
+        id: "Jika bisa, hentikan pengeluaran tambahan sampai besok.",
+        en: "If you can, stop extra spending until tomorrow.",
+    },
+    CopyEntry {
+        key: "overspent_today.next_step.strict",
+        id: "Stop belanja hari ini; catat apa pun yang terlanjur keluar.",
+        en: "Stop spending today; log anything that already went out.",
+    },
+    CopyEntry {
+        key: "buffer_reached.status_title",
+        id: "Penyangga tercapai. Kerja bagus!",
+        en: "Buffer reached. Nice work!",
+    },
+    CopyEntry {
+        key: "buffer_reached.balance_vs_target",
+        id: "Saldo {0} sudah melewati target penyangga {1}.",
+        en: "Balance {0} is now past the {1} buffer target.",
+    },
+    CopyEntry {
+        key: "buffer_reached.next_step",
+        id: "Pertahankan ritme ini; penyangga yang terjaga memberi ruang bernapas.",
+        en: "Keep this rhythm; a steady buffer gives you room to breathe.",
+    },
+    CopyEntry {
+        key: "no_spend_day.status_title",
+        id: "Hari tanpa belanja sedang berjalan.",
+        en: "No-spend day in progress.",
+    },
+    CopyEntry {
+        key: "no_spend_day.nothing_spent",
+        id: "Belum ada pengeluaran tercatat hari ini.",
+        en: "No spending recorded today yet.",
+    },
+    CopyEntry {
+        key: "no_spend_day.untouched",
+        id: "Rekomendasi hari ini {0} tetap utuh.",
+        en: "Today's recommendation of {0} is still untouched.",
+    },
+    CopyEntry {
+        key: "no_spend_day.next_step",
+        id: "Tahan sampai besok; kebutuhan kecil bisa menunggu.",
+        en: "Hold on until tomorrow; small needs can wait.",
+    },
+    CopyEntry {
+        key: "weekly_recap.status_title",
+        id: "Begini minggu lalu berjalan.",
+        en: "Here's how last week went.",
+    },
+    CopyEntry {
+        key: "weekly_recap.days_with_tx",
+        id: "Ada catatan di {0} dari 7 hari.",
+        en: "Something was logged on {0} of 7 days.",
+    },
+    CopyEntry {
+        key: "weekly_recap.next_step",
+        id: "Pilih satu kebiasaan kecil untuk dijaga minggu ini.",
+        en: "Pick one small habit to keep up this week.",
+    },
+    CopyEntry {
+        key: "no_tx_today.status_title",
+        id: "Belum ada catatan hari ini, 0 transaksi.",
+        en: "Nothing logged today yet, 0 transactions.",
+    },
+    CopyEntry {
+        key: "no_tx_today.next_step.morning",
+        id: "Kalau ada satu catatan kecil pagi ini, ritmenya lebih terasa.",
+        en: "One small entry this morning keeps the rhythm going.",
+    },
+    CopyEntry {
+        key: "no_tx_today.next_step.night",
+        id: "Hari ini sudah hampir selesai; besok kita mulai lagi.",
+        en: "The day is almost over; we start again tomorrow.",
+    },
+    CopyEntry {
+        key: "no_tx_today.next_step.default",
+        id: "Langkah kecil: catat 1 transaksi pertama hari ini.",
+        en: "Small step: log your first transaction today.",
+    },
+    CopyEntry {
+        key: "fixed_cost_unpaid.status_title",
+        id: "Ada {0} biaya tetap belum lunas bulan ini.",
+        en: "{0} fixed costs are still unpaid this month.",
+    },
+    CopyEntry {
+        key: "fixed_cost_unpaid.unpaid_total",
+        id: "Total belum lunas {0}.",
+        en: "Unpaid total: {0}.",
+    },
+    CopyEntry {
+        key: "fixed_cost_unpaid.net_balance",
+        id: "Saldo bersih {0}.",
+        en: "Net balance: {0}.",
+    },
+    CopyEntry {
+        key: "fixed_cost_unpaid.next_step",
+        id: "Langkah kecil: pilih 1 biaya tetap yang paling dekat jatuh tempo.",
+        en: "Small step: pick the 1 fixed cost closest to its due date.",
+    },
+    CopyEntry {
+        key: "fixed_cost_unpaid.next_step.overdue",
+        id: "Dahulukan {0} biaya tetap yang sudah lewat jatuh tempo.",
+        en: "Settle the {0} overdue fixed costs first.",
+    },
+    CopyEntry {
+        key: "low_buffer.status_title",
+        id: "Penyangga belum aman, ketahanan {0} hari.",
+        en: "Buffer not safe yet, {0} days of resilience.",
+    },
+    CopyEntry {
+        key: "low_buffer.balance_vs_target",
+        id: "Saldo bersih {0} vs target {1}.",
+        en: "Net balance {0} vs target {1}.",
+    },
+    CopyEntry {
+        key: "low_buffer.next_step.calm",
+        id: "Hari ini aman kalau jaga pengeluaran di bawah {0}.",
+        en: "Today is fine if spending stays under {0}.",
+    },
+    CopyEntry {
+        key: "low_buffer.next_step.watchful",
+        id: "Prioritaskan kebutuhan inti; jaga pengeluaran di bawah {0}.",
+        en: "Prioritize essentials; keep spending under {0}.",
+    },
+    CopyEntry {
+        key: "low_buffer.next_step.strict",
+        id: "Hanya kebutuhan inti hari ini; jangan lewati {0}.",
+        en: "Essentials only today; do not go past {0}.",
+    },
+    CopyEntry {
+        key: "outpacing_income.status_title",
+        id: "Seminggu ini pengeluaran melampaui pemasukan.",
+        en: "Spending outpaced income this week.",
+    },
+    CopyEntry {
+        key: "outpacing_income.in_vs_out",
+        id: "Pemasukan 7 hari {0}, pengeluaran {1}.",
+        en: "Income over 7 days {0}, spending {1}.",
+    },
+    CopyEntry {
+        key: "outpacing_income.next_step.calm",
+        id: "Cek pengeluaran terbesar minggu ini; hari ini cukup di bawah {0}.",
+        en: "Review this week's biggest expenses; today, stay under {0}.",
+    },
+    CopyEntry {
+        key: "outpacing_income.next_step.watchful",
+        id: "Kurangi belanja yang bisa ditunda; jaga hari ini di bawah {0}.",
+        en: "Cut what can wait; keep today under {0}.",
+    },
+    CopyEntry {
+        key: "outpacing_income.next_step.strict",
+        id: "Hanya kebutuhan inti sampai pemasukan menyusul; jangan lewati {0}.",
+        en: "Essentials only until income catches up; do not go past {0}.",
+    },
+    CopyEntry {
+        key: "near_limit.status_title",
+        id: "Hampir menyentuh batas {0}.",
+        en: "Almost at the {0} limit.",
+    },
+    CopyEntry {
+        key: "near_limit.used",
+        id: "Sudah terpakai {0} hari ini.",
+        en: "{0} used so far today.",
+    },
+    CopyEntry {
+        key: "near_limit.left",
+        id: "Sisa {0} untuk hari ini.",
+        en: "{0} left for today.",
+    },
+    CopyEntry {
+        key: "near_limit.next_step.calm",
+        id: "Langkah kecil: kalau perlu belanja lagi, pilih yang paling penting di bawah {0}.",
+        en: "Small step: if you need to buy more, pick what matters most under {0}.",
+    },
+    CopyEntry {
+        key: "near_limit.next_step.watchful",
+        id: "Tekan belanja tambahan; sisa aman {0} untuk hari ini.",
+        en: "Cut extra spending; {0} is the safe remainder for today.",
+    },
+    CopyEntry {
+        key: "near_limit.next_step.strict",
+        id: "Cukup untuk hari ini; sisa {0} hanya untuk yang benar-benar wajib.",
+        en: "That's enough for today; keep the remaining {0} for true essentials only.",
+    },
+    CopyEntry {
+        key: "consistency_praise.status_title",
+        id: "Kamu konsisten {0} dari 7 hari.",
+        en: "You were consistent on {0} of the last 7 days.",
+    },
+    CopyEntry {
+        key: "consistency_praise.total_7d",
+        id: "Total pengeluaran 7 hari {0}.",
+        en: "7-day spending total: {0}.",
+    },
+    CopyEntry {
+        key: "consistency_praise.tx_total",
+        id: "Total transaksi tercatat {0}.",
+        en: "Total transactions recorded: {0}.",
+    },
+    CopyEntry {
+        key: "consistency_praise.next_step",
+        id: "Pertahankan: cukup 1 catatan per hari selama 2 hari lagi.",
+        en: "Keep it up: just 1 entry a day for 2 more days.",
+    },
+    CopyEntry {
+        key: "normal.status_title",
+        id: "Kondisi hari ini cukup stabil, saldo {0}.",
+        en: "Things look stable today, balance {0}.",
+    },
+    CopyEntry {
+        key: "normal.flexible",
+        id: "Dana fleksibel {0} di atas penyangga.",
+        en: "Flexible funds: {0} above the buffer.",
+    },
+    CopyEntry {
+        key: "normal.next_step",
+        id: "Langkah kecil: belanja aman jika tetap di bawah {0}.",
+        en: "Small step: spending is safe if it stays under {0}.",
+    },
+    CopyEntry {
+        key: "continuity.alert_to_calm",
+        id: "Kemarin sempat ketat, hari ini kita mulai lagi pelan-pelan.",
+        en: "Yesterday was tight; today we start again, slowly.",
+    },
+    CopyEntry {
+        key: "continuity.calm_to_alert",
+        id: "Hari ini lebih ketat dari kemarin. Kita jaga pelan-pelan.",
+        en: "Today is tighter than yesterday. Let's take it carefully.",
+    },
+    CopyEntry {
+        key: "continuity.first_open.morning",
+        id: "Pagi ini kita mulai pelan-pelan.",
+        en: "This morning we start slowly.",
+    },
+    CopyEntry {
+        key: "continuity.first_open.night",
+        id: "Hari ini hampir selesai, besok kita mulai lagi.",
+        en: "The day is nearly done; we start again tomorrow.",
+    },
+    CopyEntry {
+        key: "continuity.first_open.default",
+        id: "Hari ini kita mulai pelan-pelan.",
+        en: "Today we start slowly.",
+    },
+    CopyEntry {
+        key: "memory.reflection",
+        id: "Catatan terakhir: {0}.",
+        en: "Last note: {0}.",
+    },
+];
+
+// Kunci tak dikenal dikembalikan apa adanya supaya salah ketik terlihat di UI.
+pub fn text(locale: Locale, key: &str) -> &str {
+    match COPY.iter().find(|entry| entry.key == key) {
+        Some(entry) => match locale {
+            Locale::Id => entry.id,
+            Locale::En => entry.en,
+        },
+        None => key,
+    }
+}
+
+pub fn fill(template: &str, args: &[String]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (index, arg)| {
+            text.replace(&format!("{{{}}}", index), arg)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placeholders(template: &str) -> Vec<usize> {
+        (0..4)
+            .filter(|index| template.contains(&format!("{{{}}}", index)))
+            .collect()
+    }
+
+    #[test]
+    fn every_entry_uses_the_same_placeholders_in_each_locale() {
+        for entry in COPY {
+            assert_eq!(
+                placeholders(entry.id),
+                placeholders(entry.en),
+                "{}",
+                entry.key
+            );
+        }
+        let mut keys: Vec<&str> = COPY.iter().map(|entry| entry.key).collect();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), COPY.len());
+    }
+
+    #[test]
+    fn parse_defaults_to_indonesian() {
+        assert_eq!(Locale::parse(None), Ok(Locale::Id));
+        assert_eq!(Locale::parse(Some("EN")), Ok(Locale::En));
+        assert!(Locale::parse(Some("fr")).is_err());
+        assert_eq!(
+            fill(
+                text(Locale::En, "low_buffer.balance_vs_target"),
+                &["a".into(), "b".into()]
+            ),
+            "Net balance a vs target b."
+        );
+    }
+}
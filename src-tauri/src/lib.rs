@@ -1,10 +1,22 @@
+mod analytics;
+mod backup;
+mod clock;
+mod csv_import;
 mod db;
 mod insight;
+mod insight_copy;
+mod profiles;
+mod recurring_income;
+mod savings;
 
-use chrono::{Local, Utc};
-use rusqlite::{params, Connection, OptionalExtension};
+use std::collections::BTreeMap;
+
+use chrono::{Datelike, Duration, Months, NaiveDate, Utc};
+use rusqlite::{named_params, params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
+
+use crate::clock::{Clock, SystemClock};
 
 #[derive(Serialize)]
 struct Transaction {
@@ -16,6 +28,10 @@ struct Transaction {
     source: String,
     fixed_cost_id: Option<i64>,
     description: Option<String>,
+    category: Option<String>,
+    note: Option<String>,
+    // Hanya diisi saat input; peringatan salah ketik, transaksi tetap tersimpan.
+    warning: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -24,9 +40,15 @@ struct FixedCost {
     name: String,
     amount: i64,
     is_active: bool,
+    cadence: String,
     paid_date_local: Option<String>,
     paid_ts_utc: Option<i64>,
     paid_tx_id: Option<i64>,
+    paid_amount: Option<i64>,
+    paid_total: i64,
+    due_day: Option<u32>,
+    is_overdue: bool,
+    is_skipped: bool,
 }
 
 #[derive(Serialize)]
@@ -36,45 +58,228 @@ struct TodaySummary {
     today_remaining: i64,
     today_remaining_clamped: i64,
     overspent_today: bool,
+    spend_status: insight::SpendStatus,
 }
 
 #[derive(Serialize)]
-struct Config {
+pub(crate) struct Config {
     min_floor: i64,
     max_ceil: i64,
     resilience_days: i64,
+    income_smoothing_days: i64,
+    opening_balance: i64,
+    record_near_limit: bool,
+    near_limit_ratio: i64,
+    budget_granularity: String,
+    coach_mode: String,
+    currency_code: String,
+    minor_units: u32,
+    memory_retention_limit: i64,
+    large_amount_multiplier: i64,
+    recommend_rounding: i64,
+    spending_frozen: bool,
+    carryover_enabled: bool,
+    morning_start_hour: i64,
+    midday_start_hour: i64,
+    afternoon_start_hour: i64,
+    evening_start_hour: i64,
+    night_start_hour: i64,
+    memory_recording_enabled: bool,
+    max_ceil_mode: String,
+    max_ceil_percent: i64,
+    week_start_day: u32,
+    low_buffer_days: i64,
+    exclude_fixed_from_daily: bool,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Default)]
 struct ConfigPayload {
     min_floor: i64,
     max_ceil: i64,
     resilience_days: i64,
+    income_smoothing_days: Option<i64>,
+    record_near_limit: Option<bool>,
+    near_limit_ratio: Option<i64>,
+    budget_granularity: Option<String>,
+    currency_code: Option<String>,
+    minor_units: Option<u32>,
+    large_amount_multiplier: Option<i64>,
+    recommend_rounding: Option<i64>,
+    carryover_enabled: Option<bool>,
+    max_ceil_mode: Option<String>,
+    max_ceil_percent: Option<i64>,
+    week_start_day: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct ConfigWithDerived {
+    config: Config,
+    target_penyangga: i64,
+    dana_fleksibel: i64,
+    per_day_fleksibel: i64,
+    penyangga_tercapai: bool,
+}
+
+#[derive(Serialize)]
+struct SpendVsRecommended {
+    recommended: i64,
+    spent: i64,
+    remaining: i64,
+    ratio_percent: i64,
+}
+
+#[derive(Serialize)]
+struct FixedCostCoverage {
+    first_period: Option<String>,
+    months_elapsed: i64,
+    months_paid: i64,
+    months_skipped: i64,
+    coverage_ratio: i64,
+}
+
+#[derive(Serialize)]
+struct FixedCostCalendarEntry {
+    day: Option<u32>,
+    fixed_cost_id: i64,
+    name: String,
+    amount: i64,
+    // Masih harus dibayar pada periode ini (punya hari jatuh tempo, belum lunas, tidak dilewati).
+    due: bool,
+    paid: bool,
+    is_skipped: bool,
+    is_overdue: bool,
+}
+
+#[derive(Serialize)]
+struct RecommendedSpendForDate {
+    date_local: String,
+    net_balance: i64,
+    recommended_spend: i64,
+}
+
+#[derive(Serialize)]
+struct BufferProgress {
+    net_balance: i64,
+    target_penyangga: i64,
+    progress_ratio: i64,
+    amount_remaining: i64,
 }
 
 #[derive(Serialize)]
+struct ConfigHistoryEntry {
+    id: i64,
+    changed_ts_utc: i64,
+    change_source: String,
+    old_config: serde_json::Value,
+    new_config: serde_json::Value,
+    reason: Option<String>,
+}
+
+#[derive(Serialize, Clone)]
 pub(crate) struct PoolsSummary {
     total_in: i64,
     total_out: i64,
+    opening_balance: i64,
     net_balance: i64,
+    net_balance_negative: bool,
     min_floor: i64,
     max_ceil: i64,
     resilience_days: i64,
     target_penyangga: i64,
     dana_fleksibel: i64,
+    income_deferred: i64,
+    savings_reserved: i64,
+    budget_granularity: String,
+    // Mode mingguan: rekomendasi, today_out dan sisa dihitung untuk minggu ISO berjalan.
     recommended_spend_today: i64,
     today_out: i64,
+    // Sisa anggaran hari-hari sebelumnya (carryover_enabled); sudah termasuk di today_remaining.
+    carried_over: i64,
     today_remaining: i64,
     today_remaining_clamped: i64,
     overspent_today: bool,
+    spend_status: insight::SpendStatus,
     hari_ketahanan_stop_pemasukan: i64,
 }
-fn resolve_date_local(date_local: Option<String>) -> String {
-    date_local.unwrap_or_else(|| Local::now().format("%Y-%m-%d").to_string())
+
+#[derive(Serialize)]
+struct NoSpendDayResult {
+    date_local: String,
+    total_out: i64,
+    success: bool,
+    in_progress: bool,
+}
+
+#[derive(Serialize)]
+struct MonthlySummary {
+    period_ym: String,
+    total_in: i64,
+    total_out: i64,
+    net: i64,
+}
+
+fn resolve_date_local(date_local: Option<String>, clock: &dyn Clock) -> String {
+    date_local.unwrap_or_else(|| clock.today_local())
+}
+
+pub(crate) fn parse_date_local(date_local: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date_local, "%Y-%m-%d")
+        .map_err(|err| format!("invalid date_local: {}", err))
+}
+
+const CADENCE_MONTHLY: &str = "monthly";
+const CADENCE_WEEKLY: &str = "weekly";
+const CADENCE_YEARLY: &str = "yearly";
+
+fn validate_cadence(cadence: &str) -> Result<(), String> {
+    match cadence {
+        CADENCE_MONTHLY | CADENCE_WEEKLY | CADENCE_YEARLY => Ok(()),
+        _ => Err("cadence must be 'monthly', 'weekly' or 'yearly'".to_string()),
+    }
+}
+
+pub(crate) fn period_key_for_date(cadence: &str, date: NaiveDate) -> String {
+    match cadence {
+        CADENCE_WEEKLY => date.format("%G-W%V").to_string(),
+        CADENCE_YEARLY => date.format("%Y").to_string(),
+        _ => date.format("%Y-%m").to_string(),
+    }
+}
+
+// Bulan berjalan dinilai pada `today`, bulan lalu pada hari terakhir, bulan depan pada hari pertama.
+pub(crate) fn period_reference_date(
+    period_ym: &str,
+    today: NaiveDate,
+) -> Result<NaiveDate, String> {
+    let bytes = period_ym.as_bytes();
+    let well_formed = bytes.len() == 7
+        && bytes[4] == b'-'
+        && bytes
+            .iter()
+            .enumerate()
+            .all(|(index, byte)| index == 4 || byte.is_ascii_digit());
+    if !well_formed {
+        return Err("invalid period_ym: expected YYYY-MM".to_string());
+    }
+    let first = NaiveDate::parse_from_str(&format!("{}-01", period_ym), "%Y-%m-%d")
+        .map_err(|err| format!("invalid period_ym: {}", err))?;
+    let last = first
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| "invalid period_ym".to_string())?;
+    Ok(if today >= first && today <= last {
+        today
+    } else if last < today {
+        last
+    } else {
+        first
+    })
 }
 
-fn period_ym_from_date(date_local: &str) -> String {
-    date_local.get(0..7).unwrap_or(date_local).to_string()
+// week_start_day: 0 = Minggu .. 6 = Sabtu.
+pub(crate) fn week_start_for(date: NaiveDate, week_start_day: u32) -> NaiveDate {
+    let days_back = (date.weekday().num_days_from_sunday() + 7 - week_start_day % 7) % 7;
+    date - Duration::days(i64::from(days_back))
 }
 
 fn clamp_i64(value: i64, min: i64, max: i64) -> i64 {
@@ -87,41 +292,66 @@ fn clamp_i64(value: i64, min: i64, max: i64) -> i64 {
     }
 }
 
-fn floor_to_thousand(value: i64) -> i64 {
+// granularity 0 berarti tanpa pembulatan.
+fn floor_to_granularity(value: i64, granularity: i64) -> i64 {
     if value <= 0 {
         0
+    } else if granularity <= 0 {
+        value
     } else {
-        (value / 1_000) * 1_000
+        (value / granularity) * granularity
+    }
+}
+
+fn is_valid_rounding(granularity: i64) -> bool {
+    if granularity == 0 {
+        return true;
+    }
+    let mut value = granularity;
+    while value > 1 && value % 10 == 0 {
+        value /= 10;
     }
+    value == 1
 }
 
 pub(crate) fn compute_pools_summary(conn: &Connection) -> Result<PoolsSummary, String> {
-    let config = fetch_config(conn)?;
+    compute_pools_summary_with_clock(conn, &SystemClock)
+}
 
-    let total_in: i64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE kind = 'IN'",
-            [],
-            |row| row.get(0),
-        )
-        .map_err(|err| err.to_string())?;
-    let total_out: i64 = conn
-        .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE kind = 'OUT'",
-            [],
-            |row| row.get(0),
-        )
-        .map_err(|err| err.to_string())?;
+pub(crate) fn compute_pools_summary_with_clock(
+    conn: &Connection,
+    clock: &dyn Clock,
+) -> Result<PoolsSummary, String> {
+    compute_pools_summary_for_date(conn, clock.today())
+}
+
+pub(crate) struct DailyRecommendation {
+    pub(crate) target_penyangga: i64,
+    pub(crate) dana_fleksibel: i64,
+    pub(crate) per_day_fleksibel: i64,
+    pub(crate) penyangga_tercapai: bool,
+    pub(crate) recommended_daily: i64,
+}
 
-    let net_balance = total_in - total_out;
+// Dipakai ringkasan untuk saldo sekarang dan riwayat (streak) untuk saldo akhir hari lampau.
+pub(crate) fn daily_recommendation(
+    config: &Config,
+    net_balance: i64,
+    income_deferred: i64,
+    savings_reserved: i64,
+) -> DailyRecommendation {
     // resilience_days berperan ganda: target penyangga dan horizon pembagian dana fleksibel.
+    // 0 berarti tanpa penyangga: seluruh dana fleksibel boleh dipakai (tetap dibatasi max_ceil).
     let target_penyangga = config.min_floor * config.resilience_days;
-    let dana_fleksibel = std::cmp::max(0, net_balance - target_penyangga);
+    let dana_fleksibel = std::cmp::max(
+        0,
+        net_balance - income_deferred - savings_reserved - target_penyangga,
+    );
 
     let per_day_fleksibel = if config.resilience_days > 0 {
         dana_fleksibel / config.resilience_days
     } else {
-        0
+        dana_fleksibel
     };
     let penyangga_tercapai = net_balance >= target_penyangga;
     let recommended_spend_today_raw = if penyangga_tercapai {
@@ -134,27 +364,115 @@ pub(crate) fn compute_pools_summary(conn: &Connection) -> Result<PoolsSummary, S
     } else {
         0
     };
-    let clamped = clamp_i64(recommended_spend_today_raw, min_bound, config.max_ceil);
-    // Rounded for UX; if min_floor isn't a round thousand, keep min_floor when penyangga tercapai.
-    let rounded = floor_to_thousand(clamped);
-    let recommended_spend_today = if penyangga_tercapai {
+    let clamped = clamp_i64(
+        recommended_spend_today_raw,
+        min_bound,
+        effective_max_ceil(config, net_balance),
+    );
+    // Rounded for UX; if min_floor isn't a round number, keep min_floor when penyangga tercapai.
+    let rounded = floor_to_granularity(clamped, config.recommend_rounding);
+    let recommended_daily = if penyangga_tercapai {
         std::cmp::max(min_bound, rounded)
     } else {
         rounded
     };
 
-    let today_local = Local::now().format("%Y-%m-%d").to_string();
+    DailyRecommendation {
+        target_penyangga,
+        dana_fleksibel,
+        per_day_fleksibel,
+        penyangga_tercapai,
+        recommended_daily,
+    }
+}
+
+// Dibekukan berarti 0; mode mingguan menganggarkan tujuh hari sekaligus.
+fn period_recommendation(config: &Config, recommended_daily: i64) -> i64 {
+    // Mode beku mengabaikan hitungan penyangga sampai dicabut lagi.
+    if config.spending_frozen {
+        0
+    } else if config.budget_granularity == BUDGET_WEEKLY {
+        recommended_daily * 7
+    } else {
+        recommended_daily
+    }
+}
+
+fn transaction_totals(
+    conn: &Connection,
+    through_date_local: Option<&str>,
+) -> Result<(i64, i64), String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(CASE WHEN kind = 'IN' THEN amount END), 0),
+                COALESCE(SUM(CASE WHEN kind = 'OUT' THEN amount END), 0)
+         FROM transactions
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND (?1 IS NULL OR date_local <= ?1)",
+        [through_date_local],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )
+    .map_err(|err| err.to_string())
+}
+
+// `today` menggantikan tanggal sekarang; today_* menggambarkan hari itu (atau minggunya).
+pub(crate) fn compute_pools_summary_for_date(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<PoolsSummary, String> {
+    let config = fetch_config(conn)?;
+
+    let (total_in, total_out) = transaction_totals(conn, None)?;
+
+    let today_local = today.format("%Y-%m-%d").to_string();
+    // opening_balance adalah saldo awal (baseline), bukan pemasukan: ikut ke net_balance
+    // tapi tidak ke total_in maupun perataan pemasukan.
+    let net_balance = config.opening_balance + total_in - total_out;
+    let income_deferred =
+        compute_income_deferred(conn, &today_local, config.income_smoothing_days)?;
+    let savings_reserved = savings::reserved_amount(conn)?;
+    let DailyRecommendation {
+        target_penyangga,
+        dana_fleksibel,
+        recommended_daily,
+        ..
+    } = daily_recommendation(&config, net_balance, income_deferred, savings_reserved);
+    let recommended_spend_today = period_recommendation(&config, recommended_daily);
+
+    let weekly = config.budget_granularity == BUDGET_WEEKLY;
+    let (period_start, period_end) = if weekly {
+        let week_start = week_start_for(today, config.week_start_day);
+        (week_start, week_start + Duration::days(6))
+    } else {
+        (today, today)
+    };
+
     let today_out: i64 = conn
         .query_row(
-            "SELECT COALESCE(SUM(amount), 0) FROM transactions WHERE kind = 'OUT' AND date_local = ?1",
-            [today_local],
+            // Setoran tabungan bukan belanja harian; biaya tetap opsional ikut dikecualikan.
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND source != 'savings' AND date_local >= ?1 AND date_local <= ?2
+               AND (?3 = 0 OR source != 'fixed_cost')",
+            params![
+                period_start.format("%Y-%m-%d").to_string(),
+                period_end.format("%Y-%m-%d").to_string(),
+                config.exclude_fixed_from_daily
+            ],
             |row| row.get(0),
         )
         .map_err(|err| err.to_string())?;
 
-    let today_remaining = recommended_spend_today - today_out;
+    let carried_over = compute_carried_over(conn, &config, today)?;
+    // Sisa kemarin ikut jatah hari ini, jadi status dan sisa memakai batas yang sama.
+    let available_today = recommended_spend_today + carried_over;
+    let today_remaining = available_today - today_out;
     let today_remaining_clamped = std::cmp::max(0, today_remaining);
-    let overspent_today = today_out > recommended_spend_today;
+    let overspent_today = today_remaining < 0;
+    let spend_status = insight::spend_status(
+        today_out,
+        available_today,
+        insight::effective_near_limit_ratio(config.near_limit_ratio, &config.coach_mode),
+    );
 
     let hari_ketahanan_stop_pemasukan = if config.min_floor > 0 {
         std::cmp::max(0, net_balance.div_euclid(config.min_floor))
@@ -165,47 +483,360 @@ pub(crate) fn compute_pools_summary(conn: &Connection) -> Result<PoolsSummary, S
     Ok(PoolsSummary {
         total_in,
         total_out,
+        opening_balance: config.opening_balance,
         net_balance,
+        net_balance_negative: net_balance < 0,
         min_floor: config.min_floor,
         max_ceil: config.max_ceil,
         resilience_days: config.resilience_days,
         target_penyangga,
         dana_fleksibel,
+        income_deferred,
+        savings_reserved,
+        budget_granularity: config.budget_granularity,
         recommended_spend_today,
         today_out,
+        carried_over,
         today_remaining,
         today_remaining_clamped,
         overspent_today,
+        spend_status,
         hari_ketahanan_stop_pemasukan,
     })
 }
 
-fn cleanup_fixed_cost_payments(conn: &Connection) -> Result<(), String> {
-    conn.execute("DELETE FROM fixed_cost_payments WHERE tx_id IS NULL", [])
+// Sisa rekomendasi (minimal 0 per hari) dari resilience_days hari sebelumnya; hanya mode harian.
+fn compute_carried_over(
+    conn: &Connection,
+    config: &Config,
+    today: NaiveDate,
+) -> Result<i64, String> {
+    if !config.carryover_enabled
+        || config.spending_frozen
+        || config.budget_granularity == BUDGET_WEEKLY
+        || config.resilience_days <= 0
+    {
+        return Ok(0);
+    }
+    let yesterday = today
+        .pred_opt()
+        .ok_or_else(|| "date underflow".to_string())?;
+    let mut carried = 0;
+    let mut days_left = config.resilience_days;
+    analytics::walk_daily_budgets(conn, config, yesterday, |_, spend, recommended| {
+        carried += std::cmp::max(0, recommended - spend);
+        days_left -= 1;
+        days_left > 0
+    })?;
+    Ok(carried)
+}
+
+const BUDGET_DAILY: &str = "daily";
+const BUDGET_WEEKLY: &str = "weekly";
+const MAX_CEIL_ABSOLUTE: &str = "absolute";
+const MAX_CEIL_PERCENT: &str = "percent";
+
+// Mode persen: bagian dari net_balance, tidak pernah di bawah 0.
+fn effective_max_ceil(config: &Config, net_balance: i64) -> i64 {
+    if config.max_ceil_mode == MAX_CEIL_PERCENT {
+        std::cmp::max(0, net_balance.saturating_mul(config.max_ceil_percent) / 100)
+    } else {
+        config.max_ceil
+    }
+}
+
+const LOW_BUFFER_DAYS_MIN: i64 = 1;
+const LOW_BUFFER_DAYS_MAX: i64 = 60;
+
+const MEMORY_RETENTION_MIN: i64 = 20;
+const MEMORY_RETENTION_MAX: i64 = 5_000;
+
+const POOLS_UPDATED_EVENT: &str = "pools-updated";
+const OVERSPEND_TRIGGERED_EVENT: &str = "overspend-triggered";
+
+// Best-effort: mutasi sudah tersimpan, jadi gagal hitung ulang atau emit tidak dianggap error.
+fn emit_pools_updated(app: &AppHandle, conn: &Connection) {
+    if let Ok(summary) = compute_pools_summary(conn) {
+        let _ = app.emit(POOLS_UPDATED_EVENT, summary);
+    }
+}
+
+// Hanya saat perubahan ini yang membuat hari ini lewat anggaran.
+fn overspend_triggered(overspent_before: bool, after: &PoolsSummary) -> bool {
+    !overspent_before && after.overspent_today
+}
+
+// Seperti emit_pools_updated, ditambah event transisi overspend untuk notifikasi native.
+fn emit_pools_updated_after_expense(app: &AppHandle, conn: &Connection, overspent_before: bool) {
+    if let Ok(summary) = compute_pools_summary(conn) {
+        let _ = app.emit(POOLS_UPDATED_EVENT, &summary);
+        if overspend_triggered(overspent_before, &summary) {
+            let _ = app.emit(OVERSPEND_TRIGGERED_EVENT, summary);
+        }
+    }
+}
+
+// Pemasukan dianggap cair bertahap selama `smoothing_days` hari: hari gajian baru 1/N,
+// sisanya ditahan agar rekomendasi tidak melonjak di hari yang sama.
+pub(crate) fn compute_income_deferred(
+    conn: &Connection,
+    today_local: &str,
+    smoothing_days: i64,
+) -> Result<i64, String> {
+    if smoothing_days <= 1 {
+        return Ok(0);
+    }
+    let today = parse_date_local(today_local)?;
+    let window_start = today
+        .checked_sub_signed(Duration::days(smoothing_days - 1))
+        .ok_or_else(|| "date underflow".to_string())?
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT date_local, COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'IN' AND date_local >= ?1 AND date_local <= ?2
+             GROUP BY date_local",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![window_start, today_local], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+        })
         .map_err(|err| err.to_string())?;
+
+    let mut deferred = 0;
+    for row in rows {
+        let (date_local, amount) = row.map_err(|err| err.to_string())?;
+        let date = parse_date_local(&date_local)?;
+        let elapsed = (today - date).num_days();
+        let released_days = elapsed + 1;
+        if released_days < smoothing_days {
+            deferred += amount * (smoothing_days - released_days) / smoothing_days;
+        }
+    }
+    Ok(deferred)
+}
+
+// Pembayaran tanpa transaksi biaya tetap yang cocok; dipakai cleanup dan check_integrity.
+// Penanda periode dilewati memang tidak punya transaksi.
+const ORPHANED_PAYMENT_CONDITION: &str = "is_skipped = 0 AND (
+   tx_id IS NULL
+   OR tx_id NOT IN (
+     SELECT id FROM transactions
+     WHERE kind = 'OUT' AND source = 'fixed_cost'
+       AND fixed_cost_id = fixed_cost_payments.fixed_cost_id
+   ))";
+
+fn cleanup_fixed_cost_payments(conn: &Connection) -> Result<(), String> {
     conn.execute(
-        "DELETE FROM fixed_cost_payments
-         WHERE tx_id IS NOT NULL
-           AND tx_id NOT IN (
-             SELECT id FROM transactions
-             WHERE kind = 'OUT' AND source = 'fixed_cost'
-               AND fixed_cost_id = fixed_cost_payments.fixed_cost_id
-           )",
+        &format!(
+            "DELETE FROM fixed_cost_payments WHERE {}",
+            ORPHANED_PAYMENT_CONDITION
+        ),
         [],
     )
     .map_err(|err| err.to_string())?;
     Ok(())
 }
 
-fn fetch_config(conn: &Connection) -> Result<Config, String> {
+#[derive(Serialize)]
+struct DataStats {
+    total_transactions: i64,
+    total_income_count: i64,
+    total_expense_count: i64,
+    earliest_date_local: Option<String>,
+    latest_date_local: Option<String>,
+    fixed_cost_count: i64,
+    memory_entry_count: i64,
+}
+
+#[derive(Serialize)]
+struct IntegrityReport {
+    orphaned_payment_ids: Vec<i64>,
+    unpaid_fixed_cost_tx_ids: Vec<i64>,
+    missing_fixed_cost_payment_ids: Vec<i64>,
+    is_clean: bool,
+}
+
+fn query_ids(conn: &Connection, sql: &str) -> Result<Vec<i64>, String> {
+    let mut stmt = conn.prepare(sql).map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    let mut ids = Vec::new();
+    for row in rows {
+        ids.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(ids)
+}
+
+// Versi baca-saja dari cleanup_fixed_cost_payments, plus cek yang tidak diperbaiki.
+fn check_integrity_with_conn(conn: &Connection) -> Result<IntegrityReport, String> {
+    let orphaned_payment_ids = query_ids(
+        conn,
+        &format!(
+            "SELECT id FROM fixed_cost_payments
+             WHERE fixed_cost_id IN (
+               SELECT id FROM fixed_costs
+               WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ) AND {}
+             ORDER BY id",
+            ORPHANED_PAYMENT_CONDITION
+        ),
+    )?;
+    let unpaid_fixed_cost_tx_ids = query_ids(
+        conn,
+        "SELECT id FROM transactions
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND source = 'fixed_cost'
+           AND id NOT IN (SELECT tx_id FROM fixed_cost_payments WHERE tx_id IS NOT NULL)
+         ORDER BY id",
+    )?;
+    // Induknya sudah hilang, jadi profilnya tidak bisa diketahui; dilaporkan di semua profil.
+    let missing_fixed_cost_payment_ids = query_ids(
+        conn,
+        "SELECT id FROM fixed_cost_payments
+         WHERE fixed_cost_id NOT IN (SELECT id FROM fixed_costs)
+         ORDER BY id",
+    )?;
+    let is_clean = orphaned_payment_ids.is_empty()
+        && unpaid_fixed_cost_tx_ids.is_empty()
+        && missing_fixed_cost_payment_ids.is_empty();
+    Ok(IntegrityReport {
+        orphaned_payment_ids,
+        unpaid_fixed_cost_tx_ids,
+        missing_fixed_cost_payment_ids,
+        is_clean,
+    })
+}
+
+// MIN/MAX atas tabel kosong menghasilkan NULL, jadi DB kosong cukup jadi None.
+fn data_stats_with_conn(conn: &Connection) -> Result<DataStats, String> {
+    let (total_transactions, total_income_count, total_expense_count, earliest, latest) = conn
+        .query_row(
+            "SELECT COUNT(*),
+                    COALESCE(SUM(kind = 'IN'), 0),
+                    COALESCE(SUM(kind = 'OUT'), 0),
+                    MIN(date_local),
+                    MAX(date_local)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+            [],
+            |row| {
+                Ok((
+                    row.get(0)?,
+                    row.get(1)?,
+                    row.get(2)?,
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            },
+        )
+        .map_err(|err| err.to_string())?;
+    let fixed_cost_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM fixed_costs
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    let memory_entry_count: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM coaching_memory
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(DataStats {
+        total_transactions,
+        total_income_count,
+        total_expense_count,
+        earliest_date_local: earliest,
+        latest_date_local: latest,
+        fixed_cost_count,
+        memory_entry_count,
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_profiles(app: AppHandle) -> Result<Vec<profiles::Profile>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    profiles::list_profiles(&conn)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn create_profile(app: AppHandle, name: String) -> Result<profiles::Profile, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    profiles::create_profile(&mut conn, &name)
+}
+
+// Query membaca profil aktif dari app_state, jadi command berikutnya ikut berpindah.
+#[tauri::command(rename_all = "snake_case")]
+fn switch_profile(app: AppHandle, profile_id: i64) -> Result<profiles::Profile, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let profile = profiles::set_active_profile(&conn, profile_id)?;
+    emit_pools_updated(&app, &conn);
+    Ok(profile)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_data_stats(app: AppHandle) -> Result<DataStats, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    data_stats_with_conn(&conn)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn check_integrity(app: AppHandle) -> Result<IntegrityReport, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    check_integrity_with_conn(&conn)
+}
+
+pub(crate) fn fetch_config(conn: &Connection) -> Result<Config, String> {
     conn.query_row(
-        "SELECT min_floor, max_ceil, resilience_days FROM config WHERE id = 1",
+        "SELECT min_floor, max_ceil, resilience_days, income_smoothing_days, opening_balance,
+                record_near_limit, near_limit_ratio, budget_granularity, coach_mode,
+                currency_code, minor_units, memory_retention_limit, large_amount_multiplier,
+                recommend_rounding, spending_frozen, carryover_enabled,
+                morning_start_hour, midday_start_hour, afternoon_start_hour,
+                evening_start_hour, night_start_hour, memory_recording_enabled,
+                max_ceil_mode, max_ceil_percent, week_start_day, low_buffer_days,
+                exclude_fixed_from_daily
+         FROM config WHERE profile_id = (SELECT active_profile_id FROM app_state)",
         [],
         |row| {
             Ok(Config {
                 min_floor: row.get(0)?,
                 max_ceil: row.get(1)?,
                 resilience_days: row.get(2)?,
+                income_smoothing_days: row.get(3)?,
+                opening_balance: row.get(4)?,
+                record_near_limit: row.get::<_, i64>(5)? != 0,
+                near_limit_ratio: row.get(6)?,
+                budget_granularity: row.get(7)?,
+                coach_mode: row.get(8)?,
+                currency_code: row.get(9)?,
+                minor_units: row.get(10)?,
+                memory_retention_limit: row.get(11)?,
+                large_amount_multiplier: row.get(12)?,
+                recommend_rounding: row.get(13)?,
+                spending_frozen: row.get::<_, i64>(14)? != 0,
+                carryover_enabled: row.get::<_, i64>(15)? != 0,
+                morning_start_hour: row.get(16)?,
+                midday_start_hour: row.get(17)?,
+                afternoon_start_hour: row.get(18)?,
+                evening_start_hour: row.get(19)?,
+                night_start_hour: row.get(20)?,
+                memory_recording_enabled: row.get::<_, i64>(21)? != 0,
+                max_ceil_mode: row.get(22)?,
+                max_ceil_percent: row.get(23)?,
+                week_start_day: row.get(24)?,
+                low_buffer_days: row.get(25)?,
+                exclude_fixed_from_daily: row.get::<_, i64>(26)? != 0,
             })
         },
     )
@@ -214,37 +845,93 @@ fn fetch_config(conn: &Connection) -> Result<Config, String> {
 
 fn fetch_fixed_cost_amount(conn: &Connection, fixed_cost_id: i64) -> Result<i64, String> {
     conn.query_row(
-        "SELECT amount FROM fixed_costs WHERE id = ?1",
+        "SELECT amount FROM fixed_costs
+         WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+        [fixed_cost_id],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+fn fetch_fixed_cost_cadence(conn: &Connection, fixed_cost_id: i64) -> Result<String, String> {
+    conn.query_row(
+        "SELECT cadence FROM fixed_costs
+         WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
         [fixed_cost_id],
         |row| row.get(0),
     )
     .map_err(|err| err.to_string())
 }
 
+fn fixed_cost_from_row(row: &rusqlite::Row) -> rusqlite::Result<FixedCost> {
+    let active: i64 = row.get(3)?;
+    let amount: i64 = row.get(2)?;
+    let paid_total: i64 = row.get(9)?;
+    let mut paid_date_local: Option<String> = row.get(5)?;
+    let mut paid_ts_utc: Option<i64> = row.get(6)?;
+    // Lunas lewat cicilan: tampilkan tanggal cicilan terakhir sebagai tanggal lunas.
+    if paid_date_local.is_none() && paid_total > 0 && paid_total >= amount {
+        paid_date_local = row.get(10)?;
+        paid_ts_utc = row.get(11)?;
+    }
+    Ok(FixedCost {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        amount,
+        is_active: active != 0,
+        cadence: row.get(4)?,
+        paid_date_local,
+        paid_ts_utc,
+        paid_tx_id: row.get(7)?,
+        paid_amount: row.get(8)?,
+        paid_total,
+        due_day: row.get(12)?,
+        is_overdue: false,
+        is_skipped: row.get(13)?,
+    })
+}
+
+// Hanya biaya bulanan yang punya tanggal jatuh tempo.
+fn is_fixed_cost_overdue(cost: &FixedCost, today: NaiveDate) -> bool {
+    cost.is_active
+        && !cost.is_skipped
+        && cost.cadence == CADENCE_MONTHLY
+        && cost.paid_date_local.is_none()
+        && cost.due_day.is_some_and(|due_day| today.day() > due_day)
+}
+
+fn validate_due_day(due_day: Option<u32>) -> Result<(), String> {
+    if due_day.is_some_and(|day| !(1..=31).contains(&day)) {
+        return Err("due_day must be between 1 and 31".to_string());
+    }
+    Ok(())
+}
+
 fn fetch_fixed_cost_for_period(
     conn: &Connection,
     fixed_cost_id: i64,
     period_ym: &str,
 ) -> Result<FixedCost, String> {
     conn.query_row(
-        "SELECT fc.id, fc.name, fc.amount, fc.is_active, p.paid_date_local, p.paid_ts_utc, p.tx_id
-         FROM fixed_costs fc
+        "SELECT fc.id, fc.name, fc.amount, fc.is_active, fc.cadence, p.paid_date_local, p.paid_ts_utc, p.tx_id,
+                t.amount,
+                (SELECT COALESCE(SUM(pt.amount), 0) FROM fixed_cost_payments pp
+                   JOIN transactions pt ON pt.id = pp.tx_id
+                  WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key),
+                (SELECT pp.paid_date_local FROM fixed_cost_payments pp
+                  WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key AND pp.is_partial = 1
+                  ORDER BY pp.paid_ts_utc DESC, pp.id DESC LIMIT 1),
+                (SELECT MAX(pp.paid_ts_utc) FROM fixed_cost_payments pp
+                  WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key AND pp.is_partial = 1),
+                fc.due_day,
+                COALESCE(p.is_skipped, 0)
+         FROM (SELECT *, ?1 AS period_key FROM fixed_costs) fc
          LEFT JOIN fixed_cost_payments p
-           ON p.fixed_cost_id = fc.id AND p.period_ym = ?1
-         WHERE fc.id = ?2",
+           ON p.fixed_cost_id = fc.id AND p.period_ym = fc.period_key AND p.is_partial = 0
+         LEFT JOIN transactions t ON t.id = p.tx_id
+         WHERE fc.id = ?2 AND fc.profile_id = (SELECT active_profile_id FROM app_state)",
         params![period_ym, fixed_cost_id],
-        |row| {
-            let active: i64 = row.get(3)?;
-            Ok(FixedCost {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                amount: row.get(2)?,
-                is_active: active != 0,
-                paid_date_local: row.get(4)?,
-                paid_ts_utc: row.get(5)?,
-                paid_tx_id: row.get(6)?,
-            })
-        },
+        fixed_cost_from_row,
     )
     .map_err(|err| err.to_string())
 }
@@ -253,11 +940,14 @@ fn resolve_period_for_unpaid(
     conn: &Connection,
     fixed_cost_id: i64,
     paid_date_local: Option<String>,
+    clock: &dyn Clock,
 ) -> Result<String, String> {
-    let desired_period = paid_date_local
-        .as_deref()
-        .map(period_ym_from_date)
-        .unwrap_or_else(|| Local::now().format("%Y-%m").to_string());
+    let cadence = fetch_fixed_cost_cadence(conn, fixed_cost_id)?;
+    let desired_date = match paid_date_local.as_deref() {
+        Some(date_local) => parse_date_local(date_local)?,
+        None => clock.today(),
+    };
+    let desired_period = period_key_for_date(&cadence, desired_date);
 
     let existing: Option<String> = conn
         .query_row(
@@ -288,24 +978,22 @@ fn mark_fixed_cost_unpaid_with_conn(
     conn: &mut Connection,
     fixed_cost_id: i64,
     paid_date_local: Option<String>,
+    clock: &dyn Clock,
 ) -> Result<FixedCost, String> {
-    let paid_date_local = paid_date_local.map(|value| resolve_date_local(Some(value)));
-    let period_ym = resolve_period_for_unpaid(conn, fixed_cost_id, paid_date_local)?;
+    let paid_date_local = paid_date_local.map(|value| resolve_date_local(Some(value), clock));
+    let period_ym = resolve_period_for_unpaid(conn, fixed_cost_id, paid_date_local, clock)?;
     let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let snapshot = snapshot_fixed_cost_period(&tx, fixed_cost_id, &period_ym)?;
 
-    let tx_id: Option<Option<i64>> = tx
-        .query_row(
-            "SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
-            params![fixed_cost_id, &period_ym],
-            |row| row.get(0),
-        )
-        .optional()
-        .map_err(|err| err.to_string())?;
-
-    if let Some(Some(tx_id)) = tx_id {
-        tx.execute("DELETE FROM transactions WHERE id = ?1", params![tx_id])
-            .map_err(|err| err.to_string())?;
-    }
+    // Termasuk cicilan: membatalkan berarti seluruh pembayaran periode ini dihapus.
+    tx.execute(
+        "DELETE FROM transactions WHERE id IN (
+           SELECT tx_id FROM fixed_cost_payments
+           WHERE fixed_cost_id = ?1 AND period_ym = ?2 AND tx_id IS NOT NULL
+         )",
+        params![fixed_cost_id, &period_ym],
+    )
+    .map_err(|err| err.to_string())?;
 
     tx.execute(
         "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
@@ -313,822 +1001,5638 @@ fn mark_fixed_cost_unpaid_with_conn(
     )
     .map_err(|err| err.to_string())?;
 
+    record_fixed_cost_action(
+        &tx,
+        FIXED_COST_ACTION_UNPAID,
+        fixed_cost_id,
+        &period_ym,
+        &snapshot,
+    )?;
     tx.commit().map_err(|err| err.to_string())?;
 
     fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym)
 }
 
-fn delete_transaction_with_conn(conn: &mut Connection, transaction_id: i64) -> Result<(), String> {
-    if transaction_id <= 0 {
-        return Err("ID transaksi tidak valid".to_string());
-    }
-    let tx = conn.transaction().map_err(|err| err.to_string())?;
-    tx.execute(
-        "DELETE FROM fixed_cost_payments WHERE tx_id = ?1",
-        params![transaction_id],
-    )
-    .map_err(|err| err.to_string())?;
-    let affected = tx
-        .execute(
-            "DELETE FROM transactions WHERE id = ?1",
-            params![transaction_id],
-        )
-        .map_err(|err| err.to_string())?;
-    if affected == 0 {
-        return Err("Transaksi tidak ditemukan".to_string());
-    }
-    tx.commit().map_err(|err| err.to_string())?;
-    Ok(())
-}
-fn insert_transaction(
-    app: AppHandle,
-    kind: &str,
+const FIXED_COST_ACTION_PAID: &str = "mark_paid";
+const FIXED_COST_ACTION_UNPAID: &str = "mark_unpaid";
+const FIXED_COST_ACTION_SKIPPED: &str = "skip_period";
+
+#[derive(Serialize, Deserialize)]
+struct TransactionSnapshot {
+    id: i64,
+    ts_utc: i64,
+    date_local: String,
+    kind: String,
     amount: i64,
-    date_local: Option<String>,
-    source: &str,
+    source: String,
     fixed_cost_id: Option<i64>,
     description: Option<String>,
-) -> Result<Transaction, String> {
-    if amount < 0 {
-        return Err("amount must be >= 0".to_string());
-    }
-    let date_local = resolve_date_local(date_local);
-    let ts_utc = Utc::now().timestamp_millis();
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-
-    conn.execute(
-        "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id, description)
-         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        params![ts_utc, date_local, kind, amount, source, fixed_cost_id, description],
-    )
-    .map_err(|err| err.to_string())?;
-
-    let id = conn.last_insert_rowid();
-
-    Ok(Transaction {
-        id,
-        ts_utc,
-        date_local,
-        kind: kind.to_string(),
-        amount,
-        source: source.to_string(),
-        fixed_cost_id,
-        description,
-    })
+    category: Option<String>,
+    // Snapshot lama belum punya kolom note.
+    #[serde(default)]
+    note: Option<String>,
+    #[serde(default)]
+    savings_goal_id: Option<i64>,
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn add_income(
-    app: AppHandle,
-    amount: i64,
-    date_local: Option<String>,
-    description: Option<String>,
-) -> Result<Transaction, String> {
-    insert_transaction(app, "IN", amount, date_local, "manual", None, description)
+impl TransactionSnapshot {
+    fn insert(&self, conn: &Connection) -> Result<(), String> {
+        conn.execute(
+            "INSERT INTO transactions (id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note, savings_goal_id, profile_id)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, (SELECT active_profile_id FROM app_state))",
+            params![
+                self.id,
+                self.ts_utc,
+                self.date_local,
+                self.kind,
+                self.amount,
+                self.source,
+                self.fixed_cost_id,
+                self.description,
+                self.category,
+                self.note,
+                self.savings_goal_id
+            ],
+        )
+        .map_err(|err| err.to_string())?;
+        Ok(())
+    }
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn add_expense(
-    app: AppHandle,
-    amount: i64,
-    date_local: Option<String>,
-    description: Option<String>,
-) -> Result<Transaction, String> {
-    insert_transaction(app, "OUT", amount, date_local, "manual", None, description)
+#[derive(Serialize, Deserialize)]
+struct FixedCostPaymentSnapshot {
+    id: i64,
+    paid_date_local: Option<String>,
+    paid_ts_utc: Option<i64>,
+    tx_id: Option<i64>,
+    is_partial: i64,
+    // Snapshot lama belum punya kolom is_skipped.
+    #[serde(default)]
+    is_skipped: i64,
+    transaction: Option<TransactionSnapshot>,
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn list_recent_transactions(app: AppHandle, limit: u32) -> Result<Vec<Transaction>, String> {
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+// Keadaan periode sebelum aksi dijalankan; undo cukup mengembalikan baris-baris ini.
+fn snapshot_fixed_cost_period(
+    conn: &Connection,
+    fixed_cost_id: i64,
+    period_ym: &str,
+) -> Result<Vec<FixedCostPaymentSnapshot>, String> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description
-             FROM transactions
-             ORDER BY ts_utc DESC
-             LIMIT ?1",
+            "SELECT p.id, p.paid_date_local, p.paid_ts_utc, p.tx_id, p.is_partial,
+                    t.id, t.ts_utc, t.date_local, t.kind, t.amount, t.source, t.fixed_cost_id,
+                    t.description, t.category, t.note, p.is_skipped
+             FROM fixed_cost_payments p
+             LEFT JOIN transactions t ON t.id = p.tx_id
+             WHERE p.fixed_cost_id = ?1 AND p.period_ym = ?2
+             ORDER BY p.id",
         )
         .map_err(|err| err.to_string())?;
-
     let rows = stmt
-        .query_map([limit], |row| {
-            Ok(Transaction {
+        .query_map(params![fixed_cost_id, period_ym], |row| {
+            let transaction = match row.get::<_, Option<i64>>(5)? {
+                Some(id) => Some(TransactionSnapshot {
+                    id,
+                    ts_utc: row.get(6)?,
+                    date_local: row.get(7)?,
+                    kind: row.get(8)?,
+                    amount: row.get(9)?,
+                    source: row.get(10)?,
+                    fixed_cost_id: row.get(11)?,
+                    description: row.get(12)?,
+                    category: row.get(13)?,
+                    note: row.get(14)?,
+                    savings_goal_id: None,
+                }),
+                None => None,
+            };
+            Ok(FixedCostPaymentSnapshot {
                 id: row.get(0)?,
-                ts_utc: row.get(1)?,
-                date_local: row.get(2)?,
-                kind: row.get(3)?,
-                amount: row.get(4)?,
-                source: row.get(5)?,
-                fixed_cost_id: row.get(6)?,
-                description: row.get(7)?,
+                paid_date_local: row.get(1)?,
+                paid_ts_utc: row.get(2)?,
+                tx_id: row.get(3)?,
+                is_partial: row.get(4)?,
+                is_skipped: row.get(15)?,
+                transaction,
             })
         })
         .map_err(|err| err.to_string())?;
 
-    let mut transactions = Vec::new();
+    let mut snapshot = Vec::new();
     for row in rows {
-        transactions.push(row.map_err(|err| err.to_string())?);
+        snapshot.push(row.map_err(|err| err.to_string())?);
     }
-
-    Ok(transactions)
+    Ok(snapshot)
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn list_transactions_between(
-    app: AppHandle,
-    start_date: String,
-    end_date: String,
-    limit: u32,
-    offset: u32,
-    kind: Option<String>,
-) -> Result<Vec<Transaction>, String> {
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    let limit = limit as i64;
-    let offset = offset as i64;
+// Hanya aksi terakhir yang bisa dibatalkan, jadi log cukup berisi satu baris.
+fn record_fixed_cost_action(
+    conn: &Connection,
+    action_type: &str,
+    fixed_cost_id: i64,
+    period_ym: &str,
+    snapshot: &[FixedCostPaymentSnapshot],
+) -> Result<(), String> {
+    let snapshot_json = serde_json::to_string(snapshot).map_err(|err| err.to_string())?;
+    conn.execute(
+        "DELETE FROM fixed_cost_action_log
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        [],
+    )
+    .map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO fixed_cost_action_log (profile_id, action_type, fixed_cost_id, period_ym, snapshot_json, created_ts_utc)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, ?3, ?4, ?5)",
+        params![
+            action_type,
+            fixed_cost_id,
+            period_ym,
+            snapshot_json,
+            Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
 
-    let (sql, params): (&str, Vec<rusqlite::types::Value>) = if let Some(kind) = kind {
-        (
-            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description
-             FROM transactions
-             WHERE date_local >= ?1 AND date_local <= ?2 AND kind = ?3
-             ORDER BY date_local DESC, ts_utc DESC
-             LIMIT ?4 OFFSET ?5",
-            vec![
-                start_date.into(),
-                end_date.into(),
-                kind.into(),
-                limit.into(),
-                offset.into(),
-            ],
+fn undo_last_fixed_cost_action_with_conn(conn: &mut Connection) -> Result<FixedCost, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let (log_id, fixed_cost_id, period_ym, snapshot_json): (i64, i64, String, String) = tx
+        .query_row(
+            "SELECT id, fixed_cost_id, period_ym, snapshot_json
+             FROM fixed_cost_action_log
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY id DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
         )
-    } else {
-        (
-            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description
-             FROM transactions
-             WHERE date_local >= ?1 AND date_local <= ?2
-             ORDER BY date_local DESC, ts_utc DESC
-             LIMIT ?3 OFFSET ?4",
-            vec![
-                start_date.into(),
-                end_date.into(),
-                limit.into(),
-                offset.into(),
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Tidak ada aksi biaya tetap untuk dibatalkan".to_string())?;
+    let snapshot: Vec<FixedCostPaymentSnapshot> =
+        serde_json::from_str(&snapshot_json).map_err(|err| err.to_string())?;
+
+    tx.execute(
+        "DELETE FROM transactions WHERE id IN (
+           SELECT tx_id FROM fixed_cost_payments
+           WHERE fixed_cost_id = ?1 AND period_ym = ?2 AND tx_id IS NOT NULL
+         )",
+        params![fixed_cost_id, &period_ym],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
+        params![fixed_cost_id, &period_ym],
+    )
+    .map_err(|err| err.to_string())?;
+
+    for payment in &snapshot {
+        if let Some(transaction) = &payment.transaction {
+            transaction.insert(&tx)?;
+        }
+        tx.execute(
+            "INSERT INTO fixed_cost_payments (id, fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id, is_partial, is_skipped)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                payment.id,
+                fixed_cost_id,
+                &period_ym,
+                payment.paid_date_local,
+                payment.paid_ts_utc,
+                payment.tx_id,
+                payment.is_partial,
+                payment.is_skipped
             ],
         )
-    };
-
-    let mut stmt = conn.prepare(sql).map_err(|err| err.to_string())?;
-    let rows = stmt
-        .query_map(rusqlite::params_from_iter(params), |row| {
-            Ok(Transaction {
-                id: row.get(0)?,
-                ts_utc: row.get(1)?,
-                date_local: row.get(2)?,
-                kind: row.get(3)?,
-                amount: row.get(4)?,
-                source: row.get(5)?,
-                fixed_cost_id: row.get(6)?,
-                description: row.get(7)?,
-            })
-        })
         .map_err(|err| err.to_string())?;
-
-    let mut transactions = Vec::new();
-    for row in rows {
-        transactions.push(row.map_err(|err| err.to_string())?);
     }
 
-    Ok(transactions)
+    tx.execute(
+        "DELETE FROM fixed_cost_action_log WHERE id = ?1",
+        params![log_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn delete_transaction(app: AppHandle, transaction_id: i64) -> Result<(), String> {
-    if transaction_id <= 0 {
-        return Err("ID transaksi tidak valid".to_string());
-    }
+fn undo_last_fixed_cost_action(app: AppHandle) -> Result<FixedCost, String> {
     let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    delete_transaction_with_conn(&mut conn, transaction_id)
+    let fixed_cost = undo_last_fixed_cost_action_with_conn(&mut conn)?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn get_config(app: AppHandle) -> Result<Config, String> {
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    fetch_config(&conn)
+#[derive(Serialize, Deserialize)]
+struct TrashedPayment {
+    fixed_cost_id: i64,
+    period_ym: String,
+    paid_date_local: Option<String>,
+    paid_ts_utc: Option<i64>,
+    is_partial: i64,
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn update_config(app: AppHandle, payload: ConfigPayload) -> Result<Config, String> {
-    if payload.min_floor < 0 || payload.max_ceil < 0 {
-        return Err("min_floor and max_ceil must be >= 0".to_string());
-    }
-    if payload.resilience_days < 1 {
-        return Err("resilience_days must be >= 1".to_string());
-    }
-    if payload.min_floor > payload.max_ceil {
-        return Err("min_floor must be <= max_ceil".to_string());
-    }
-
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    conn.execute(
-        "UPDATE config SET min_floor = ?1, max_ceil = ?2, resilience_days = ?3, updated_ts_utc = ?4 WHERE id = 1",
-        params![
-            payload.min_floor,
-            payload.max_ceil,
-            payload.resilience_days,
-            Utc::now().timestamp_millis()
-        ],
-    )
-    .map_err(|err| err.to_string())?;
+#[derive(Serialize, Deserialize)]
+struct TrashedReceipt {
+    recurring_income_id: i64,
+    period_ym: String,
+    received_date_local: String,
+    received_ts_utc: i64,
+}
 
-    fetch_config(&conn)
+#[derive(Serialize, Deserialize)]
+struct TrashSnapshot {
+    transaction: TransactionSnapshot,
+    payments: Vec<TrashedPayment>,
+    receipts: Vec<TrashedReceipt>,
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn list_fixed_costs(app: AppHandle) -> Result<Vec<FixedCost>, String> {
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    let period_ym = Local::now().format("%Y-%m").to_string();
-    cleanup_fixed_cost_payments(&conn)?;
+// Sampah hanya untuk undo cepat; yang lebih lama dari ini dibuang.
+const TRASH_RETENTION_LIMIT: i64 = 50;
+
+fn snapshot_for_trash(
+    conn: &Connection,
+    transaction_id: i64,
+) -> Result<Option<TrashSnapshot>, String> {
+    let Some(transaction) = find_transaction(conn, transaction_id)? else {
+        return Ok(None);
+    };
     let mut stmt = conn
         .prepare(
-            "SELECT fc.id, fc.name, fc.amount, fc.is_active, p.paid_date_local, p.paid_ts_utc, p.tx_id
-             FROM fixed_costs fc
-             LEFT JOIN fixed_cost_payments p
-               ON p.fixed_cost_id = fc.id AND p.period_ym = ?1
-             ORDER BY fc.id DESC",
+            "SELECT fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, is_partial
+             FROM fixed_cost_payments WHERE tx_id = ?1 ORDER BY id",
         )
         .map_err(|err| err.to_string())?;
-
-    let rows = stmt
-        .query_map([period_ym], |row| {
-            let active: i64 = row.get(3)?;
-            Ok(FixedCost {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                amount: row.get(2)?,
-                is_active: active != 0,
-                paid_date_local: row.get(4)?,
-                paid_ts_utc: row.get(5)?,
-                paid_tx_id: row.get(6)?,
+    let payments = stmt
+        .query_map([transaction_id], |row| {
+            Ok(TrashedPayment {
+                fixed_cost_id: row.get(0)?,
+                period_ym: row.get(1)?,
+                paid_date_local: row.get(2)?,
+                paid_ts_utc: row.get(3)?,
+                is_partial: row.get(4)?,
             })
         })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
         .map_err(|err| err.to_string())?;
-
-    let mut costs = Vec::new();
-    for row in rows {
-        costs.push(row.map_err(|err| err.to_string())?);
-    }
-
-    Ok(costs)
+    let mut stmt = conn
+        .prepare(
+            "SELECT recurring_income_id, period_ym, received_date_local, received_ts_utc
+             FROM recurring_income_receipts WHERE tx_id = ?1 ORDER BY id",
+        )
+        .map_err(|err| err.to_string())?;
+    let receipts = stmt
+        .query_map([transaction_id], |row| {
+            Ok(TrashedReceipt {
+                recurring_income_id: row.get(0)?,
+                period_ym: row.get(1)?,
+                received_date_local: row.get(2)?,
+                received_ts_utc: row.get(3)?,
+            })
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    let savings_goal_id: Option<i64> = conn
+        .query_row(
+            "SELECT savings_goal_id FROM transactions WHERE id = ?1",
+            [transaction_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(Some(TrashSnapshot {
+        transaction: TransactionSnapshot {
+            id: transaction.id,
+            ts_utc: transaction.ts_utc,
+            date_local: transaction.date_local,
+            kind: transaction.kind,
+            amount: transaction.amount,
+            source: transaction.source,
+            fixed_cost_id: transaction.fixed_cost_id,
+            description: transaction.description,
+            category: transaction.category,
+            note: transaction.note,
+            savings_goal_id,
+        },
+        payments,
+        receipts,
+    }))
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn add_fixed_cost(app: AppHandle, name: String, amount: i64) -> Result<FixedCost, String> {
-    if amount < 0 {
-        return Err("amount must be >= 0".to_string());
-    }
-    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-
+// Snapshot ikut menyimpan pembayaran/receipt yang tertaut supaya undo lengkap.
+fn move_to_trash(conn: &Connection, transaction_id: i64) -> Result<(), String> {
+    let Some(snapshot) = snapshot_for_trash(conn, transaction_id)? else {
+        return Ok(());
+    };
     conn.execute(
-        "INSERT INTO fixed_costs (name, amount, is_active) VALUES (?1, ?2, 1)",
-        params![name, amount],
+        "INSERT INTO transaction_trash (profile_id, transaction_id, deleted_ts_utc, snapshot_json)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, ?3)",
+        params![
+            transaction_id,
+            Utc::now().timestamp_millis(),
+            serde_json::to_string(&snapshot).map_err(|err| err.to_string())?
+        ],
     )
     .map_err(|err| err.to_string())?;
-
-    let id = conn.last_insert_rowid();
-
-    fetch_fixed_cost_for_period(&conn, id, &Local::now().format("%Y-%m").to_string())
+    Ok(())
 }
 
-#[tauri::command(rename_all = "snake_case")]
-fn delete_fixed_cost(app: AppHandle, fixed_cost_id: i64) -> Result<(), String> {
-    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    let tx = conn.transaction().map_err(|err| err.to_string())?;
-
-    tx.execute(
-        "DELETE FROM transactions WHERE id IN (
-            SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND tx_id IS NOT NULL
-         )",
-        params![fixed_cost_id],
-    )
-    .map_err(|err| err.to_string())?;
-    tx.execute(
-        "DELETE FROM transactions WHERE source = 'fixed_cost' AND fixed_cost_id = ?1",
-        params![fixed_cost_id],
+fn trim_trash(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM transaction_trash
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND id NOT IN (
+             SELECT id FROM transaction_trash
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY deleted_ts_utc DESC, id DESC LIMIT ?1
+           )",
+        [TRASH_RETENTION_LIMIT],
     )
     .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn delete_transaction_with_conn(conn: &mut Connection, transaction_id: i64) -> Result<(), String> {
+    if transaction_id <= 0 {
+        return Err("ID transaksi tidak valid".to_string());
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    move_to_trash(&tx, transaction_id)?;
+    trim_trash(&tx)?;
     tx.execute(
-        "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
-        params![fixed_cost_id],
+        "DELETE FROM fixed_cost_payments WHERE tx_id = ?1",
+        params![transaction_id],
     )
     .map_err(|err| err.to_string())?;
     tx.execute(
-        "DELETE FROM fixed_costs WHERE id = ?1",
-        params![fixed_cost_id],
+        "DELETE FROM recurring_income_receipts WHERE tx_id = ?1",
+        params![transaction_id],
     )
     .map_err(|err| err.to_string())?;
-    tx.commit().map_err(|err| err.to_string())?;
-    Ok(())
-}
-
-#[tauri::command(rename_all = "snake_case")]
-fn mark_fixed_cost_paid(
-    app: AppHandle,
-    fixed_cost_id: i64,
-    paid_date_local: Option<String>,
-) -> Result<FixedCost, String> {
-    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    mark_fixed_cost_paid_with_conn(&mut conn, fixed_cost_id, paid_date_local)
-}
-
-#[tauri::command(rename_all = "snake_case")]
-fn mark_fixed_cost_unpaid(
-    app: AppHandle,
-    fixed_cost_id: i64,
-    paid_date_local: Option<String>,
-) -> Result<FixedCost, String> {
-    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, paid_date_local)
+    let affected = tx
+        .execute(
+            "DELETE FROM transactions
+             WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            params![transaction_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Transaksi tidak ditemukan".to_string());
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
 }
 
-fn mark_fixed_cost_paid_with_conn(
-    conn: &mut Connection,
-    fixed_cost_id: i64,
-    paid_date_local: Option<String>,
-) -> Result<FixedCost, String> {
-    let paid_date_local = resolve_date_local(paid_date_local);
-    let period_ym = period_ym_from_date(&paid_date_local);
-    let paid_ts_utc = Utc::now().timestamp_millis();
+// Gagal bila tong sampah kosong atau periodenya sudah dibayar/diterima lagi.
+fn undo_delete_transaction_with_conn(conn: &mut Connection) -> Result<Transaction, String> {
     let tx = conn.transaction().map_err(|err| err.to_string())?;
-
-    let amount = fetch_fixed_cost_amount(&tx, fixed_cost_id)?;
-    if amount < 0 {
-        return Err("amount must be >= 0".to_string());
-    }
-
-    let existing_payment: Option<Option<i64>> = tx
+    let (trash_id, snapshot_json): (i64, String) = tx
         .query_row(
-            "SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
-            params![fixed_cost_id, &period_ym],
-            |row| row.get(0),
+            "SELECT id, snapshot_json FROM transaction_trash
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY deleted_ts_utc DESC, id DESC LIMIT 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
         )
         .optional()
-        .map_err(|err| err.to_string())?;
-
-    if let Some(Some(tx_id)) = existing_payment {
-        let tx_exists: i64 = tx
-            .query_row(
-                "SELECT COUNT(*) FROM transactions
-                 WHERE id = ?1 AND kind = 'OUT' AND source = 'fixed_cost' AND fixed_cost_id = ?2",
-                params![tx_id, fixed_cost_id],
-                |row| row.get(0),
-            )
-            .map_err(|err| err.to_string())?;
-        if tx_exists > 0 {
-            tx.execute(
-                "UPDATE fixed_cost_payments
-                 SET paid_date_local = ?1, paid_ts_utc = ?2
-                 WHERE fixed_cost_id = ?3 AND period_ym = ?4",
-                params![paid_date_local, paid_ts_utc, fixed_cost_id, &period_ym],
-            )
-            .map_err(|err| err.to_string())?;
-            tx.commit().map_err(|err| err.to_string())?;
-            return fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym);
-        }
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Tidak ada transaksi terhapus untuk dikembalikan".to_string())?;
+    let snapshot: TrashSnapshot =
+        serde_json::from_str(&snapshot_json).map_err(|err| err.to_string())?;
+    let transaction_id = snapshot.transaction.id;
 
+    snapshot.transaction.insert(&tx)?;
+    for payment in &snapshot.payments {
         tx.execute(
-            "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
-            params![fixed_cost_id, &period_ym],
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id, is_partial)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                payment.fixed_cost_id,
+                payment.period_ym,
+                payment.paid_date_local,
+                payment.paid_ts_utc,
+                transaction_id,
+                payment.is_partial
+            ],
         )
-        .map_err(|err| err.to_string())?;
-    } else if existing_payment.is_some() {
+        .map_err(|err| format!("Transaksi tidak bisa dikembalikan: {}", err))?;
+    }
+    for receipt in &snapshot.receipts {
         tx.execute(
-            "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
-            params![fixed_cost_id, &period_ym],
+            "INSERT INTO recurring_income_receipts
+               (recurring_income_id, period_ym, received_date_local, received_ts_utc, tx_id)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                receipt.recurring_income_id,
+                receipt.period_ym,
+                receipt.received_date_local,
+                receipt.received_ts_utc,
+                transaction_id
+            ],
         )
+        .map_err(|err| format!("Transaksi tidak bisa dikembalikan: {}", err))?;
+    }
+    tx.execute("DELETE FROM transaction_trash WHERE id = ?1", [trash_id])
         .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_transaction(conn, transaction_id)
+}
+
+// Tiap baris disimpan ke transaction_trash seperti delete_transaction.
+fn delete_transactions_between_with_conn(
+    conn: &mut Connection,
+    start_date: &str,
+    end_date: &str,
+    kind: Option<&str>,
+) -> Result<usize, String> {
+    let start = parse_date_local(start_date)?;
+    let end = parse_date_local(end_date)?;
+    if end < start {
+        return Err("end_date must be >= start_date".to_string());
+    }
+    if kind.is_some_and(|kind| kind != "IN" && kind != "OUT") {
+        return Err("kind must be 'IN' or 'OUT'".to_string());
+    }
+    let (clause, params) = transactions_between_filter(start_date, end_date, kind);
+    // Baris manual dan impor tidak punya pembayaran/receipt; biaya tetap, tabungan, dan
+    // pemasukan rutin dihapus lewat alurnya sendiri.
+    let matching = format!(
+        "SELECT id FROM transactions {} AND source IN ('manual', 'import')",
+        clause
+    );
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let ids = {
+        let mut stmt = tx.prepare(&matching).map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map(rusqlite::params_from_iter(params.iter()), |row| {
+                row.get::<_, i64>(0)
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?
+    };
+    for id in &ids {
+        move_to_trash(&tx, *id)?;
     }
+    trim_trash(&tx)?;
+    let deleted = tx
+        .execute(
+            &format!("DELETE FROM transactions WHERE id IN ({})", matching),
+            rusqlite::params_from_iter(params.iter()),
+        )
+        .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(deleted)
+}
+
+fn normalize_category(category: Option<String>) -> Option<String> {
+    category
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty())
+}
+
+fn transaction_from_row(row: &rusqlite::Row) -> rusqlite::Result<Transaction> {
+    Ok(Transaction {
+        id: row.get(0)?,
+        ts_utc: row.get(1)?,
+        date_local: row.get(2)?,
+        kind: row.get(3)?,
+        amount: row.get(4)?,
+        source: row.get(5)?,
+        fixed_cost_id: row.get(6)?,
+        description: row.get(7)?,
+        category: row.get(8)?,
+        note: row.get(9)?,
+        warning: None,
+    })
+}
+
+fn find_transaction(conn: &Connection, transaction_id: i64) -> Result<Option<Transaction>, String> {
+    conn.query_row(
+        "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+         FROM transactions
+         WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+        [transaction_id],
+        transaction_from_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())
+}
+
+fn fetch_transaction(conn: &Connection, transaction_id: i64) -> Result<Transaction, String> {
+    find_transaction(conn, transaction_id)?.ok_or_else(|| "Transaksi tidak ditemukan".to_string())
+}
+
+// Catatan kosong disimpan sebagai NULL.
+fn normalize_note(note: Option<String>) -> Option<String> {
+    note.map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
 
+// note None mempertahankan catatan lama; Some("") menghapusnya.
+fn update_transaction_with_conn(
+    conn: &mut Connection,
+    transaction_id: i64,
+    amount: i64,
+    date_local: &str,
+    note: Option<String>,
+) -> Result<Transaction, String> {
+    if transaction_id <= 0 {
+        return Err("ID transaksi tidak valid".to_string());
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let existing = fetch_transaction(&tx, transaction_id)?;
+    // Hanya input manual yang boleh diedit; sumber lain dimiliki alurnya masing-masing.
+    if existing.source != "manual" {
+        return Err(match existing.source.as_str() {
+            "fixed_cost" => "Transaksi biaya tetap tidak bisa diedit di sini; batalkan lalu tandai ulang di menu biaya tetap".to_string(),
+            "savings" => "Setoran tabungan tidak bisa diedit di sini; ubah lewat menu target tabungan".to_string(),
+            "recurring_income" => "Pemasukan rutin tidak bisa diedit di sini; ubah lewat menu pemasukan rutin".to_string(),
+            "import" => "Transaksi hasil impor tidak bisa diedit; hapus lalu catat ulang secara manual".to_string(),
+            other => format!("Transaksi dengan sumber '{}' tidak bisa diedit", other),
+        });
+    }
+    validate_amount_for_kind(&existing.kind, amount)?;
+    let date_local = parse_date_local(date_local)?.format("%Y-%m-%d").to_string();
+    let note = match note {
+        Some(note) => normalize_note(Some(note)),
+        None => existing.note,
+    };
     tx.execute(
-        "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
-         VALUES (?1, ?2, 'OUT', ?3, 'fixed_cost', ?4)",
-        params![paid_ts_utc, paid_date_local, amount, fixed_cost_id],
+        "UPDATE transactions SET amount = ?1, date_local = ?2, note = ?3 WHERE id = ?4",
+        params![amount, date_local, note, transaction_id],
     )
     .map_err(|err| err.to_string())?;
-    let tx_id = tx.last_insert_rowid();
+    let updated = fetch_transaction(&tx, transaction_id)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
 
-    tx.execute(
-        "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-         VALUES (?1, ?2, ?3, ?4, ?5)
-         ON CONFLICT(fixed_cost_id, period_ym) DO UPDATE SET
-           paid_date_local = excluded.paid_date_local,
-           paid_ts_utc = excluded.paid_ts_utc,
-           tx_id = excluded.tx_id",
+// Nominal di atas large_amount_multiplier x batas harian efektif biasanya kelebihan nol.
+fn large_amount_warning(config: &Config, net_balance: i64, amount: i64) -> Option<String> {
+    let threshold =
+        effective_max_ceil(config, net_balance).saturating_mul(config.large_amount_multiplier);
+    if threshold <= 0 || amount <= threshold {
+        return None;
+    }
+    Some(format!(
+        "Nominal {} lebih dari {}x batas harian; pastikan tidak ada nol berlebih.",
+        insight::format_money(amount, config, insight_copy::Locale::Id),
+        config.large_amount_multiplier
+    ))
+}
+
+// Satu transaksi manual dari input; dipakai add_income/add_expense dan batch.
+#[derive(Deserialize)]
+struct NewTransaction {
+    kind: String,
+    amount: i64,
+    date_local: Option<String>,
+    description: Option<String>,
+    category: Option<String>,
+    note: Option<String>,
+    ts_utc: Option<i64>,
+}
+
+// 2000-01-01T00:00:00Z; nilai di bawahnya hampir pasti detik, bukan milidetik.
+const MIN_TS_UTC: i64 = 946_684_800_000;
+// Toleransi sehari ke depan untuk selisih zona waktu date_local.
+const MAX_TS_UTC_AHEAD_MS: i64 = 24 * 60 * 60 * 1000;
+
+fn resolve_ts_utc(ts_utc: Option<i64>, now_ms: i64) -> Result<i64, String> {
+    match ts_utc {
+        None => Ok(now_ms),
+        Some(value) if (MIN_TS_UTC..=now_ms + MAX_TS_UTC_AHEAD_MS).contains(&value) => Ok(value),
+        Some(_) => Err("ts_utc must be epoch milliseconds between 2000-01-01 and now".to_string()),
+    }
+}
+
+// Pemasukan nol hampir selalu salah ketik; pengeluaran nol (barang gratis) boleh.
+pub(crate) fn validate_amount_for_kind(kind: &str, amount: i64) -> Result<(), String> {
+    match kind {
+        "IN" if amount <= 0 => Err("income amount must be > 0".to_string()),
+        "OUT" if amount < 0 => Err("amount must be >= 0".to_string()),
+        "IN" | "OUT" => Ok(()),
+        _ => Err("kind must be 'IN' or 'OUT'".to_string()),
+    }
+}
+
+fn insert_transaction_with_conn(
+    conn: &Connection,
+    config: &Config,
+    source: &str,
+    entry: NewTransaction,
+    now_ms: i64,
+    clock: &dyn Clock,
+) -> Result<Transaction, String> {
+    validate_amount_for_kind(&entry.kind, entry.amount)?;
+    let date_local = resolve_date_local(entry.date_local, clock);
+    parse_date_local(&date_local)?;
+    let ts_utc = resolve_ts_utc(entry.ts_utc, now_ms)?;
+    let category = normalize_category(entry.category);
+    let note = normalize_note(entry.note);
+    // Batas mode persen mengikuti saldo sebelum transaksi ini.
+    let (total_in, total_out) = transaction_totals(conn, None)?;
+    let net_balance = config.opening_balance + total_in - total_out;
+
+    conn.execute(
+        "INSERT INTO transactions (profile_id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, ?3, ?4, ?5, NULL, ?6, ?7, ?8)",
         params![
-            fixed_cost_id,
-            &period_ym,
-            paid_date_local,
-            paid_ts_utc,
-            tx_id
+            ts_utc,
+            date_local,
+            entry.kind,
+            entry.amount,
+            source,
+            entry.description,
+            category,
+            note
         ],
     )
     .map_err(|err| err.to_string())?;
 
+    Ok(Transaction {
+        id: conn.last_insert_rowid(),
+        ts_utc,
+        date_local,
+        kind: entry.kind,
+        amount: entry.amount,
+        source: source.to_string(),
+        fixed_cost_id: None,
+        description: entry.description,
+        category,
+        note,
+        warning: large_amount_warning(config, net_balance, entry.amount),
+    })
+}
+
+fn insert_transaction(
+    app: AppHandle,
+    source: &str,
+    entry: NewTransaction,
+) -> Result<Transaction, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = fetch_config(&conn)?;
+    // Status sebelum insert dibutuhkan untuk mendeteksi transisi ke overspend.
+    let overspent_before = match entry.kind.as_str() {
+        "OUT" => Some(compute_pools_summary(&conn)?.overspent_today),
+        _ => None,
+    };
+
+    let transaction = insert_transaction_with_conn(
+        &conn,
+        &config,
+        source,
+        entry,
+        Utc::now().timestamp_millis(),
+        &SystemClock,
+    )?;
+    match overspent_before {
+        Some(overspent_before) => emit_pools_updated_after_expense(&app, &conn, overspent_before),
+        None => emit_pools_updated(&app, &conn),
+    }
+    Ok(transaction)
+}
+
+// Satu entri tidak valid membatalkan seluruh batch; error menyebut indeksnya.
+fn batch_add_transactions_with_conn(
+    conn: &mut Connection,
+    entries: Vec<NewTransaction>,
+    now_ms: i64,
+    clock: &dyn Clock,
+) -> Result<Vec<Transaction>, String> {
+    let config = fetch_config(conn)?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let mut created = Vec::with_capacity(entries.len());
+    for (index, entry) in entries.into_iter().enumerate() {
+        let transaction =
+            insert_transaction_with_conn(&tx, &config, "manual", entry, now_ms, clock)
+                .map_err(|err| format!("entry {}: {}", index, err))?;
+        created.push(transaction);
+    }
     tx.commit().map_err(|err| err.to_string())?;
+    Ok(created)
+}
 
-    fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym)
+#[tauri::command(rename_all = "snake_case")]
+fn batch_add_transactions(
+    app: AppHandle,
+    entries: Vec<NewTransaction>,
+) -> Result<Vec<Transaction>, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let overspent_before = compute_pools_summary(&conn)?.overspent_today;
+    let created = batch_add_transactions_with_conn(
+        &mut conn,
+        entries,
+        Utc::now().timestamp_millis(),
+        &SystemClock,
+    )?;
+    emit_pools_updated_after_expense(&app, &conn, overspent_before);
+    Ok(created)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn get_today_summary(app: AppHandle) -> Result<TodaySummary, String> {
+fn add_income(
+    app: AppHandle,
+    amount: i64,
+    date_local: Option<String>,
+    description: Option<String>,
+    category: Option<String>,
+    note: Option<String>,
+    ts_utc: Option<i64>,
+) -> Result<Transaction, String> {
+    let entry = NewTransaction {
+        kind: "IN".to_string(),
+        amount,
+        date_local,
+        description,
+        category,
+        note,
+        ts_utc,
+    };
+    insert_transaction(app, "manual", entry)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn add_expense(
+    app: AppHandle,
+    amount: i64,
+    date_local: Option<String>,
+    description: Option<String>,
+    category: Option<String>,
+    note: Option<String>,
+    ts_utc: Option<i64>,
+) -> Result<Transaction, String> {
+    let entry = NewTransaction {
+        kind: "OUT".to_string(),
+        amount,
+        date_local,
+        description,
+        category,
+        note,
+        ts_utc,
+    };
+    insert_transaction(app, "manual", entry)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_category_totals(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<analytics::CategoryTotal>, String> {
     let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    let summary = compute_pools_summary(&conn)?;
-    Ok(TodaySummary {
-        recommended_spend_today: summary.recommended_spend_today,
-        today_out: summary.today_out,
-        today_remaining: summary.today_remaining,
-        today_remaining_clamped: summary.today_remaining_clamped,
-        overspent_today: summary.overspent_today,
-    })
+    analytics::category_totals(&conn, &start_date, &end_date)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn get_pools_summary(app: AppHandle) -> Result<PoolsSummary, String> {
+fn get_income_breakdown(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<analytics::IncomeCategoryTotal>, String> {
     let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    compute_pools_summary(&conn)
+    analytics::income_breakdown(&conn, &start_date, &end_date)
 }
 
 #[tauri::command(rename_all = "snake_case")]
-fn get_coaching_insight(app: AppHandle) -> Result<insight::CoachingInsight, String> {
+fn get_weekday_spending_profile(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<analytics::WeekdaySpend>, String> {
     let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
-    insight::compute_coaching_insight(&conn)
+    analytics::weekday_spending_profile(&conn, &start_date, &end_date)
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
-        .setup(|app| {
-            db::init_db(app.handle())?;
-            Ok(())
-        })
-        .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_opener::init())
-        .invoke_handler(tauri::generate_handler![
-            add_income,
-            add_expense,
-            list_recent_transactions,
-            list_transactions_between,
-            delete_transaction,
-            get_config,
-            update_config,
-            list_fixed_costs,
-            add_fixed_cost,
-            delete_fixed_cost,
-            mark_fixed_cost_paid,
-            mark_fixed_cost_unpaid,
-            get_today_summary,
-            get_pools_summary,
-            get_coaching_insight
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+#[tauri::command(rename_all = "snake_case")]
+fn get_spending_breakdown_by_source(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<analytics::SourceBreakdown>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::spending_by_source(&conn, &start_date, &end_date)
 }
 
-#[cfg(test)]
-mod tests {
+#[tauri::command(rename_all = "snake_case")]
+fn get_transaction(app: AppHandle, transaction_id: i64) -> Result<Option<Transaction>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    find_transaction(&conn, transaction_id)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_recent_transactions(app: AppHandle, limit: u32) -> Result<Vec<Transaction>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY ts_utc DESC
+             LIMIT ?1",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map([limit], transaction_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        transactions.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(transactions)
+}
+
+fn today_transactions_with_conn(
+    conn: &Connection,
+    today: &str,
+) -> Result<Vec<Transaction>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local = ?1
+             ORDER BY ts_utc DESC",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map([today], transaction_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        transactions.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(transactions)
+}
+
+// Berdasarkan tanggal lokal backend, terbaru dulu.
+#[tauri::command(rename_all = "snake_case")]
+fn get_today_transactions(app: AppHandle) -> Result<Vec<Transaction>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    today_transactions_with_conn(&conn, &SystemClock.today_local())
+}
+
+#[derive(Serialize)]
+struct TransactionPage {
+    items: Vec<Transaction>,
+    total: i64,
+}
+
+// Satu sumber WHERE untuk listing dan COUNT(*) supaya keduanya tidak pernah berbeda.
+fn transactions_between_filter(
+    start_date: &str,
+    end_date: &str,
+    kind: Option<&str>,
+) -> (&'static str, Vec<rusqlite::types::Value>) {
+    let mut params: Vec<rusqlite::types::Value> =
+        vec![start_date.to_string().into(), end_date.to_string().into()];
+    let clause = if let Some(kind) = kind {
+        params.push(kind.to_string().into());
+        "WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND date_local >= ?1 AND date_local <= ?2 AND kind = ?3"
+    } else {
+        "WHERE profile_id = (SELECT active_profile_id FROM app_state)
+           AND date_local >= ?1 AND date_local <= ?2"
+    };
+    (clause, params)
+}
+
+fn list_transactions_between_with_conn(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    limit: u32,
+    offset: u32,
+    kind: Option<&str>,
+) -> Result<Vec<Transaction>, String> {
+    let (clause, mut params) = transactions_between_filter(start_date, end_date, kind);
+    let sql = format!(
+        "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+         FROM transactions
+         {}
+         ORDER BY date_local DESC, ts_utc DESC
+         LIMIT ?{} OFFSET ?{}",
+        clause,
+        params.len() + 1,
+        params.len() + 2
+    );
+    params.push((limit as i64).into());
+    params.push((offset as i64).into());
+
+    let mut stmt = conn.prepare(&sql).map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(params), transaction_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        transactions.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(transactions)
+}
+
+fn count_transactions_between(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    kind: Option<&str>,
+) -> Result<i64, String> {
+    let (clause, params) = transactions_between_filter(start_date, end_date, kind);
+    conn.query_row(
+        &format!("SELECT COUNT(*) FROM transactions {}", clause),
+        rusqlite::params_from_iter(params),
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_transactions_between(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+    limit: u32,
+    offset: u32,
+    kind: Option<String>,
+) -> Result<Vec<Transaction>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    list_transactions_between_with_conn(
+        &conn,
+        &start_date,
+        &end_date,
+        limit,
+        offset,
+        kind.as_deref(),
+    )
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_transactions_between_paged(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+    limit: u32,
+    offset: u32,
+    kind: Option<String>,
+) -> Result<TransactionPage, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let items = list_transactions_between_with_conn(
+        &conn,
+        &start_date,
+        &end_date,
+        limit,
+        offset,
+        kind.as_deref(),
+    )?;
+    let total = count_transactions_between(&conn, &start_date, &end_date, kind.as_deref())?;
+    Ok(TransactionPage { items, total })
+}
+
+fn biggest_expenses_with_conn(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    limit: u32,
+) -> Result<Vec<Transaction>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND date_local >= ?1 AND date_local <= ?2
+             ORDER BY amount DESC, ts_utc DESC
+             LIMIT ?3",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params![start_date, end_date, limit], transaction_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut transactions = Vec::new();
+    for row in rows {
+        transactions.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(transactions)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_biggest_expenses(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+    limit: u32,
+) -> Result<Vec<Transaction>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    biggest_expenses_with_conn(&conn, &start_date, &end_date, limit)
+}
+
+// Kind, nominal, dan tanggal sama dengan ts_utc berantai dalam within_minutes; baca-saja.
+fn find_possible_duplicates_with_conn(
+    conn: &Connection,
+    within_minutes: i64,
+) -> Result<Vec<Vec<Transaction>>, String> {
+    if within_minutes < 0 {
+        return Err("within_minutes must be >= 0".to_string());
+    }
+    let window_ms = within_minutes.saturating_mul(60_000);
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, ts_utc, date_local, kind, amount, source, fixed_cost_id, description, category, note
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY kind, amount, date_local, ts_utc, id",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], transaction_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut groups = Vec::new();
+    let mut current: Vec<Transaction> = Vec::new();
+    for row in rows {
+        let transaction = row.map_err(|err| err.to_string())?;
+        let joins_current = current.last().is_some_and(|last| {
+            last.kind == transaction.kind
+                && last.amount == transaction.amount
+                && last.date_local == transaction.date_local
+                && transaction.ts_utc - last.ts_utc <= window_ms
+        });
+        if !joins_current {
+            if current.len() > 1 {
+                groups.push(std::mem::take(&mut current));
+            }
+            current.clear();
+        }
+        current.push(transaction);
+    }
+    if current.len() > 1 {
+        groups.push(current);
+    }
+    Ok(groups)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn find_possible_duplicates(
+    app: AppHandle,
+    within_minutes: i64,
+) -> Result<Vec<Vec<Transaction>>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    find_possible_duplicates_with_conn(&conn, within_minutes)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn undo_delete_transaction(app: AppHandle) -> Result<Transaction, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let transaction = undo_delete_transaction_with_conn(&mut conn)?;
+    emit_pools_updated(&app, &conn);
+    Ok(transaction)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn delete_transaction(app: AppHandle, transaction_id: i64) -> Result<(), String> {
+    if transaction_id <= 0 {
+        return Err("ID transaksi tidak valid".to_string());
+    }
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    delete_transaction_with_conn(&mut conn, transaction_id)?;
+    emit_pools_updated(&app, &conn);
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn delete_transactions_between(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+    kind: Option<String>,
+) -> Result<usize, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let deleted =
+        delete_transactions_between_with_conn(&mut conn, &start_date, &end_date, kind.as_deref())?;
+    if deleted > 0 {
+        emit_pools_updated(&app, &conn);
+    }
+    Ok(deleted)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn update_transaction(
+    app: AppHandle,
+    transaction_id: i64,
+    amount: i64,
+    date_local: String,
+    note: Option<String>,
+) -> Result<Transaction, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let transaction =
+        update_transaction_with_conn(&mut conn, transaction_id, amount, &date_local, note)?;
+    emit_pools_updated(&app, &conn);
+    Ok(transaction)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn import_transactions_csv(
+    app: AppHandle,
+    path: String,
+    dry_run: bool,
+) -> Result<csv_import::CsvImportReport, String> {
+    let content = std::fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let report = csv_import::import_transactions_csv(&mut conn, &content, dry_run)?;
+    if report.committed {
+        emit_pools_updated(&app, &conn);
+    }
+    Ok(report)
+}
+
+// Anak dihapus sebelum induk supaya foreign key tetap terpenuhi. Anak tidak punya
+// profile_id sendiri, jadi disaring lewat induknya.
+const RESET_TABLES: &[(&str, &str)] = &[
+    (
+        "fixed_cost_payments",
+        "fixed_cost_id IN (SELECT id FROM fixed_costs
+           WHERE profile_id = (SELECT active_profile_id FROM app_state))",
+    ),
+    (
+        "fixed_cost_action_log",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "recurring_income_receipts",
+        "recurring_income_id IN (SELECT id FROM recurring_incomes
+           WHERE profile_id = (SELECT active_profile_id FROM app_state))",
+    ),
+    (
+        "transactions",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "fixed_costs",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "recurring_incomes",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "savings_goals",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "no_spend_days",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "coaching_memory",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+    (
+        "transaction_trash",
+        "profile_id = (SELECT active_profile_id FROM app_state)",
+    ),
+];
+
+// Hanya data profil aktif. Baris config selalu tersisa; tanpa keep_config semua nilainya
+// kembali ke nilai instalasi (lebih luas dari reset_config_to_defaults).
+fn reset_all_data_with_conn(
+    conn: &mut Connection,
+    keep_config: bool,
+) -> Result<BTreeMap<String, usize>, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let mut removed = BTreeMap::new();
+    for (table, scope) in RESET_TABLES {
+        let count = tx
+            .execute(&format!("DELETE FROM {} WHERE {}", table, scope), [])
+            .map_err(|err| err.to_string())?;
+        removed.insert(table.to_string(), count);
+    }
+    if !keep_config {
+        let current = fetch_config(&tx)?;
+        db::reset_config_row(&tx).map_err(|err| err.to_string())?;
+        let updated = fetch_config(&tx)?;
+        record_config_history(&tx, "reset_all_data", &current, &updated)?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(removed)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_config_defaults() -> db::ConfigDefaults {
+    db::config_defaults()
+}
+
+// Hanya ambang anggaran yang dikembalikan; saldo awal, mata uang, mode coach, dan
+// pengaturan lain tetap seperti yang dipilih pengguna.
+fn default_config_payload() -> ConfigPayload {
+    let defaults = db::config_defaults();
+    ConfigPayload {
+        min_floor: defaults.min_floor,
+        max_ceil: defaults.max_ceil,
+        resilience_days: defaults.resilience_days,
+        ..ConfigPayload::default()
+    }
+}
+
+fn reset_config_to_defaults_with_conn(conn: &mut Connection) -> Result<Config, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let updated = apply_config_payload(&tx, default_config_payload(), "reset_config_to_defaults")?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn reset_config_to_defaults(app: AppHandle) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = reset_config_to_defaults_with_conn(&mut conn)?;
+    emit_pools_updated(&app, &conn);
+    Ok(config)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn reset_all_data(app: AppHandle, keep_config: bool) -> Result<BTreeMap<String, usize>, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let removed = reset_all_data_with_conn(&mut conn, keep_config)?;
+    emit_pools_updated(&app, &conn);
+    Ok(removed)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn compact_database(app: AppHandle) -> Result<db::CompactReport, String> {
+    db::compact_database(&app).map_err(|err| err.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn explain_query_plan(app: AppHandle, sql: String) -> Result<Vec<db::QueryPlanRow>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    db::explain_query_plan(&conn, &sql).map_err(|err| err.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn export_backup_json(app: AppHandle, path: String) -> Result<(), String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let document = backup::export_backup(&conn)?;
+    let json = serde_json::to_string_pretty(&document).map_err(|err| err.to_string())?;
+    std::fs::write(&path, json).map_err(|err| err.to_string())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn import_backup_json(app: AppHandle, path: String) -> Result<(), String> {
+    let content = std::fs::read_to_string(&path).map_err(|err| err.to_string())?;
+    let document: backup::BackupDocument =
+        serde_json::from_str(&content).map_err(|err| format!("invalid backup file: {}", err))?;
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    backup::restore_backup(&mut conn, &document)?;
+    emit_pools_updated(&app, &conn);
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_config(app: AppHandle) -> Result<Config, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    fetch_config(&conn)
+}
+
+// Turunan dihitung lewat daily_recommendation yang sama dengan ringkasan pools.
+fn config_with_derived_with_conn(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<ConfigWithDerived, String> {
+    let summary = compute_pools_summary_for_date(conn, today)?;
+    let config = fetch_config(conn)?;
+    let derived = daily_recommendation(
+        &config,
+        summary.net_balance,
+        summary.income_deferred,
+        summary.savings_reserved,
+    );
+    Ok(ConfigWithDerived {
+        config,
+        target_penyangga: derived.target_penyangga,
+        dana_fleksibel: derived.dana_fleksibel,
+        per_day_fleksibel: derived.per_day_fleksibel,
+        penyangga_tercapai: derived.penyangga_tercapai,
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_config_with_derived(app: AppHandle) -> Result<ConfigWithDerived, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    config_with_derived_with_conn(&conn, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn update_config(app: AppHandle, payload: ConfigPayload) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = update_config_with_conn(&mut conn, payload)?;
+    emit_pools_updated(&app, &conn);
+    Ok(config)
+}
+
+fn validate_config_payload(payload: &ConfigPayload) -> Result<(), String> {
+    if payload.min_floor < 0 || payload.max_ceil < 0 {
+        return Err("min_floor and max_ceil must be >= 0".to_string());
+    }
+    if payload.resilience_days < 0 {
+        return Err("resilience_days must be >= 0".to_string());
+    }
+    if payload.min_floor > payload.max_ceil {
+        return Err("min_floor must be <= max_ceil".to_string());
+    }
+    if let Some(days) = payload.income_smoothing_days {
+        if !(0..=90).contains(&days) {
+            return Err("income_smoothing_days must be between 0 and 90".to_string());
+        }
+    }
+    if let Some(ratio) = payload.near_limit_ratio {
+        if !(50..=100).contains(&ratio) {
+            return Err("near_limit_ratio must be between 50 and 100".to_string());
+        }
+    }
+    if let Some(granularity) = payload.budget_granularity.as_deref() {
+        if granularity != BUDGET_DAILY && granularity != BUDGET_WEEKLY {
+            return Err("budget_granularity must be 'daily' or 'weekly'".to_string());
+        }
+    }
+    if let Some(code) = payload.currency_code.as_deref() {
+        if code.len() != 3 || !code.chars().all(|ch| ch.is_ascii_uppercase()) {
+            return Err("currency_code must be a 3-letter ISO code like 'IDR'".to_string());
+        }
+    }
+    if payload.minor_units.is_some_and(|units| units > 4) {
+        return Err("minor_units must be between 0 and 4".to_string());
+    }
+    if payload
+        .large_amount_multiplier
+        .is_some_and(|multiplier| multiplier < 1)
+    {
+        return Err("large_amount_multiplier must be >= 1".to_string());
+    }
+    if payload
+        .recommend_rounding
+        .is_some_and(|granularity| !is_valid_rounding(granularity))
+    {
+        return Err("recommend_rounding must be 0 or a power of ten".to_string());
+    }
+    if let Some(mode) = payload.max_ceil_mode.as_deref() {
+        if mode != MAX_CEIL_ABSOLUTE && mode != MAX_CEIL_PERCENT {
+            return Err("max_ceil_mode must be 'absolute' or 'percent'".to_string());
+        }
+    }
+    if payload
+        .max_ceil_percent
+        .is_some_and(|percent| !(1..=100).contains(&percent))
+    {
+        return Err("max_ceil_percent must be between 1 and 100".to_string());
+    }
+    if payload.week_start_day.is_some_and(|day| day > 6) {
+        return Err("week_start_day must be between 0 (Sunday) and 6 (Saturday)".to_string());
+    }
+    Ok(())
+}
+
+fn update_config_with_conn(
+    conn: &mut Connection,
+    payload: ConfigPayload,
+) -> Result<Config, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let updated = apply_config_payload(&tx, payload, "update_config")?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+// Di dalam transaksi pemanggil; field opsional yang kosong mempertahankan nilai lama.
+fn apply_config_payload(
+    tx: &Connection,
+    payload: ConfigPayload,
+    source: &str,
+) -> Result<Config, String> {
+    validate_config_payload(&payload)?;
+    let current = fetch_config(tx)?;
+    tx.execute(
+        "UPDATE config SET min_floor = ?1, max_ceil = ?2, resilience_days = ?3, income_smoothing_days = ?4,
+           record_near_limit = ?5, near_limit_ratio = ?6, budget_granularity = ?7, updated_ts_utc = ?8,
+           currency_code = ?9, minor_units = ?10, large_amount_multiplier = ?11,
+           recommend_rounding = ?12, carryover_enabled = ?13, max_ceil_mode = ?14,
+           max_ceil_percent = ?15, week_start_day = ?16
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![
+            payload.min_floor,
+            payload.max_ceil,
+            payload.resilience_days,
+            payload
+                .income_smoothing_days
+                .unwrap_or(current.income_smoothing_days),
+            payload
+                .record_near_limit
+                .unwrap_or(current.record_near_limit),
+            payload
+                .near_limit_ratio
+                .unwrap_or(current.near_limit_ratio),
+            payload
+                .budget_granularity
+                .unwrap_or_else(|| current.budget_granularity.clone()),
+            Utc::now().timestamp_millis(),
+            payload
+                .currency_code
+                .unwrap_or_else(|| current.currency_code.clone()),
+            payload.minor_units.unwrap_or(current.minor_units),
+            payload
+                .large_amount_multiplier
+                .unwrap_or(current.large_amount_multiplier),
+            payload
+                .recommend_rounding
+                .unwrap_or(current.recommend_rounding),
+            payload
+                .carryover_enabled
+                .unwrap_or(current.carryover_enabled),
+            payload
+                .max_ceil_mode
+                .unwrap_or_else(|| current.max_ceil_mode.clone()),
+            payload
+                .max_ceil_percent
+                .unwrap_or(current.max_ceil_percent),
+            payload.week_start_day.unwrap_or(current.week_start_day)
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let updated = fetch_config(tx)?;
+    record_config_history(tx, source, &current, &updated)?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_opening_balance(app: AppHandle, amount: i64) -> Result<Config, String> {
+    if amount < 0 {
+        return Err("amount must be >= 0".to_string());
+    }
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET opening_balance = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![amount, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_opening_balance", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    emit_pools_updated(&app, &conn);
+    Ok(updated)
+}
+
+// 'calm' dan 'watchful' tetap memakai pilihan otomatis berbasis penyangga.
+#[tauri::command(rename_all = "snake_case")]
+fn set_coach_mode(
+    app: AppHandle,
+    coach_mode: String,
+    reason: Option<String>,
+) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = set_coach_mode_with_conn(&mut conn, &coach_mode, reason)?;
+    emit_pools_updated(&app, &conn);
+    Ok(config)
+}
+
+// reason disimpan di riwayat config, mis. "bulan ini sedang ketat".
+fn set_coach_mode_with_conn(
+    conn: &mut Connection,
+    coach_mode: &str,
+    reason: Option<String>,
+) -> Result<Config, String> {
+    insight::validate_coach_mode(coach_mode)?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET coach_mode = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![coach_mode, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    let reason = normalize_note(reason);
+    record_config_history_with_reason(
+        &tx,
+        "set_coach_mode",
+        &current,
+        &updated,
+        reason.as_deref(),
+    )?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_low_buffer_days(app: AppHandle, days: i64) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    set_low_buffer_days_with_conn(&mut conn, days)
+}
+
+fn set_low_buffer_days_with_conn(conn: &mut Connection, days: i64) -> Result<Config, String> {
+    if !(LOW_BUFFER_DAYS_MIN..=LOW_BUFFER_DAYS_MAX).contains(&days) {
+        return Err(format!(
+            "low_buffer_days must be between {} and {}",
+            LOW_BUFFER_DAYS_MIN, LOW_BUFFER_DAYS_MAX
+        ));
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET low_buffer_days = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![days, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_low_buffer_days", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_memory_retention(app: AppHandle, limit: i64) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    set_memory_retention_with_conn(&mut conn, limit)
+}
+
+fn set_memory_retention_with_conn(conn: &mut Connection, limit: i64) -> Result<Config, String> {
+    if !(MEMORY_RETENTION_MIN..=MEMORY_RETENTION_MAX).contains(&limit) {
+        return Err(format!(
+            "memory_retention_limit must be between {} and {}",
+            MEMORY_RETENTION_MIN, MEMORY_RETENTION_MAX
+        ));
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET memory_retention_limit = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![limit, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_memory_retention", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_time_buckets(
+    app: AppHandle,
+    morning_start_hour: i64,
+    midday_start_hour: i64,
+    afternoon_start_hour: i64,
+    evening_start_hour: i64,
+    night_start_hour: i64,
+) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    set_time_buckets_with_conn(
+        &mut conn,
+        [
+            morning_start_hour,
+            midday_start_hour,
+            afternoon_start_hour,
+            evening_start_hour,
+            night_start_hour,
+        ],
+    )
+}
+
+// starts: jam mulai pagi, siang, sore, petang, dan malam.
+fn set_time_buckets_with_conn(conn: &mut Connection, starts: [i64; 5]) -> Result<Config, String> {
+    if starts.iter().any(|hour| !(0..=23).contains(hour)) {
+        return Err("bucket hours must be between 0 and 23".to_string());
+    }
+    if starts.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return Err("bucket hours must be strictly increasing".to_string());
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET morning_start_hour = ?1, midday_start_hour = ?2,
+           afternoon_start_hour = ?3, evening_start_hour = ?4, night_start_hour = ?5,
+           updated_ts_utc = ?6
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![
+            starts[0],
+            starts[1],
+            starts[2],
+            starts[3],
+            starts[4],
+            Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_time_buckets", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_spending_frozen(app: AppHandle, frozen: bool) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = set_spending_frozen_with_conn(&mut conn, frozen)?;
+    emit_pools_updated(&app, &conn);
+    Ok(config)
+}
+
+fn set_spending_frozen_with_conn(conn: &mut Connection, frozen: bool) -> Result<Config, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET spending_frozen = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![frozen as i64, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_spending_frozen", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_exclude_fixed_from_daily(app: AppHandle, exclude: bool) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let config = set_exclude_fixed_from_daily_with_conn(&mut conn, exclude)?;
+    emit_pools_updated(&app, &conn);
+    Ok(config)
+}
+
+fn set_exclude_fixed_from_daily_with_conn(
+    conn: &mut Connection,
+    exclude: bool,
+) -> Result<Config, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET exclude_fixed_from_daily = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![exclude as i64, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_exclude_fixed_from_daily", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_memory_recording_enabled(app: AppHandle, enabled: bool) -> Result<Config, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    set_memory_recording_enabled_with_conn(&mut conn, enabled)
+}
+
+fn set_memory_recording_enabled_with_conn(
+    conn: &mut Connection,
+    enabled: bool,
+) -> Result<Config, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let current = fetch_config(&tx)?;
+    tx.execute(
+        "UPDATE config SET memory_recording_enabled = ?1, updated_ts_utc = ?2
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        params![enabled as i64, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let updated = fetch_config(&tx)?;
+    record_config_history(&tx, "set_memory_recording_enabled", &current, &updated)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(updated)
+}
+
+// Snapshot utuh Config sebagai JSON supaya kolom baru ikut tercatat tanpa migrasi tabel.
+fn record_config_history(
+    conn: &Connection,
+    change_source: &str,
+    old_config: &Config,
+    new_config: &Config,
+) -> Result<(), String> {
+    record_config_history_with_reason(conn, change_source, old_config, new_config, None)
+}
+
+fn record_config_history_with_reason(
+    conn: &Connection,
+    change_source: &str,
+    old_config: &Config,
+    new_config: &Config,
+    reason: Option<&str>,
+) -> Result<(), String> {
+    let old_json = serde_json::to_string(old_config).map_err(|err| err.to_string())?;
+    let new_json = serde_json::to_string(new_config).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT INTO config_history (profile_id, changed_ts_utc, change_source, old_json, new_json, reason)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, ?3, ?4, ?5)",
+        params![
+            Utc::now().timestamp_millis(),
+            change_source,
+            old_json,
+            new_json,
+            reason
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn config_history_with_conn(
+    conn: &Connection,
+    limit: u32,
+) -> Result<Vec<ConfigHistoryEntry>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, changed_ts_utc, change_source, old_json, new_json, reason
+             FROM config_history
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY changed_ts_utc DESC, id DESC
+             LIMIT ?1",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([limit], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut entries = Vec::new();
+    for row in rows {
+        let (id, changed_ts_utc, change_source, old_json, new_json, reason) =
+            row.map_err(|err| err.to_string())?;
+        entries.push(ConfigHistoryEntry {
+            id,
+            changed_ts_utc,
+            change_source,
+            old_config: serde_json::from_str(&old_json).map_err(|err| err.to_string())?,
+            new_config: serde_json::from_str(&new_json).map_err(|err| err.to_string())?,
+            reason,
+        });
+    }
+    Ok(entries)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_config_history(app: AppHandle, limit: u32) -> Result<Vec<ConfigHistoryEntry>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    config_history_with_conn(&conn, limit)
+}
+
+fn list_fixed_costs_with_conn(
+    conn: &Connection,
+    today: NaiveDate,
+) -> Result<Vec<FixedCost>, String> {
+    cleanup_fixed_cost_payments(conn)?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT fc.id, fc.name, fc.amount, fc.is_active, fc.cadence, p.paid_date_local, p.paid_ts_utc, p.tx_id,
+                    t.amount,
+                    (SELECT COALESCE(SUM(pt.amount), 0) FROM fixed_cost_payments pp
+                       JOIN transactions pt ON pt.id = pp.tx_id
+                      WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key),
+                    (SELECT pp.paid_date_local FROM fixed_cost_payments pp
+                      WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key AND pp.is_partial = 1
+                      ORDER BY pp.paid_ts_utc DESC, pp.id DESC LIMIT 1),
+                    (SELECT MAX(pp.paid_ts_utc) FROM fixed_cost_payments pp
+                      WHERE pp.fixed_cost_id = fc.id AND pp.period_ym = fc.period_key AND pp.is_partial = 1),
+                    fc.due_day,
+                    COALESCE(p.is_skipped, 0)
+             FROM (
+               SELECT *, CASE cadence
+                   WHEN 'weekly' THEN :week_key
+                   WHEN 'yearly' THEN :year_key
+                   ELSE :month_key
+                 END AS period_key
+               FROM fixed_costs
+               WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ) fc
+             LEFT JOIN fixed_cost_payments p
+               ON p.fixed_cost_id = fc.id AND p.period_ym = fc.period_key AND p.is_partial = 0
+             LEFT JOIN transactions t ON t.id = p.tx_id
+             ORDER BY fc.id DESC",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(
+            named_params! {
+                ":month_key": period_key_for_date(CADENCE_MONTHLY, today),
+                ":week_key": period_key_for_date(CADENCE_WEEKLY, today),
+                ":year_key": period_key_for_date(CADENCE_YEARLY, today),
+            },
+            fixed_cost_from_row,
+        )
+        .map_err(|err| err.to_string())?;
+
+    let mut costs = Vec::new();
+    for row in rows {
+        let mut cost = row.map_err(|err| err.to_string())?;
+        cost.is_overdue = is_fixed_cost_overdue(&cost, today);
+        costs.push(cost);
+    }
+
+    Ok(costs)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_savings_goals(app: AppHandle) -> Result<Vec<savings::SavingsGoal>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    savings::list_goals(&conn)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn add_savings_goal(
+    app: AppHandle,
+    name: String,
+    target_amount: i64,
+    saved_amount: Option<i64>,
+) -> Result<savings::SavingsGoal, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let goal = savings::add_goal(&conn, &name, target_amount, saved_amount.unwrap_or(0))?;
+    emit_pools_updated(&app, &conn);
+    Ok(goal)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn update_savings_goal(
+    app: AppHandle,
+    goal_id: i64,
+    name: String,
+    target_amount: i64,
+    saved_amount: i64,
+) -> Result<savings::SavingsGoal, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let goal = savings::update_goal(&conn, goal_id, &name, target_amount, saved_amount)?;
+    emit_pools_updated(&app, &conn);
+    Ok(goal)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn delete_savings_goal(app: AppHandle, goal_id: i64) -> Result<(), String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    savings::delete_goal(&conn, goal_id)?;
+    emit_pools_updated(&app, &conn);
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn contribute_to_goal(
+    app: AppHandle,
+    goal_id: i64,
+    amount: i64,
+    date_local: Option<String>,
+) -> Result<savings::SavingsGoal, String> {
+    let date_local = resolve_date_local(date_local, &SystemClock);
+    parse_date_local(&date_local)?;
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let goal = savings::contribute(&mut conn, goal_id, amount, &date_local)?;
+    emit_pools_updated(&app, &conn);
+    Ok(goal)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_recurring_incomes(
+    app: AppHandle,
+) -> Result<Vec<recurring_income::RecurringIncome>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    recurring_income::list_incomes(&conn, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn add_recurring_income(
+    app: AppHandle,
+    name: String,
+    amount: i64,
+    day_of_month: Option<u32>,
+) -> Result<recurring_income::RecurringIncome, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    recurring_income::add_income(&conn, &name, amount, day_of_month, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn delete_recurring_income(app: AppHandle, recurring_income_id: i64) -> Result<(), String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    recurring_income::delete_income(&mut conn, recurring_income_id)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn mark_recurring_income_received(
+    app: AppHandle,
+    recurring_income_id: i64,
+    date_local: Option<String>,
+) -> Result<recurring_income::RecurringIncome, String> {
+    let date_local = resolve_date_local(date_local, &SystemClock);
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let income = recurring_income::mark_received(&mut conn, recurring_income_id, &date_local)?;
+    emit_pools_updated(&app, &conn);
+    Ok(income)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_unpaid_fixed_costs(
+    app: AppHandle,
+    period_ym: Option<String>,
+) -> Result<insight::UnpaidFixedCosts, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::unpaid_fixed_costs(&conn, period_ym.as_deref(), SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_fixed_costs_total(app: AppHandle) -> Result<insight::FixedCostsTotal, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::fixed_costs_total(&conn, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn project_month_end(app: AppHandle) -> Result<insight::MonthEndProjection, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::project_month_end(&conn, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_average_spend(app: AppHandle, days: u32) -> Result<insight::AverageSpend, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::average_spend(&conn, SystemClock.today(), days)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_fixed_costs(app: AppHandle) -> Result<Vec<FixedCost>, String> {
+    let today = SystemClock.today();
+    list_fixed_costs_for_period(app, period_key_for_date(CADENCE_MONTHLY, today))
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_fixed_costs_for_period(
+    app: AppHandle,
+    period_ym: String,
+) -> Result<Vec<FixedCost>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    list_fixed_costs_for_period_with_conn(&conn, &period_ym, SystemClock.today())
+}
+
+fn list_fixed_costs_for_period_with_conn(
+    conn: &Connection,
+    period_ym: &str,
+    today: NaiveDate,
+) -> Result<Vec<FixedCost>, String> {
+    list_fixed_costs_with_conn(conn, period_reference_date(period_ym, today)?)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_fixed_cost_calendar(
+    app: AppHandle,
+    period_ym: String,
+) -> Result<Vec<FixedCostCalendarEntry>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    fixed_cost_calendar_with_conn(&conn, &period_ym, SystemClock.today())
+}
+
+// Biaya tanpa jatuh tempo bulanan (termasuk mingguan/tahunan) di akhir dengan day = None.
+fn fixed_cost_calendar_with_conn(
+    conn: &Connection,
+    period_ym: &str,
+    today: NaiveDate,
+) -> Result<Vec<FixedCostCalendarEntry>, String> {
+    let reference_date = period_reference_date(period_ym, today)?;
+    let last_day = analytics::days_in_month(reference_date.year(), reference_date.month());
+    let mut entries: Vec<FixedCostCalendarEntry> =
+        list_fixed_costs_for_period_with_conn(conn, period_ym, today)?
+            .into_iter()
+            .filter(|cost| cost.is_active)
+            .map(|cost| {
+                // Jatuh tempo 31 di bulan pendek jatuh ke hari terakhir bulan itu.
+                let day = cost
+                    .due_day
+                    .filter(|_| cost.cadence == CADENCE_MONTHLY)
+                    .map(|due_day| due_day.min(last_day));
+                let paid = cost.paid_date_local.is_some();
+                FixedCostCalendarEntry {
+                    day,
+                    fixed_cost_id: cost.id,
+                    name: cost.name,
+                    amount: cost.amount,
+                    due: day.is_some() && !paid && !cost.is_skipped,
+                    paid,
+                    is_skipped: cost.is_skipped,
+                    is_overdue: cost.is_overdue,
+                }
+            })
+            .collect();
+    entries.sort_by_key(|entry| (entry.day.is_none(), entry.day, entry.fixed_cost_id));
+    Ok(entries)
+}
+
+// Nomor bulan berurutan (tahun * 12 + bulan) dari kunci periode YYYY-MM.
+fn month_index(period_ym: &str) -> Result<i64, String> {
+    let date = NaiveDate::parse_from_str(&format!("{}-01", period_ym), "%Y-%m-%d")
+        .map_err(|err| format!("invalid period_ym: {}", err))?;
+    Ok(i64::from(date.year()) * 12 + i64::from(date.month0()))
+}
+
+// Bulan yang dilewati tidak diharapkan dibayar, jadi tidak mengubah rasio.
+fn fixed_cost_coverage_with_conn(
+    conn: &Connection,
+    fixed_cost_id: i64,
+    today: NaiveDate,
+) -> Result<FixedCostCoverage, String> {
+    if fetch_fixed_cost_cadence(conn, fixed_cost_id)? != CADENCE_MONTHLY {
+        return Err("Cakupan pembayaran hanya untuk biaya tetap bulanan".to_string());
+    }
+    // Lunas penuh, atau cicilan yang totalnya sudah mencapai nominal template.
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.period_ym FROM fixed_cost_payments p
+             JOIN transactions t ON t.id = p.tx_id
+             WHERE p.fixed_cost_id = ?1
+             GROUP BY p.period_ym
+             HAVING MAX(p.is_partial = 0) = 1
+                OR SUM(t.amount) >= (SELECT amount FROM fixed_costs WHERE id = ?1)
+             ORDER BY p.period_ym",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([fixed_cost_id], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?;
+    let mut paid_months = Vec::new();
+    for row in rows {
+        paid_months.push(month_index(&row.map_err(|err| err.to_string())?)?);
+    }
+    let Some(&first_month) = paid_months.first() else {
+        return Ok(FixedCostCoverage {
+            first_period: None,
+            months_elapsed: 0,
+            months_paid: 0,
+            months_skipped: 0,
+            coverage_ratio: 0,
+        });
+    };
+    let first_period = format!(
+        "{:04}-{:02}",
+        first_month.div_euclid(12),
+        first_month.rem_euclid(12) + 1
+    );
+    let current_period = period_key_for_date(CADENCE_MONTHLY, today);
+    let current_month = month_index(&current_period)?;
+    let months_elapsed = std::cmp::max(0, current_month - first_month + 1);
+    let months_paid = paid_months
+        .iter()
+        .filter(|month| **month <= current_month)
+        .count() as i64;
+    let months_skipped: i64 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM fixed_cost_payments
+             WHERE fixed_cost_id = ?1 AND is_skipped = 1 AND period_ym >= ?2 AND period_ym <= ?3",
+            params![fixed_cost_id, &first_period, &current_period],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    let expected = months_elapsed - months_skipped;
+    Ok(FixedCostCoverage {
+        first_period: Some(first_period),
+        months_elapsed,
+        months_paid,
+        months_skipped,
+        coverage_ratio: if expected > 0 {
+            std::cmp::min(100, months_paid * 100 / expected)
+        } else {
+            100
+        },
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_fixed_cost_coverage(
+    app: AppHandle,
+    fixed_cost_id: i64,
+) -> Result<FixedCostCoverage, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    fixed_cost_coverage_with_conn(&conn, fixed_cost_id, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn add_fixed_cost(
+    app: AppHandle,
+    name: String,
+    amount: i64,
+    cadence: Option<String>,
+    due_day: Option<u32>,
+) -> Result<FixedCost, String> {
+    if amount < 0 {
+        return Err("amount must be >= 0".to_string());
+    }
+    let cadence = cadence.unwrap_or_else(|| CADENCE_MONTHLY.to_string());
+    validate_cadence(&cadence)?;
+    validate_due_day(due_day)?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "INSERT INTO fixed_costs (profile_id, name, amount, is_active, cadence, due_day)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, 1, ?3, ?4)",
+        params![name, amount, cadence, due_day],
+    )
+    .map_err(|err| err.to_string())?;
+
+    let id = conn.last_insert_rowid();
+
+    fetch_fixed_cost_current(&conn, id, SystemClock.today())
+}
+
+fn fetch_fixed_cost_current(
+    conn: &Connection,
+    fixed_cost_id: i64,
+    today: NaiveDate,
+) -> Result<FixedCost, String> {
+    let cadence = fetch_fixed_cost_cadence(conn, fixed_cost_id)?;
+    let mut cost =
+        fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_key_for_date(&cadence, today))?;
+    cost.is_overdue = is_fixed_cost_overdue(&cost, today);
+    Ok(cost)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn update_fixed_cost(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    name: String,
+    amount: i64,
+    is_active: bool,
+) -> Result<FixedCost, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost = update_fixed_cost_with_conn(
+        &conn,
+        fixed_cost_id,
+        &name,
+        amount,
+        is_active,
+        SystemClock.today(),
+    )?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_fixed_cost_active(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    is_active: bool,
+) -> Result<FixedCost, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost =
+        set_fixed_cost_active_with_conn(&conn, fixed_cost_id, is_active, SystemClock.today())?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+fn set_fixed_cost_active_with_conn(
+    conn: &Connection,
+    fixed_cost_id: i64,
+    is_active: bool,
+    today: NaiveDate,
+) -> Result<FixedCost, String> {
+    let affected = conn
+        .execute(
+            "UPDATE fixed_costs SET is_active = ?1
+             WHERE id = ?2 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            params![is_active, fixed_cost_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Biaya tetap tidak ditemukan".to_string());
+    }
+    fetch_fixed_cost_current(conn, fixed_cost_id, today)
+}
+
+// Hanya template yang berubah; riwayat pembayaran dan transaksi lama tetap utuh.
+fn update_fixed_cost_with_conn(
+    conn: &Connection,
+    fixed_cost_id: i64,
+    name: &str,
+    amount: i64,
+    is_active: bool,
+    today: NaiveDate,
+) -> Result<FixedCost, String> {
+    if name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if amount < 0 {
+        return Err("amount must be >= 0".to_string());
+    }
+    let affected = conn
+        .execute(
+            "UPDATE fixed_costs SET name = ?1, amount = ?2, is_active = ?3
+             WHERE id = ?4 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            params![name.trim(), amount, is_active, fixed_cost_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Biaya tetap tidak ditemukan".to_string());
+    }
+    fetch_fixed_cost_current(conn, fixed_cost_id, today)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn delete_fixed_cost(app: AppHandle, fixed_cost_id: i64) -> Result<(), String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    // Pembayaran dan transaksinya hanya dihapus bila template milik profil aktif.
+    let owned: i64 = tx
+        .query_row(
+            "SELECT COUNT(*) FROM fixed_costs
+             WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            [fixed_cost_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if owned == 0 {
+        return Err("Biaya tetap tidak ditemukan".to_string());
+    }
+
+    tx.execute(
+        "DELETE FROM transactions WHERE id IN (
+            SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND tx_id IS NOT NULL
+         )",
+        params![fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "DELETE FROM transactions WHERE source = 'fixed_cost' AND fixed_cost_id = ?1",
+        params![fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
+        params![fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "DELETE FROM fixed_costs WHERE id = ?1",
+        params![fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+    emit_pools_updated(&app, &conn);
+    Ok(())
+}
+
+// Bentrok periode (dua pelunasan penuh di period_ym yang sama): pembayaran yang lebih baru
+// dipertahankan. Transaksi pembayaran yang kalah tetap ada sebagai pengeluaran manual
+// supaya saldo tidak berubah.
+fn merge_fixed_costs_with_conn(
+    conn: &mut Connection,
+    keep_id: i64,
+    merge_id: i64,
+    today: NaiveDate,
+) -> Result<FixedCost, String> {
+    if keep_id == merge_id {
+        return Err("Biaya tetap tidak bisa digabung dengan dirinya sendiri".to_string());
+    }
+    let keep_cadence = fetch_fixed_cost_cadence(conn, keep_id)?;
+    let merge_cadence = fetch_fixed_cost_cadence(conn, merge_id)?;
+    if keep_cadence != merge_cadence {
+        return Err("Biaya tetap dengan cadence berbeda tidak bisa digabung".to_string());
+    }
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let losing_payments: Vec<(i64, Option<i64>)> = {
+        let mut stmt = tx
+            .prepare(
+                "SELECT p.id, p.tx_id FROM fixed_cost_payments p
+                 JOIN fixed_cost_payments other
+                   ON other.period_ym = p.period_ym AND other.is_partial = 0 AND other.id <> p.id
+                  AND other.fixed_cost_id IN (?1, ?2)
+                 WHERE p.fixed_cost_id IN (?1, ?2) AND p.is_partial = 0
+                   AND (COALESCE(p.paid_ts_utc, 0), p.id)
+                       < (COALESCE(other.paid_ts_utc, 0), other.id)",
+            )
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map(params![keep_id, merge_id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .map_err(|err| err.to_string())?;
+        let mut payments = Vec::new();
+        for row in rows {
+            payments.push(row.map_err(|err| err.to_string())?);
+        }
+        payments
+    };
+    for (payment_id, tx_id) in losing_payments {
+        tx.execute(
+            "DELETE FROM fixed_cost_payments WHERE id = ?1",
+            [payment_id],
+        )
+        .map_err(|err| err.to_string())?;
+        if let Some(tx_id) = tx_id {
+            tx.execute(
+                "UPDATE transactions SET source = 'manual', fixed_cost_id = NULL WHERE id = ?1",
+                [tx_id],
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+
+    tx.execute(
+        "UPDATE fixed_cost_payments SET fixed_cost_id = ?1 WHERE fixed_cost_id = ?2",
+        params![keep_id, merge_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "UPDATE transactions SET fixed_cost_id = ?1 WHERE fixed_cost_id = ?2",
+        params![keep_id, merge_id],
+    )
+    .map_err(|err| err.to_string())?;
+    // Snapshot undo bisa merujuk ke template yang dihapus.
+    tx.execute(
+        "DELETE FROM fixed_cost_action_log
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        [],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute("DELETE FROM fixed_costs WHERE id = ?1", [merge_id])
+        .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_fixed_cost_current(conn, keep_id, today)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn merge_fixed_costs(app: AppHandle, keep_id: i64, merge_id: i64) -> Result<FixedCost, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost =
+        merge_fixed_costs_with_conn(&mut conn, keep_id, merge_id, SystemClock.today())?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn mark_fixed_cost_paid(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    paid_date_local: Option<String>,
+    amount_override: Option<i64>,
+) -> Result<FixedCost, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost = mark_fixed_cost_paid_with_conn(
+        &mut conn,
+        fixed_cost_id,
+        paid_date_local,
+        amount_override,
+        &SystemClock,
+    )?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn mark_fixed_cost_unpaid(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    paid_date_local: Option<String>,
+) -> Result<FixedCost, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost =
+        mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, paid_date_local, &SystemClock)?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+// Periode dilewati tidak dihitung belum lunas. Tandai lunas menggantikan penanda ini;
+// tandai belum lunas menghapusnya.
+fn skip_fixed_cost_period_with_conn(
+    conn: &mut Connection,
+    fixed_cost_id: i64,
+    period_ym: &str,
+    today: NaiveDate,
+) -> Result<FixedCost, String> {
+    let reference_date = period_reference_date(period_ym, today)?;
+    let cadence = fetch_fixed_cost_cadence(conn, fixed_cost_id)?;
+    let period_key = period_key_for_date(&cadence, reference_date);
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let snapshot = snapshot_fixed_cost_period(&tx, fixed_cost_id, &period_key)?;
+
+    if snapshot.iter().any(|payment| payment.is_skipped != 0) {
+        drop(tx);
+        return fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_key);
+    }
+    if snapshot.iter().any(|payment| payment.tx_id.is_some()) {
+        return Err("Periode ini sudah ada pembayaran; batalkan lunas dulu".to_string());
+    }
+
+    // Sisa baris tanpa transaksi hanyalah pembayaran yatim; penanda menggantikannya.
+    tx.execute(
+        "DELETE FROM fixed_cost_payments WHERE fixed_cost_id = ?1 AND period_ym = ?2",
+        params![fixed_cost_id, &period_key],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute(
+        "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, is_partial, is_skipped)
+         VALUES (?1, ?2, 0, 1)",
+        params![fixed_cost_id, &period_key],
+    )
+    .map_err(|err| err.to_string())?;
+    record_fixed_cost_action(
+        &tx,
+        FIXED_COST_ACTION_SKIPPED,
+        fixed_cost_id,
+        &period_key,
+        &snapshot,
+    )?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_key)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn skip_fixed_cost_period(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    period_ym: String,
+) -> Result<FixedCost, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost = skip_fixed_cost_period_with_conn(
+        &mut conn,
+        fixed_cost_id,
+        &period_ym,
+        SystemClock.today(),
+    )?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+fn mark_fixed_cost_paid_with_conn(
+    conn: &mut Connection,
+    fixed_cost_id: i64,
+    paid_date_local: Option<String>,
+    amount_override: Option<i64>,
+    clock: &dyn Clock,
+) -> Result<FixedCost, String> {
+    if amount_override.is_some_and(|amount| amount < 0) {
+        return Err("amount_override must be >= 0".to_string());
+    }
+    let paid_date_local = resolve_date_local(paid_date_local, clock);
+    let cadence = fetch_fixed_cost_cadence(conn, fixed_cost_id)?;
+    let period_ym = period_key_for_date(&cadence, parse_date_local(&paid_date_local)?);
+    let paid_ts_utc = Utc::now().timestamp_millis();
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let snapshot = snapshot_fixed_cost_period(&tx, fixed_cost_id, &period_ym)?;
+
+    // Template fixed_costs.amount tetap; override hanya untuk transaksi periode ini.
+    let amount = match amount_override {
+        Some(amount) => amount,
+        None => fetch_fixed_cost_amount(&tx, fixed_cost_id)?,
+    };
+    if amount < 0 {
+        return Err("amount must be >= 0".to_string());
+    }
+    // Cicilan yang sudah masuk ikut dihitung; transaksi pelunasan hanya menutup sisanya.
+    let installments_total: i64 = tx
+        .query_row(
+            "SELECT COALESCE(SUM(t.amount), 0) FROM fixed_cost_payments p
+               JOIN transactions t ON t.id = p.tx_id
+             WHERE p.fixed_cost_id = ?1 AND p.period_ym = ?2 AND p.is_partial = 1",
+            params![fixed_cost_id, &period_ym],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    // Sisa nol berarti cicilan sudah menutup tagihan; jangan buat transaksi Rp0.
+    if installments_total > 0 && amount <= installments_total {
+        return Err("Periode ini sudah lunas".to_string());
+    }
+    let amount = amount - installments_total;
+
+    let existing_payment: Option<Option<i64>> = tx
+        .query_row(
+            "SELECT tx_id FROM fixed_cost_payments
+             WHERE fixed_cost_id = ?1 AND period_ym = ?2 AND is_partial = 0",
+            params![fixed_cost_id, &period_ym],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    if let Some(Some(tx_id)) = existing_payment {
+        let tx_exists: i64 = tx
+            .query_row(
+                "SELECT COUNT(*) FROM transactions
+                 WHERE id = ?1 AND kind = 'OUT' AND source = 'fixed_cost' AND fixed_cost_id = ?2",
+                params![tx_id, fixed_cost_id],
+                |row| row.get(0),
+            )
+            .map_err(|err| err.to_string())?;
+        if tx_exists > 0 {
+            if amount_override.is_some() {
+                tx.execute(
+                    "UPDATE transactions SET amount = ?1 WHERE id = ?2",
+                    params![amount, tx_id],
+                )
+                .map_err(|err| err.to_string())?;
+            }
+            tx.execute(
+                "UPDATE fixed_cost_payments
+                 SET paid_date_local = ?1, paid_ts_utc = ?2
+                 WHERE fixed_cost_id = ?3 AND period_ym = ?4 AND is_partial = 0",
+                params![paid_date_local, paid_ts_utc, fixed_cost_id, &period_ym],
+            )
+            .map_err(|err| err.to_string())?;
+            record_fixed_cost_action(
+                &tx,
+                FIXED_COST_ACTION_PAID,
+                fixed_cost_id,
+                &period_ym,
+                &snapshot,
+            )?;
+            tx.commit().map_err(|err| err.to_string())?;
+            return fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym);
+        }
+
+        tx.execute(
+            "DELETE FROM fixed_cost_payments
+             WHERE fixed_cost_id = ?1 AND period_ym = ?2 AND is_partial = 0",
+            params![fixed_cost_id, &period_ym],
+        )
+        .map_err(|err| err.to_string())?;
+    } else if existing_payment.is_some() {
+        tx.execute(
+            "DELETE FROM fixed_cost_payments
+             WHERE fixed_cost_id = ?1 AND period_ym = ?2 AND is_partial = 0",
+            params![fixed_cost_id, &period_ym],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+
+    tx.execute(
+        "INSERT INTO transactions (profile_id, ts_utc, date_local, kind, amount, source, fixed_cost_id)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, 'OUT', ?3, 'fixed_cost', ?4)",
+        params![paid_ts_utc, paid_date_local, amount, fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    let tx_id = tx.last_insert_rowid();
+
+    tx.execute(
+        "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(fixed_cost_id, period_ym) WHERE is_partial = 0 DO UPDATE SET
+           paid_date_local = excluded.paid_date_local,
+           paid_ts_utc = excluded.paid_ts_utc,
+           tx_id = excluded.tx_id",
+        params![
+            fixed_cost_id,
+            &period_ym,
+            paid_date_local,
+            paid_ts_utc,
+            tx_id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+
+    record_fixed_cost_action(
+        &tx,
+        FIXED_COST_ACTION_PAID,
+        fixed_cost_id,
+        &period_ym,
+        &snapshot,
+    )?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym)
+}
+
+fn add_fixed_cost_partial_payment_with_conn(
+    conn: &mut Connection,
+    fixed_cost_id: i64,
+    amount: i64,
+    paid_date_local: Option<String>,
+    clock: &dyn Clock,
+) -> Result<FixedCost, String> {
+    if amount <= 0 {
+        return Err("amount must be > 0".to_string());
+    }
+    let paid_date_local = resolve_date_local(paid_date_local, clock);
+    let cadence = fetch_fixed_cost_cadence(conn, fixed_cost_id)?;
+    let period_ym = period_key_for_date(&cadence, parse_date_local(&paid_date_local)?);
+    let paid_ts_utc = Utc::now().timestamp_millis();
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+
+    let current = fetch_fixed_cost_for_period(&tx, fixed_cost_id, &period_ym)?;
+    if !current.is_active {
+        return Err("Biaya tetap tidak aktif".to_string());
+    }
+    if current.is_skipped {
+        return Err("Periode ini dilewati; batalkan lewati dulu".to_string());
+    }
+    if current.paid_date_local.is_some() {
+        return Err("Periode ini sudah lunas".to_string());
+    }
+    let remaining = current.amount - current.paid_total;
+    if amount > remaining {
+        return Err(format!("Cicilan melebihi sisa tagihan ({})", remaining));
+    }
+
+    tx.execute(
+        "INSERT INTO transactions (profile_id, ts_utc, date_local, kind, amount, source, fixed_cost_id)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2, 'OUT', ?3, 'fixed_cost', ?4)",
+        params![paid_ts_utc, paid_date_local, amount, fixed_cost_id],
+    )
+    .map_err(|err| err.to_string())?;
+    let tx_id = tx.last_insert_rowid();
+
+    tx.execute(
+        "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id, is_partial)
+         VALUES (?1, ?2, ?3, ?4, ?5, 1)",
+        params![fixed_cost_id, &period_ym, paid_date_local, paid_ts_utc, tx_id],
+    )
+    .map_err(|err| err.to_string())?;
+    // Snapshot lama tidak memuat cicilan ini; undo setelahnya akan menghapusnya diam-diam.
+    tx.execute(
+        "DELETE FROM fixed_cost_action_log
+         WHERE profile_id = (SELECT active_profile_id FROM app_state)",
+        [],
+    )
+    .map_err(|err| err.to_string())?;
+
+    tx.commit().map_err(|err| err.to_string())?;
+
+    fetch_fixed_cost_for_period(conn, fixed_cost_id, &period_ym)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn add_fixed_cost_partial_payment(
+    app: AppHandle,
+    fixed_cost_id: i64,
+    amount: i64,
+    paid_date_local: Option<String>,
+) -> Result<FixedCost, String> {
+    let mut conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let fixed_cost = add_fixed_cost_partial_payment_with_conn(
+        &mut conn,
+        fixed_cost_id,
+        amount,
+        paid_date_local,
+        &SystemClock,
+    )?;
+    emit_pools_updated(&app, &conn);
+    Ok(fixed_cost)
+}
+
+fn monthly_summary_with_conn(
+    conn: &Connection,
+    months: u32,
+    today: NaiveDate,
+) -> Result<Vec<MonthlySummary>, String> {
+    if months == 0 {
+        return Err("months must be >= 1".to_string());
+    }
+    // Rentang dimulai dari tanggal 1 bulan ke-(months - 1) sebelum bulan ini.
+    let start = today
+        .with_day(1)
+        .and_then(|first| first.checked_sub_months(Months::new(months - 1)))
+        .ok_or_else(|| "invalid months range".to_string())?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT substr(date_local, 1, 7) AS period_ym,
+                    COALESCE(SUM(CASE WHEN kind = 'IN' THEN amount ELSE 0 END), 0),
+                    COALESCE(SUM(CASE WHEN kind = 'OUT' THEN amount ELSE 0 END), 0)
+             FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state) AND date_local >= ?1
+             GROUP BY period_ym
+             ORDER BY period_ym DESC
+             LIMIT ?2",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![start.format("%Y-%m-%d").to_string(), months],
+            |row| {
+                let total_in: i64 = row.get(1)?;
+                let total_out: i64 = row.get(2)?;
+                Ok(MonthlySummary {
+                    period_ym: row.get(0)?,
+                    total_in,
+                    total_out,
+                    net: total_in - total_out,
+                })
+            },
+        )
+        .map_err(|err| err.to_string())?;
+
+    let mut summaries = Vec::new();
+    for row in rows {
+        summaries.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(summaries)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_monthly_summary(app: AppHandle, months: u32) -> Result<Vec<MonthlySummary>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    monthly_summary_with_conn(&conn, months, SystemClock.today())
+}
+
+fn no_spend_day_results_with_conn(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+    today_local: &str,
+) -> Result<Vec<NoSpendDayResult>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT n.date_local, COALESCE(SUM(t.amount), 0)
+             FROM no_spend_days n
+             LEFT JOIN transactions t
+               ON t.date_local = n.date_local AND t.kind = 'OUT' AND t.profile_id = n.profile_id
+             WHERE n.profile_id = (SELECT active_profile_id FROM app_state)
+               AND n.date_local >= ?1 AND n.date_local <= ?2
+             GROUP BY n.date_local
+             ORDER BY n.date_local ASC",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![start_date, end_date], |row| {
+            let date_local: String = row.get(0)?;
+            let total_out: i64 = row.get(1)?;
+            Ok(NoSpendDayResult {
+                in_progress: date_local.as_str() >= today_local,
+                success: total_out == 0,
+                date_local,
+                total_out,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        results.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(results)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_break_even_daily_spend(app: AppHandle) -> Result<analytics::BreakEvenDailySpend, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::break_even_daily_spend(&conn, SystemClock.now_local())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_period_activity(
+    app: AppHandle,
+    period_ym: String,
+) -> Result<analytics::PeriodActivity, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::period_activity(&conn, &period_ym)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn set_no_spend_day(app: AppHandle, date_local: String) -> Result<(), String> {
+    parse_date_local(&date_local)?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    conn.execute(
+        "INSERT OR IGNORE INTO no_spend_days (profile_id, date_local, created_ts_utc)
+         VALUES ((SELECT active_profile_id FROM app_state), ?1, ?2)",
+        params![date_local, Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn clear_no_spend_day(app: AppHandle, date_local: String) -> Result<(), String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    conn.execute(
+        "DELETE FROM no_spend_days
+         WHERE date_local = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+        params![date_local],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn no_spend_day_results(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<NoSpendDayResult>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let today_local = SystemClock.today_local();
+    no_spend_day_results_with_conn(&conn, &start_date, &end_date, &today_local)
+}
+
+fn today_summary_from(summary: PoolsSummary) -> TodaySummary {
+    TodaySummary {
+        recommended_spend_today: summary.recommended_spend_today,
+        today_out: summary.today_out,
+        today_remaining: summary.today_remaining,
+        today_remaining_clamped: summary.today_remaining_clamped,
+        overspent_today: summary.overspent_today,
+        spend_status: summary.spend_status,
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_today_summary(app: AppHandle) -> Result<TodaySummary, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    Ok(today_summary_from(compute_pools_summary(&conn)?))
+}
+
+// Rasio tidak di-clamp: di atas 100 berarti sudah lewat rekomendasi.
+fn spend_vs_recommended_from(summary: &PoolsSummary) -> SpendVsRecommended {
+    let ratio_percent = if summary.recommended_spend_today > 0 {
+        summary.today_out.saturating_mul(100) / summary.recommended_spend_today
+    } else {
+        0
+    };
+    SpendVsRecommended {
+        recommended: summary.recommended_spend_today,
+        spent: summary.today_out,
+        remaining: summary.today_remaining,
+        ratio_percent,
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_spend_vs_recommended_today(app: AppHandle) -> Result<SpendVsRecommended, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    Ok(spend_vs_recommended_from(&compute_pools_summary(&conn)?))
+}
+
+// Seperti riwayat streak: tabungan tidak punya riwayat, jadi pakai nilai saat ini.
+fn recommended_spend_for_date_with_conn(
+    conn: &Connection,
+    date: NaiveDate,
+) -> Result<RecommendedSpendForDate, String> {
+    let config = fetch_config(conn)?;
+    let date_local = date.format("%Y-%m-%d").to_string();
+    let (total_in, total_out) = transaction_totals(conn, Some(&date_local))?;
+    let net_balance = config.opening_balance + total_in - total_out;
+    let income_deferred = compute_income_deferred(conn, &date_local, config.income_smoothing_days)?;
+    let savings_reserved = savings::reserved_amount(conn)?;
+    let recommended_daily =
+        daily_recommendation(&config, net_balance, income_deferred, savings_reserved)
+            .recommended_daily;
+    Ok(RecommendedSpendForDate {
+        date_local,
+        net_balance,
+        recommended_spend: period_recommendation(&config, recommended_daily),
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_recommended_spend_for_date(
+    app: AppHandle,
+    date_local: String,
+) -> Result<RecommendedSpendForDate, String> {
+    let date = parse_date_local(&date_local)?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    recommended_spend_for_date_with_conn(&conn, date)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_summary_for_date(app: AppHandle, date_local: String) -> Result<TodaySummary, String> {
+    let date = parse_date_local(&date_local)?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    Ok(today_summary_from(compute_pools_summary_for_date(
+        &conn, date,
+    )?))
+}
+
+// Target nol dianggap tercapai.
+fn buffer_progress(net_balance: i64, target_penyangga: i64) -> BufferProgress {
+    let progress_ratio = if target_penyangga <= 0 {
+        100
+    } else {
+        (net_balance.saturating_mul(100) / target_penyangga).clamp(0, 100)
+    };
+    BufferProgress {
+        net_balance,
+        target_penyangga,
+        progress_ratio,
+        amount_remaining: (target_penyangga - net_balance).max(0),
+    }
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_buffer_progress(app: AppHandle) -> Result<BufferProgress, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let summary = compute_pools_summary(&conn)?;
+    Ok(buffer_progress(
+        summary.net_balance,
+        summary.target_penyangga,
+    ))
+}
+
+#[derive(Serialize)]
+struct SimulatedSpend {
+    amount: i64,
+    recommended_spend_today: i64,
+    today_remaining: i64,
+    net_balance: i64,
+    overspent_today: bool,
+}
+
+// Tidak menyentuh database: ringkasan sekarang disesuaikan di memori seolah ada OUT hari ini.
+fn simulate_spend_with_conn(
+    conn: &Connection,
+    amount: i64,
+    today: NaiveDate,
+) -> Result<SimulatedSpend, String> {
+    if amount <= 0 {
+        return Err("amount must be > 0".to_string());
+    }
+    let config = fetch_config(conn)?;
+    let summary = compute_pools_summary_for_date(conn, today)?;
+    let net_balance = summary.net_balance - amount;
+    let recommended_daily = daily_recommendation(
+        &config,
+        net_balance,
+        summary.income_deferred,
+        summary.savings_reserved,
+    )
+    .recommended_daily;
+    let recommended_spend_today = period_recommendation(&config, recommended_daily);
+    let today_remaining =
+        recommended_spend_today + summary.carried_over - (summary.today_out + amount);
+    Ok(SimulatedSpend {
+        amount,
+        recommended_spend_today,
+        today_remaining,
+        net_balance,
+        overspent_today: today_remaining < 0,
+    })
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn simulate_spend(app: AppHandle, amount: i64) -> Result<SimulatedSpend, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    simulate_spend_with_conn(&conn, amount, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_pools_summary(app: AppHandle) -> Result<PoolsSummary, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    compute_pools_summary(&conn)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_coaching_insight(
+    app: AppHandle,
+    locale: Option<String>,
+    verbosity: Option<String>,
+) -> Result<insight::CoachingInsight, String> {
+    let locale = insight_copy::Locale::parse(locale.as_deref())?;
+    let verbosity = insight::Verbosity::parse(verbosity.as_deref())?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::compute_coaching_insight(&conn, &SystemClock, locale, verbosity)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn preview_coaching_insight(
+    app: AppHandle,
+    locale: Option<String>,
+) -> Result<insight::CoachingInsight, String> {
+    let locale = insight_copy::Locale::parse(locale.as_deref())?;
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::preview_coaching_insight(&conn, &SystemClock, locale)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn export_coaching_log(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<String, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::export_coaching_log(&conn, &start_date, &end_date)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn export_insights_markdown(
+    app: AppHandle,
+    path: String,
+    start_date: String,
+    end_date: String,
+) -> Result<usize, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    let (markdown, count) = insight::insights_markdown(&conn, &start_date, &end_date)?;
+    std::fs::write(&path, markdown).map_err(|err| err.to_string())?;
+    Ok(count)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_insight_history(
+    app: AppHandle,
+    limit: u32,
+) -> Result<Vec<insight::InsightHistoryEntry>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::insight_history(&conn, limit)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn clear_coaching_memory(app: AppHandle) -> Result<usize, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    insight::clear_coaching_memory(&conn)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn list_insight_rules() -> Vec<insight::InsightRuleInfo> {
+    insight::list_insight_rules()
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_runway_projection(app: AppHandle) -> Result<analytics::RunwayProjection, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::runway_projection(&conn, SystemClock.now_local())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_daily_spend_series(app: AppHandle, days: u32) -> Result<Vec<analytics::DailySpend>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::daily_spend_series(&conn, SystemClock.now_local(), days)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_net_worth_timeline(
+    app: AppHandle,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<analytics::BalancePoint>, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::net_worth_timeline(&conn, &start_date, &end_date)
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_discipline_streak(app: AppHandle) -> Result<analytics::DisciplineStreak, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::discipline_streak(&conn, SystemClock.today())
+}
+
+#[tauri::command(rename_all = "snake_case")]
+fn get_mtd_pace(app: AppHandle) -> Result<analytics::MtdPace, String> {
+    let conn = db::open_connection(&app).map_err(|err| err.to_string())?;
+    analytics::mtd_pace(&conn, SystemClock.now_local())
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
+        .setup(|app| {
+            db::init_db(app.handle())?;
+            Ok(())
+        })
+        .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_opener::init())
+        .invoke_handler(tauri::generate_handler![
+            add_income,
+            add_expense,
+            batch_add_transactions,
+            get_transaction,
+            list_recent_transactions,
+            get_today_transactions,
+            list_transactions_between,
+            list_transactions_between_paged,
+            get_biggest_expenses,
+            delete_transaction,
+            undo_delete_transaction,
+            delete_transactions_between,
+            check_integrity,
+            get_data_stats,
+            list_profiles,
+            create_profile,
+            switch_profile,
+            find_possible_duplicates,
+            update_transaction,
+            import_transactions_csv,
+            export_backup_json,
+            compact_database,
+            reset_all_data,
+            explain_query_plan,
+            import_backup_json,
+            get_config,
+            get_config_defaults,
+            reset_config_to_defaults,
+            get_config_with_derived,
+            get_buffer_progress,
+            simulate_spend,
+            update_config,
+            set_opening_balance,
+            get_config_history,
+            set_coach_mode,
+            set_memory_retention,
+            set_low_buffer_days,
+            set_spending_frozen,
+            set_memory_recording_enabled,
+            set_exclude_fixed_from_daily,
+            set_time_buckets,
+            list_fixed_costs,
+            list_fixed_costs_for_period,
+            get_fixed_cost_calendar,
+            get_fixed_cost_coverage,
+            get_unpaid_fixed_costs,
+            get_fixed_costs_total,
+            get_average_spend,
+            project_month_end,
+            list_savings_goals,
+            add_savings_goal,
+            update_savings_goal,
+            delete_savings_goal,
+            contribute_to_goal,
+            list_recurring_incomes,
+            add_recurring_income,
+            delete_recurring_income,
+            mark_recurring_income_received,
+            add_fixed_cost,
+            update_fixed_cost,
+            set_fixed_cost_active,
+            delete_fixed_cost,
+            merge_fixed_costs,
+            mark_fixed_cost_paid,
+            mark_fixed_cost_unpaid,
+            skip_fixed_cost_period,
+            undo_last_fixed_cost_action,
+            add_fixed_cost_partial_payment,
+            get_today_summary,
+            get_spend_vs_recommended_today,
+            get_summary_for_date,
+            get_recommended_spend_for_date,
+            get_pools_summary,
+            get_coaching_insight,
+            preview_coaching_insight,
+            export_coaching_log,
+            export_insights_markdown,
+            get_insight_history,
+            clear_coaching_memory,
+            list_insight_rules,
+            get_mtd_pace,
+            get_net_worth_timeline,
+            get_discipline_streak,
+            get_break_even_daily_spend,
+            get_period_activity,
+            list_category_totals,
+            get_income_breakdown,
+            get_spending_breakdown_by_source,
+            get_weekday_spending_profile,
+            get_monthly_summary,
+            get_runway_projection,
+            get_daily_spend_series,
+            set_no_spend_day,
+            clear_no_spend_day,
+            no_spend_day_results
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
     use super::*;
+    use chrono::Local;
     use rusqlite::Connection;
 
-    fn setup_conn(min_floor: i64, max_ceil: i64, resilience_days: i64) -> Connection {
+    fn setup_conn(min_floor: i64, max_ceil: i64, resilience_days: i64) -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        db::init_schema(&conn).expect("init schema");
+        conn.execute(
+            "UPDATE config SET min_floor = ?1, max_ceil = ?2, resilience_days = ?3 WHERE id = 1",
+            params![min_floor, max_ceil, resilience_days],
+        )
+        .expect("update config");
+
+        conn
+    }
+
+    fn setup_fixed_cost_schema(conn: &Connection) {
+        conn.execute_batch(
+            "PRAGMA foreign_keys = ON;
+            CREATE TABLE app_state (
+              id INTEGER PRIMARY KEY CHECK (id = 1),
+              active_profile_id INTEGER NOT NULL
+            );
+            INSERT INTO app_state (id, active_profile_id) VALUES (1, 1);
+            CREATE TABLE fixed_costs (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL DEFAULT 1,
+              name TEXT NOT NULL,
+              amount INTEGER NOT NULL,
+              is_active INTEGER NOT NULL DEFAULT 1,
+              cadence TEXT NOT NULL DEFAULT 'monthly',
+              due_day INTEGER
+            );
+            CREATE TABLE transactions (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL DEFAULT 1,
+              ts_utc INTEGER NOT NULL,
+              date_local TEXT NOT NULL,
+              kind TEXT NOT NULL,
+              amount INTEGER NOT NULL,
+              source TEXT NOT NULL DEFAULT 'manual',
+              fixed_cost_id INTEGER,
+              description TEXT,
+              category TEXT,
+              note TEXT,
+              savings_goal_id INTEGER
+            );
+            CREATE TABLE fixed_cost_payments (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              fixed_cost_id INTEGER NOT NULL,
+              period_ym TEXT NOT NULL,
+              paid_date_local TEXT,
+              paid_ts_utc INTEGER,
+              tx_id INTEGER,
+              is_partial INTEGER NOT NULL DEFAULT 0,
+              is_skipped INTEGER NOT NULL DEFAULT 0,
+              FOREIGN KEY(fixed_cost_id) REFERENCES fixed_costs(id)
+            );
+            CREATE TABLE fixed_cost_action_log (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL DEFAULT 1,
+              action_type TEXT NOT NULL,
+              fixed_cost_id INTEGER NOT NULL,
+              period_ym TEXT NOT NULL,
+              snapshot_json TEXT NOT NULL,
+              created_ts_utc INTEGER NOT NULL
+            );
+            CREATE TABLE recurring_income_receipts (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              recurring_income_id INTEGER NOT NULL,
+              period_ym TEXT NOT NULL,
+              received_date_local TEXT NOT NULL,
+              received_ts_utc INTEGER NOT NULL,
+              tx_id INTEGER
+            );
+            CREATE TABLE transaction_trash (
+              id INTEGER PRIMARY KEY AUTOINCREMENT,
+              profile_id INTEGER NOT NULL DEFAULT 1,
+              transaction_id INTEGER NOT NULL,
+              deleted_ts_utc INTEGER NOT NULL,
+              snapshot_json TEXT NOT NULL
+            );",
+        )
+        .expect("create schema");
+    }
+
+    fn insert_tx(conn: &Connection, kind: &str, amount: i64) {
+        let date_local = Local::now().format("%Y-%m-%d").to_string();
+        insert_tx_on(conn, &date_local, kind, amount);
+    }
+
+    fn insert_tx_on(conn: &Connection, date_local: &str, kind: &str, amount: i64) {
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id, profile_id)
+             VALUES (?1, ?2, ?3, ?4, 'manual', NULL, (SELECT active_profile_id FROM app_state))",
+            params![Utc::now().timestamp_millis(), date_local, kind, amount],
+        )
+        .expect("insert tx");
+    }
+
+    #[test]
+    fn pools_summary_totals_hold_with_many_rows() {
+        let conn = setup_conn(0, 100_000, 1);
+        let start = NaiveDate::from_ymd_opt(2020, 1, 1).expect("date");
+        let mut expected_in = 0;
+        let mut expected_out = 0;
+        for i in 0..3_000 {
+            let date_local = (start + Duration::days(i / 2))
+                .format("%Y-%m-%d")
+                .to_string();
+            if i % 2 == 0 {
+                insert_tx_on(&conn, &date_local, "IN", 10_000 + i);
+                expected_in += 10_000 + i;
+            } else {
+                insert_tx_on(&conn, &date_local, "OUT", 5_000 + i);
+                expected_out += 5_000 + i;
+            }
+        }
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.total_in, expected_in);
+        assert_eq!(summary.total_out, expected_out);
+        assert_eq!(summary.net_balance, expected_in - expected_out);
+        assert_eq!(summary.today_out, 0);
+    }
+
+    #[test]
+    fn summary_for_date_uses_supplied_day() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2025-03-09", "IN", 1600);
+        insert_tx_on(&conn, "2025-03-10", "OUT", 150);
+
+        let day = NaiveDate::from_ymd_opt(2025, 3, 10).expect("date");
+        let summary = compute_pools_summary_for_date(&conn, day).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 100);
+        assert_eq!(summary.today_out, 150);
+        assert_eq!(summary.today_remaining, -50);
+        assert!(summary.overspent_today);
+
+        let next_day = day + Duration::days(1);
+        let summary = compute_pools_summary_for_date(&conn, next_day).expect("summary");
+        assert_eq!(summary.today_out, 0);
+        assert_eq!(summary.today_remaining, 100);
+        assert!(!summary.overspent_today);
+    }
+
+    #[test]
+    fn spend_status_follows_near_limit_ratio_and_strict_mode() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2025-03-09", "IN", 1600);
+        let day = NaiveDate::from_ymd_opt(2025, 3, 10).expect("date");
+        let status = |conn: &Connection| {
+            compute_pools_summary_for_date(conn, day)
+                .expect("summary")
+                .spend_status
+        };
+
+        insert_tx_on(&conn, "2025-03-10", "OUT", 70);
+        assert_eq!(status(&conn), insight::SpendStatus::Under);
+
+        conn.execute("UPDATE config SET coach_mode = 'strict' WHERE id = 1", [])
+            .expect("strict");
+        assert_eq!(status(&conn), insight::SpendStatus::Near);
+
+        conn.execute("UPDATE config SET coach_mode = 'calm' WHERE id = 1", [])
+            .expect("calm");
+        insert_tx_on(&conn, "2025-03-10", "OUT", 10);
+        assert_eq!(status(&conn), insight::SpendStatus::Near);
+
+        insert_tx_on(&conn, "2025-03-10", "OUT", 21);
+        assert_eq!(status(&conn), insight::SpendStatus::Over);
+
+        // Sisa jatah kemarin menaikkan batas hari ini, sama seperti today_remaining.
+        conn.execute("UPDATE config SET carryover_enabled = 1 WHERE id = 1", [])
+            .expect("enable carryover");
+        let summary = compute_pools_summary_for_date(&conn, day).expect("summary");
+        assert_eq!(summary.carried_over, 100);
+        assert!(!summary.overspent_today);
+        assert_eq!(summary.spend_status, insight::SpendStatus::Under);
+    }
+
+    #[test]
+    fn zero_resilience_days_means_no_buffer_target() {
+        let conn = setup_conn(1_000, 50_000, 0);
+        insert_tx(&conn, "IN", 20_500);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.target_penyangga, 0);
+        assert_eq!(summary.dana_fleksibel, 20_500);
+        assert_eq!(summary.recommended_spend_today, 20_000);
+    }
+
+    #[test]
+    fn zero_resilience_days_clamps_to_max_ceil() {
+        let conn = setup_conn(1_000, 50_000, 0);
+        insert_tx(&conn, "IN", 400_000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.dana_fleksibel, 400_000);
+        assert_eq!(summary.recommended_spend_today, 50_000);
+    }
+
+    #[test]
+    fn recommended_min_floor_when_penyangga_tercapai() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx(&conn, "IN", 1100);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 100);
+    }
+
+    #[test]
+    fn recommended_max_ceil_when_fleksibel_besar() {
+        let conn = setup_conn(100, 500, 10);
+        insert_tx(&conn, "IN", 7000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 500);
+    }
+
+    #[test]
+    fn max_ceil_mode_absolute_or_percent_of_net_balance() {
+        let conn = setup_conn(1_000, 5_000, 10);
+        insert_tx(&conn, "IN", 200_000);
+        let today = Local::now().date_naive();
+
+        let absolute = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(
+            fetch_config(&conn).expect("config").max_ceil_mode,
+            "absolute"
+        );
+        assert_eq!(absolute.recommended_spend_today, 5_000);
+
+        conn.execute(
+            "UPDATE config SET max_ceil_mode = 'percent', max_ceil_percent = 5 WHERE id = 1",
+            [],
+        )
+        .expect("percent mode");
+        let capped = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(capped.recommended_spend_today, 10_000);
+
+        conn.execute("UPDATE config SET max_ceil_percent = 50 WHERE id = 1", [])
+            .expect("wider percent");
+        let uncapped = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(uncapped.recommended_spend_today, 19_000);
+    }
+
+    #[test]
+    fn recommended_can_be_below_min_floor_when_penyangga_belum_aman() {
+        let conn = setup_conn(100, 500, 10);
+        insert_tx(&conn, "IN", 500);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 0);
+    }
+
+    #[test]
+    fn recommended_floor_to_thousand() {
+        let conn = setup_conn(0, 100_000, 1);
+        insert_tx(&conn, "IN", 29_285);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 29_000);
+    }
+
+    #[test]
+    fn recommended_rounding_granularity_is_configurable() {
+        let conn = setup_conn(0, 100_000, 1);
+        insert_tx(&conn, "IN", 29_285);
+        assert_eq!(
+            fetch_config(&conn).expect("config").recommend_rounding,
+            1_000
+        );
+
+        conn.execute(
+            "UPDATE config SET recommend_rounding = 100 WHERE id = 1",
+            [],
+        )
+        .expect("rounding 100");
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 29_200);
+
+        conn.execute("UPDATE config SET recommend_rounding = 0 WHERE id = 1", [])
+            .expect("no rounding");
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 29_285);
+
+        assert!([0, 1, 10, 100, 1_000, 100_000]
+            .into_iter()
+            .all(is_valid_rounding));
+        assert!(![-10, 5, 50, 250, 1_001].into_iter().any(is_valid_rounding));
+    }
+
+    #[test]
+    fn weekly_summary_follows_clock_across_month_boundary() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET budget_granularity = 'weekly' WHERE id = 1",
+            [],
+        )
+        .expect("weekly");
+        insert_tx_on(&conn, "2025-05-20", "IN", 500_000);
+        insert_tx_on(&conn, "2025-05-30", "OUT", 12_000);
+
+        // Minggu 2025-06-01 masih satu pekan dengan Jumat 2025-05-30.
+        let sunday = crate::clock::FixedClock::at(2025, 6, 1);
+        let summary = compute_pools_summary_with_clock(&conn, &sunday).expect("summary");
+        assert_eq!(summary.today_out, 12_000);
+        assert_eq!(resolve_date_local(None, &sunday), "2025-06-01");
+
+        let monday = crate::clock::FixedClock::at(2025, 6, 2);
+        let summary = compute_pools_summary_with_clock(&conn, &monday).expect("summary");
+        assert_eq!(summary.today_out, 0);
+    }
+
+    #[test]
+    fn carryover_adds_unspent_budget_from_trailing_days() {
+        let conn = setup_conn(10_000, 100_000, 3);
+        insert_tx_on(&conn, "2025-03-01", "IN", 1_000_000);
+        insert_tx_on(&conn, "2025-03-02", "OUT", 100_000);
+        insert_tx_on(&conn, "2025-03-03", "OUT", 70_000);
+        insert_tx_on(&conn, "2025-03-04", "OUT", 90_000);
+        insert_tx_on(&conn, "2025-03-05", "OUT", 5_000);
+        let today = NaiveDate::from_ymd_opt(2025, 3, 5).expect("date");
+
+        let off = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(off.carried_over, 0);
+        assert_eq!(off.today_remaining, off.recommended_spend_today - 5_000);
+
+        conn.execute("UPDATE config SET carryover_enabled = 1 WHERE id = 1", [])
+            .expect("enable carryover");
+        let on = compute_pools_summary_for_date(&conn, today).expect("summary");
+        // Tiga hari sebelumnya dengan rekomendasi 100rb: sisa 0 + 30rb + 10rb.
+        assert_eq!(on.carried_over, 40_000);
+        assert_eq!(on.today_remaining, off.today_remaining + 40_000);
+    }
+
+    #[test]
+    fn time_buckets_must_increase() {
+        let mut conn = setup_conn(100, 1000, 10);
+        let config = fetch_config(&conn).expect("config");
+        assert_eq!(
+            [
+                config.morning_start_hour,
+                config.midday_start_hour,
+                config.afternoon_start_hour,
+                config.evening_start_hour,
+                config.night_start_hour
+            ],
+            [5, 10, 15, 18, 22]
+        );
+
+        assert!(set_time_buckets_with_conn(&mut conn, [5, 10, 10, 18, 22]).is_err());
+        assert!(set_time_buckets_with_conn(&mut conn, [5, 10, 15, 18, 24]).is_err());
+        let updated = set_time_buckets_with_conn(&mut conn, [7, 11, 16, 20, 23]).expect("set");
+        assert_eq!(updated.morning_start_hour, 7);
+        assert_eq!(updated.night_start_hour, 23);
+    }
+
+    #[test]
+    fn frozen_spending_forces_zero_recommendation() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 50_000);
+        let normal = compute_pools_summary(&conn).expect("summary");
+        assert!(normal.recommended_spend_today > 0);
+
+        let config = set_spending_frozen_with_conn(&mut conn, true).expect("freeze");
+        assert!(config.spending_frozen);
+        let frozen = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(frozen.recommended_spend_today, 0);
+        assert_eq!(frozen.target_penyangga, normal.target_penyangga);
+
+        set_spending_frozen_with_conn(&mut conn, false).expect("unfreeze");
+        let restored = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(
+            restored.recommended_spend_today,
+            normal.recommended_spend_today
+        );
+        let history = config_history_with_conn(&conn, 1).expect("history");
+        assert_eq!(history[0].change_source, "set_spending_frozen");
+    }
+
+    #[test]
+    fn exclude_fixed_from_daily_leaves_fixed_cost_out_of_today_out() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET income_smoothing_days = 1 WHERE id = 1",
+            [],
+        )
+        .expect("disable smoothing");
+        insert_tx(&conn, "IN", 110_000);
+        insert_tx(&conn, "OUT", 3_000);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Sewa', 50000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        let today_local = Local::now().format("%Y-%m-%d").to_string();
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some(today_local),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+
+        let lumped = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(lumped.today_out, 53_000);
+        assert!(lumped.overspent_today);
+
+        let config = set_exclude_fixed_from_daily_with_conn(&mut conn, true).expect("exclude");
+        assert!(config.exclude_fixed_from_daily);
+        let discretionary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(discretionary.today_out, 3_000);
+        assert!(!discretionary.overspent_today);
+        assert_eq!(
+            discretionary.today_remaining,
+            discretionary.recommended_spend_today - 3_000
+        );
+        // Saldo tetap berkurang oleh pembayaran biaya tetap.
+        assert_eq!(discretionary.net_balance, lumped.net_balance);
+    }
+
+    #[test]
+    fn recommended_respects_min_floor_when_not_round() {
+        let conn = setup_conn(20_500, 100_000, 1);
+        insert_tx(&conn, "IN", 20_700);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 20_500);
+    }
+
+    #[test]
+    fn recommended_rounds_down_when_penyangga_belum_aman() {
+        let conn = setup_conn(1_000, 100_000, 2);
+        insert_tx(&conn, "IN", 1_500);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, 0);
+    }
+
+    #[test]
+    fn income_smoothing_off_keeps_payday_spike() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 1_010_000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.income_deferred, 0);
+        assert_eq!(summary.recommended_spend_today, 100_000);
+    }
+
+    #[test]
+    fn income_smoothing_tapers_payday_recommendation() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET income_smoothing_days = 10 WHERE id = 1",
+            [],
+        )
+        .expect("enable smoothing");
+        insert_tx(&conn, "IN", 1_010_000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.net_balance, 1_010_000);
+        assert_eq!(summary.income_deferred, 909_000);
+        assert_eq!(summary.recommended_spend_today, 9_000);
+    }
+
+    #[test]
+    fn income_smoothing_releases_older_income_fully() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET income_smoothing_days = 3 WHERE id = 1",
+            [],
+        )
+        .expect("enable smoothing");
+        let three_days_ago = (Local::now().date_naive() - Duration::days(3))
+            .format("%Y-%m-%d")
+            .to_string();
+        insert_tx_on(&conn, &three_days_ago, "IN", 1_010_000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.income_deferred, 0);
+        assert_eq!(summary.recommended_spend_today, 100_000);
+    }
+
+    #[test]
+    fn opening_balance_counts_toward_net_but_not_income() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET opening_balance = 500000, income_smoothing_days = 10 WHERE id = 1",
+            [],
+        )
+        .expect("set opening balance");
+        insert_tx(&conn, "IN", 10_000);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.total_in, 10_000);
+        assert_eq!(summary.opening_balance, 500_000);
+        assert_eq!(summary.net_balance, 510_000);
+        // Hanya pemasukan sungguhan yang dianggap gajian dan diratakan.
+        assert_eq!(summary.income_deferred, 9_000);
+    }
+
+    #[test]
+    fn overspend_triggers_only_on_transition() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET income_smoothing_days = 1 WHERE id = 1",
+            [],
+        )
+        .expect("disable smoothing");
+        insert_tx(&conn, "IN", 110_000);
+
+        let before = compute_pools_summary(&conn).expect("before");
+        insert_tx(&conn, "OUT", 20_000);
+        let crossed = compute_pools_summary(&conn).expect("crossed");
+        assert!(overspend_triggered(before.overspent_today, &crossed));
+
+        insert_tx(&conn, "OUT", 1_000);
+        let still_over = compute_pools_summary(&conn).expect("still over");
+        assert!(still_over.overspent_today);
+        assert!(!overspend_triggered(crossed.overspent_today, &still_over));
+    }
+
+    #[test]
+    fn recommended_spend_for_date_uses_balance_as_of_that_day() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET income_smoothing_days = 1 WHERE id = 1",
+            [],
+        )
+        .expect("disable smoothing");
+        insert_tx_on(&conn, "2025-05-01", "IN", 110_000);
+        insert_tx_on(&conn, "2025-05-10", "OUT", 50_000);
+
+        let date = |day| NaiveDate::from_ymd_opt(2025, 5, day).expect("date");
+        let before = recommended_spend_for_date_with_conn(&conn, date(5)).expect("before");
+        assert_eq!(before.net_balance, 110_000);
+        assert_eq!(before.recommended_spend, 10_000);
+
+        let after = recommended_spend_for_date_with_conn(&conn, date(10)).expect("after");
+        assert_eq!(after.net_balance, 60_000);
+        assert_eq!(after.recommended_spend, 5_000);
+    }
+
+    #[test]
+    fn savings_goal_reserve_reduces_dana_fleksibel() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 510_000);
+        let goal = savings::add_goal(&conn, "Laptop", 1_000_000, 100_000).expect("goal");
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.savings_reserved, 100_000);
+        assert_eq!(summary.dana_fleksibel, 400_000);
+
+        let today_local = Local::now().format("%Y-%m-%d").to_string();
+        savings::contribute(&mut conn, goal.id, 50_000, &today_local).expect("contribute");
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.net_balance, 460_000);
+        assert_eq!(summary.dana_fleksibel, 350_000);
+        assert_eq!(summary.today_out, 0);
+    }
+
+    #[test]
+    fn weekly_granularity_tracks_iso_week() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 210_000);
+        let today = Local::now().date_naive();
+        let week_start = today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+        insert_tx_on(
+            &conn,
+            &week_start.format("%Y-%m-%d").to_string(),
+            "OUT",
+            3_000,
+        );
+        insert_tx_on(
+            &conn,
+            &(week_start - Duration::days(1))
+                .format("%Y-%m-%d")
+                .to_string(),
+            "OUT",
+            5_000,
+        );
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.budget_granularity, "daily");
+        let daily = summary.recommended_spend_today;
+
+        conn.execute(
+            "UPDATE config SET budget_granularity = 'weekly' WHERE id = 1",
+            [],
+        )
+        .expect("weekly");
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.recommended_spend_today, daily * 7);
+        assert_eq!(summary.today_out, 3_000);
+        assert_eq!(summary.today_remaining, daily * 7 - 3_000);
+    }
+
+    #[test]
+    fn week_start_for_handles_boundary_days() {
+        let date = |day| NaiveDate::from_ymd_opt(2025, 5, day).expect("date");
+        // 2025-05-11 Minggu, 2025-05-12 Senin, 2025-05-17 Sabtu.
+        assert_eq!(week_start_for(date(12), 1), date(12));
+        assert_eq!(week_start_for(date(11), 1), date(5));
+        assert_eq!(week_start_for(date(11), 0), date(11));
+        assert_eq!(week_start_for(date(17), 0), date(11));
+        assert_eq!(week_start_for(date(12), 0), date(11));
+        assert_eq!(week_start_for(date(17), 6), date(17));
+        assert_eq!(week_start_for(date(16), 6), date(10));
+    }
+
+    #[test]
+    fn weekly_granularity_respects_week_start_day() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        conn.execute(
+            "UPDATE config SET budget_granularity = 'weekly', week_start_day = 0 WHERE id = 1",
+            [],
+        )
+        .expect("weekly from sunday");
+        insert_tx_on(&conn, "2025-05-01", "IN", 210_000);
+        insert_tx_on(&conn, "2025-05-10", "OUT", 5_000);
+        insert_tx_on(&conn, "2025-05-11", "OUT", 3_000);
+        insert_tx_on(&conn, "2025-05-12", "OUT", 2_000);
+
+        let monday = NaiveDate::from_ymd_opt(2025, 5, 12).expect("date");
+        let summary = compute_pools_summary_for_date(&conn, monday).expect("summary");
+        assert_eq!(summary.today_out, 5_000);
+
+        conn.execute("UPDATE config SET week_start_day = 1 WHERE id = 1", [])
+            .expect("weekly from monday");
+        let summary = compute_pools_summary_for_date(&conn, monday).expect("summary");
+        assert_eq!(summary.today_out, 2_000);
+    }
+
+    #[test]
+    fn config_with_derived_matches_summary() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 25_000);
+        let today = Local::now().date_naive();
+
+        let derived = config_with_derived_with_conn(&conn, today).expect("derived");
+        let summary = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(derived.target_penyangga, summary.target_penyangga);
+        assert_eq!(derived.dana_fleksibel, summary.dana_fleksibel);
+        assert_eq!(derived.per_day_fleksibel, 1_500);
+        assert!(derived.penyangga_tercapai);
+        assert_eq!(derived.config.min_floor, 1_000);
+    }
+
+    #[test]
+    fn simulate_spend_matches_inserting_the_expense() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 60_000);
+        insert_tx(&conn, "OUT", 2_000);
+        let today = Local::now().date_naive();
+
+        let simulated = simulate_spend_with_conn(&conn, 7_000, today).expect("simulate");
+        let stored: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(stored, 2);
+
+        insert_tx(&conn, "OUT", 7_000);
+        let actual = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(simulated.net_balance, actual.net_balance);
+        assert_eq!(
+            simulated.recommended_spend_today,
+            actual.recommended_spend_today
+        );
+        assert_eq!(simulated.today_remaining, actual.today_remaining);
+        assert_eq!(simulated.overspent_today, actual.overspent_today);
+        assert!(simulated.overspent_today);
+        assert!(simulate_spend_with_conn(&conn, 0, today).is_err());
+    }
+
+    #[test]
+    fn spend_vs_recommended_ratio_handles_zero_recommendation() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        insert_tx(&conn, "IN", 60_000);
+        insert_tx(&conn, "OUT", 2_500);
+        let summary =
+            compute_pools_summary_for_date(&conn, Local::now().date_naive()).expect("summary");
+        let slice = spend_vs_recommended_from(&summary);
+        assert_eq!(slice.recommended, summary.recommended_spend_today);
+        assert_eq!(slice.spent, 2_500);
+        assert_eq!(slice.remaining, summary.today_remaining);
+        assert_eq!(
+            slice.ratio_percent,
+            2_500 * 100 / summary.recommended_spend_today
+        );
+
+        conn.execute("UPDATE config SET spending_frozen = 1 WHERE id = 1", [])
+            .expect("freeze");
+        let frozen =
+            compute_pools_summary_for_date(&conn, Local::now().date_naive()).expect("summary");
+        assert_eq!(spend_vs_recommended_from(&frozen).ratio_percent, 0);
+    }
+
+    #[test]
+    fn data_stats_counts_rows_and_date_range() {
+        let conn = setup_conn(1_000, 100_000, 10);
+        let empty = data_stats_with_conn(&conn).expect("empty stats");
+        assert_eq!(empty.total_transactions, 0);
+        assert!(empty.earliest_date_local.is_none());
+        assert!(empty.latest_date_local.is_none());
+
+        conn.execute_batch(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source) VALUES
+               (1, '2025-03-02', 'IN', 100, 'manual'),
+               (2, '2025-01-15', 'OUT', 50, 'manual'),
+               (3, '2025-02-01', 'OUT', 20, 'manual');
+             INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Sewa', 500, 1);
+             INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+               VALUES (1, '2025-03-02', 'calm', 'Aman.', 'calm');",
+        )
+        .expect("seed");
+        let stats = data_stats_with_conn(&conn).expect("stats");
+        assert_eq!(stats.total_transactions, 3);
+        assert_eq!(stats.total_income_count, 1);
+        assert_eq!(stats.total_expense_count, 2);
+        assert_eq!(stats.earliest_date_local.as_deref(), Some("2025-01-15"));
+        assert_eq!(stats.latest_date_local.as_deref(), Some("2025-03-02"));
+        assert_eq!(stats.fixed_cost_count, 1);
+        assert_eq!(stats.memory_entry_count, 1);
+    }
+
+    #[test]
+    fn buffer_progress_clamps_ratio() {
+        let half = buffer_progress(5_000, 10_000);
+        assert_eq!(half.progress_ratio, 50);
+        assert_eq!(half.amount_remaining, 5_000);
+
+        assert_eq!(buffer_progress(-2_000, 10_000).progress_ratio, 0);
+        let over = buffer_progress(25_000, 10_000);
+        assert_eq!(over.progress_ratio, 100);
+        assert_eq!(over.amount_remaining, 0);
+        assert_eq!(buffer_progress(0, 0).progress_ratio, 100);
+    }
+
+    #[test]
+    fn update_config_records_history_newest_first() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        for max_ceil in [150_000, 200_000] {
+            update_config_with_conn(
+                &mut conn,
+                ConfigPayload {
+                    min_floor: 1_000,
+                    max_ceil,
+                    resilience_days: 10,
+                    income_smoothing_days: None,
+                    record_near_limit: None,
+                    near_limit_ratio: None,
+                    budget_granularity: None,
+                    currency_code: None,
+                    minor_units: None,
+                    large_amount_multiplier: None,
+                    recommend_rounding: None,
+                    carryover_enabled: None,
+                    max_ceil_mode: None,
+                    max_ceil_percent: None,
+                    week_start_day: None,
+                },
+            )
+            .expect("update");
+        }
+
+        let history = config_history_with_conn(&conn, 10).expect("history");
+        assert_eq!(history.len(), 2);
+        assert_eq!(history[0].change_source, "update_config");
+        assert_eq!(history[0].old_config["max_ceil"], 150_000);
+        assert_eq!(history[0].new_config["max_ceil"], 200_000);
+        assert_eq!(history[1].old_config["max_ceil"], 100_000);
+
+        set_coach_mode_with_conn(&mut conn, "strict", None).expect("coach mode");
+        assert!(set_coach_mode_with_conn(&mut conn, "loud", None).is_err());
+        let history = config_history_with_conn(&conn, 1).expect("history");
+        assert_eq!(history[0].change_source, "set_coach_mode");
+        assert_eq!(history[0].old_config["coach_mode"], "calm");
+        assert_eq!(history[0].new_config["coach_mode"], "strict");
+        assert!(history[0].reason.is_none());
+
+        set_coach_mode_with_conn(
+            &mut conn,
+            "watchful",
+            Some(" bulan ini sedang ketat ".to_string()),
+        )
+        .expect("coach mode with reason");
+        let history = config_history_with_conn(&conn, 1).expect("history");
+        assert_eq!(history[0].reason.as_deref(), Some("bulan ini sedang ketat"));
+    }
+
+    #[test]
+    fn memory_retention_defaults_to_200_and_validates_range() {
+        let mut conn = setup_conn(100, 1000, 10);
+        assert_eq!(
+            fetch_config(&conn).expect("config").memory_retention_limit,
+            200
+        );
+
+        assert!(set_memory_retention_with_conn(&mut conn, 19).is_err());
+        assert!(set_memory_retention_with_conn(&mut conn, 5001).is_err());
+        let updated = set_memory_retention_with_conn(&mut conn, 20).expect("retention");
+        assert_eq!(updated.memory_retention_limit, 20);
+        let history = config_history_with_conn(&conn, 1).expect("history");
+        assert_eq!(history[0].change_source, "set_memory_retention");
+    }
+
+    #[test]
+    fn low_buffer_days_setter_validates_range() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        assert_eq!(fetch_config(&conn).expect("config").low_buffer_days, 7);
+        assert!(set_low_buffer_days_with_conn(&mut conn, 0).is_err());
+        assert!(set_low_buffer_days_with_conn(&mut conn, 61).is_err());
+        let updated = set_low_buffer_days_with_conn(&mut conn, 14).expect("low buffer days");
+        assert_eq!(updated.low_buffer_days, 14);
+        let history = config_history_with_conn(&conn, 1).expect("history");
+        assert_eq!(history[0].change_source, "set_low_buffer_days");
+    }
+
+    #[test]
+    fn large_amount_warning_above_multiplier_of_max_ceil() {
+        let conn = setup_conn(10_000, 50_000, 10);
+        let config = fetch_config(&conn).expect("config");
+        assert_eq!(config.large_amount_multiplier, 10);
+        assert!(large_amount_warning(&config, 0, 500_000).is_none());
+        let warning = large_amount_warning(&config, 0, 500_001).expect("warning");
+        assert!(warning.contains("10x"));
+
+        // Mode persen: batas harian 10% dari saldo 2.000.000 = 200.000, ambang 2.000.000.
+        conn.execute(
+            "UPDATE config SET max_ceil_mode = 'percent', max_ceil_percent = 10 WHERE id = 1",
+            [],
+        )
+        .expect("percent mode");
+        let config = fetch_config(&conn).expect("config");
+        assert!(large_amount_warning(&config, 2_000_000, 600_000).is_none());
+        assert!(large_amount_warning(&config, 2_000_000, 2_000_001).is_some());
+        assert!(large_amount_warning(&config, 0, 5_000_000).is_none());
+
+        conn.execute(
+            "UPDATE config SET max_ceil_mode = 'absolute', max_ceil = 0 WHERE id = 1",
+            [],
+        )
+        .expect("disable ceil");
+        let config = fetch_config(&conn).expect("config");
+        assert!(large_amount_warning(&config, 0, 5_000_000).is_none());
+    }
+
+    #[test]
+    fn batch_add_is_atomic_and_names_failing_entry() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        let now = 1_746_873_600_000;
+        let entry = |kind: &str, amount: i64, category: Option<&str>| NewTransaction {
+            kind: kind.to_string(),
+            amount,
+            date_local: Some("2025-05-10".to_string()),
+            description: None,
+            category: category.map(str::to_string),
+            note: None,
+            ts_utc: None,
+        };
+
+        let created = batch_add_transactions_with_conn(
+            &mut conn,
+            vec![
+                entry("OUT", 15_000, Some(" Makan ")),
+                entry("IN", 50_000, None),
+            ],
+            now,
+            &SystemClock,
+        )
+        .expect("batch");
+        assert_eq!(created.len(), 2);
+        assert_eq!(created[0].category.as_deref(), Some("makan"));
+        assert_eq!(created[1].ts_utc, now);
+
+        let err = batch_add_transactions_with_conn(
+            &mut conn,
+            vec![entry("OUT", 5_000, None), entry("OUT", -1, None)],
+            now,
+            &SystemClock,
+        )
+        .err()
+        .expect("negative amount");
+        assert!(err.starts_with("entry 1:"), "{}", err);
+        let err = batch_add_transactions_with_conn(
+            &mut conn,
+            vec![entry("X", 1, None)],
+            now,
+            &SystemClock,
+        )
+        .err()
+        .expect("bad kind");
+        assert!(err.starts_with("entry 0:"), "{}", err);
+
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn zero_amount_rejected_for_income_but_allowed_for_expense() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        let config = fetch_config(&conn).expect("config");
+        let zero = |kind: &str| NewTransaction {
+            kind: kind.to_string(),
+            amount: 0,
+            date_local: Some("2025-05-10".to_string()),
+            description: None,
+            category: None,
+            note: None,
+            ts_utc: None,
+        };
+        let now = 1_746_873_600_000;
+
+        let err =
+            insert_transaction_with_conn(&conn, &config, "manual", zero("IN"), now, &SystemClock)
+                .err()
+                .expect("zero income");
+        assert_eq!(err, "income amount must be > 0");
+        let free =
+            insert_transaction_with_conn(&conn, &config, "manual", zero("OUT"), now, &SystemClock)
+                .expect("zero expense");
+        assert_eq!(free.amount, 0);
+
+        insert_tx_on(&conn, "2025-05-10", "IN", 50_000);
+        let income_id = conn.last_insert_rowid();
+        let err = update_transaction_with_conn(&mut conn, income_id, 0, "2025-05-10", None)
+            .err()
+            .expect("zero income edit");
+        assert_eq!(err, "income amount must be > 0");
+        update_transaction_with_conn(&mut conn, free.id, 0, "2025-05-11", None)
+            .expect("zero expense edit");
+    }
+
+    #[test]
+    fn ts_utc_override_must_be_plausible_epoch_millis() {
+        let now = 1_746_873_600_000;
+        assert_eq!(resolve_ts_utc(None, now), Ok(now));
+        assert_eq!(
+            resolve_ts_utc(Some(1_700_000_000_000), now),
+            Ok(1_700_000_000_000)
+        );
+        // Detik, bukan milidetik.
+        assert!(resolve_ts_utc(Some(1_700_000_000), now).is_err());
+        assert!(resolve_ts_utc(Some(now + 2 * 24 * 60 * 60 * 1000), now).is_err());
+    }
+
+    #[test]
+    fn biggest_expenses_sorted_by_amount_then_newest() {
+        let conn = setup_conn(100, 1000, 10);
+        let insert_at = |ts_utc: i64, date_local: &str, kind: &str, amount: i64| {
+            conn.execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+                 VALUES (?1, ?2, ?3, ?4, 'manual')",
+                params![ts_utc, date_local, kind, amount],
+            )
+            .expect("insert tx");
+        };
+        insert_at(1, "2025-04-01", "OUT", 50_000);
+        insert_at(2, "2025-04-02", "OUT", 80_000);
+        insert_at(3, "2025-04-03", "OUT", 50_000);
+        insert_at(4, "2025-04-04", "IN", 900_000);
+        insert_at(5, "2025-05-01", "OUT", 999_000);
+        insert_at(6, "2025-04-05", "OUT", 10_000);
+
+        let top = biggest_expenses_with_conn(&conn, "2025-04-01", "2025-04-30", 3).expect("top");
+        let picked: Vec<(i64, i64)> = top.iter().map(|tx| (tx.amount, tx.ts_utc)).collect();
+        assert_eq!(picked, vec![(80_000, 2), (50_000, 3), (50_000, 1)]);
+    }
+
+    #[test]
+    fn possible_duplicates_grouped_within_window() {
+        let conn = setup_conn(100, 1000, 10);
+        let insert_at = |ts_utc: i64, date_local: &str, kind: &str, amount: i64| {
+            conn.execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+                 VALUES (?1, ?2, ?3, ?4, 'manual')",
+                params![ts_utc, date_local, kind, amount],
+            )
+            .expect("insert tx");
+        };
+        let minute = 60_000;
+        insert_at(0, "2025-04-01", "OUT", 25_000);
+        insert_at(4 * minute, "2025-04-01", "OUT", 25_000);
+        insert_at(8 * minute, "2025-04-01", "OUT", 25_000);
+        insert_at(60 * minute, "2025-04-01", "OUT", 25_000);
+        insert_at(minute, "2025-04-01", "IN", 25_000);
+        insert_at(minute, "2025-04-02", "OUT", 25_000);
+        insert_at(0, "2025-04-01", "OUT", 30_000);
+
+        let groups = find_possible_duplicates_with_conn(&conn, 5).expect("duplicates");
+        assert_eq!(groups.len(), 1);
+        let ts: Vec<i64> = groups[0].iter().map(|tx| tx.ts_utc).collect();
+        assert_eq!(ts, vec![0, 4 * minute, 8 * minute]);
+        assert!(find_possible_duplicates_with_conn(&conn, -1).is_err());
+    }
+
+    #[test]
+    fn undo_delete_restores_latest_transaction_with_payment() {
+        let mut conn = setup_conn(100, 1000, 10);
+        assert!(undo_delete_transaction_with_conn(&mut conn).is_err());
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Listrik', 200000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let cost = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            1,
+            Some("2025-05-03".into()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        let paid_tx_id = cost.paid_tx_id.expect("paid tx");
+        insert_tx_on(&conn, "2025-05-04", "OUT", 7_500);
+        let manual_id = conn.last_insert_rowid();
+        conn.execute(
+            "UPDATE transactions SET note = 'kopi' WHERE id = ?1",
+            [manual_id],
+        )
+        .expect("note");
+
+        delete_transaction_with_conn(&mut conn, paid_tx_id).expect("delete paid");
+        delete_transaction_with_conn(&mut conn, manual_id).expect("delete manual");
+        assert!(find_transaction(&conn, manual_id).expect("find").is_none());
+
+        let restored = undo_delete_transaction_with_conn(&mut conn).expect("undo manual");
+        assert_eq!(restored.id, manual_id);
+        assert_eq!(restored.note.as_deref(), Some("kopi"));
+
+        let restored = undo_delete_transaction_with_conn(&mut conn).expect("undo paid");
+        assert_eq!(restored.id, paid_tx_id);
+        let payment_tx: i64 = conn
+            .query_row(
+                "SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .expect("payment restored");
+        assert_eq!(payment_tx, paid_tx_id);
+        assert!(undo_delete_transaction_with_conn(&mut conn).is_err());
+    }
+
+    #[test]
+    fn find_transaction_returns_none_for_missing_id() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2025-04-01", "OUT", 7_500);
+        let id = conn.last_insert_rowid();
+
+        let found = find_transaction(&conn, id).expect("find").expect("some");
+        assert_eq!(found.amount, 7_500);
+        assert_eq!(found.date_local, "2025-04-01");
+        assert!(find_transaction(&conn, id + 1).expect("find").is_none());
+    }
+
+    #[test]
+    fn paged_listing_total_uses_same_filter() {
+        let conn = setup_conn(100, 1000, 10);
+        for day in 1..=5 {
+            insert_tx_on(&conn, &format!("2025-04-0{}", day), "OUT", 1_000);
+        }
+        insert_tx_on(&conn, "2025-04-03", "IN", 50_000);
+        insert_tx_on(&conn, "2025-05-01", "OUT", 1_000);
+
+        let page = list_transactions_between_with_conn(
+            &conn,
+            "2025-04-01",
+            "2025-04-30",
+            2,
+            2,
+            Some("OUT"),
+        )
+        .expect("page");
+        let dates: Vec<&str> = page.iter().map(|tx| tx.date_local.as_str()).collect();
+        assert_eq!(dates, vec!["2025-04-03", "2025-04-02"]);
+        assert_eq!(
+            count_transactions_between(&conn, "2025-04-01", "2025-04-30", Some("OUT"))
+                .expect("count"),
+            5
+        );
+        assert_eq!(
+            count_transactions_between(&conn, "2025-04-01", "2025-04-30", None).expect("count"),
+            6
+        );
+    }
+
+    #[test]
+    fn today_transactions_only_returns_given_date_newest_first() {
+        let conn = setup_conn(100, 1000, 10);
+        for (ts, date, amount) in [
+            (1_000, "2025-04-02", 1_000),
+            (3_000, "2025-04-02", 3_000),
+            (2_000, "2025-04-02", 2_000),
+            (4_000, "2025-04-01", 9_000),
+        ] {
+            conn.execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+                 VALUES (?1, ?2, 'OUT', ?3, 'manual', NULL)",
+                params![ts, date, amount],
+            )
+            .expect("insert tx");
+        }
+
+        let items = today_transactions_with_conn(&conn, "2025-04-02").expect("today");
+        let amounts: Vec<i64> = items.iter().map(|tx| tx.amount).collect();
+        assert_eq!(amounts, vec![3_000, 2_000, 1_000]);
+    }
+
+    #[test]
+    fn profiles_keep_transactions_and_config_apart() {
+        let mut conn = setup_conn(10_000, 100_000, 30);
+        insert_tx_on(&conn, "2025-05-01", "IN", 500_000);
+        let today = NaiveDate::from_ymd_opt(2025, 5, 2).expect("date");
+
+        let second = profiles::create_profile(&mut conn, "Usaha").expect("create");
+        profiles::set_active_profile(&conn, second.id).expect("switch");
+        let summary = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!(summary.total_in, 0);
+        assert_eq!(summary.min_floor, 0);
+        insert_tx_on(&conn, "2025-05-01", "OUT", 7_000);
+        assert_eq!(
+            today_transactions_with_conn(&conn, "2025-05-01")
+                .expect("today")
+                .len(),
+            1
+        );
+
+        profiles::set_active_profile(&conn, profiles::DEFAULT_PROFILE_ID).expect("switch back");
+        let summary = compute_pools_summary_for_date(&conn, today).expect("summary");
+        assert_eq!((summary.total_in, summary.total_out), (500_000, 0));
+        assert_eq!(summary.min_floor, 10_000);
+    }
+
+    #[test]
+    fn unpaid_fixed_costs_list_matches_insight_totals() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute_batch(
+            "INSERT INTO fixed_costs (name, amount, is_active, due_day) VALUES ('Kos', 1000000, 1, 5);
+             INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Internet', 300000, 1);
+             INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Gym', 200000, 0);",
+        )
+        .expect("insert fixed_costs");
+        add_fixed_cost_partial_payment_with_conn(
+            &mut conn,
+            1,
+            400_000,
+            Some("2025-05-01".into()),
+            &SystemClock,
+        )
+        .expect("installment");
+        mark_fixed_cost_paid_with_conn(&mut conn, 2, Some("2025-05-02".into()), None, &SystemClock)
+            .expect("paid");
+
+        let today = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
+        let unpaid = insight::unpaid_fixed_costs(&conn, None, today).expect("unpaid");
+        let (count, amount) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-05-10").expect("totals");
+        assert_eq!(unpaid.period_ym, "2025-05");
+        assert_eq!(unpaid.items.len() as i64, count);
+        assert_eq!(unpaid.total_remaining, amount);
+        assert_eq!(unpaid.items[0].remaining, 600_000);
+        assert!(unpaid.items[0].is_overdue);
+
+        let total = insight::fixed_costs_total(&conn, today).expect("total");
+        assert_eq!(total.active_count, 2);
+        assert_eq!(total.active_total, 1_300_000);
+        assert_eq!(total.unpaid_this_month_total, unpaid.total_remaining);
+        assert_eq!(total.paid_this_month_total, 700_000);
+
+        let april = insight::unpaid_fixed_costs(&conn, Some("2025-04"), today).expect("april");
+        assert_eq!(april.items.len(), 2);
+        assert_eq!(april.total_remaining, 1_300_000);
+        assert!(insight::unpaid_fixed_costs(&conn, Some("2025-13"), today).is_err());
+    }
+
+    #[test]
+    fn project_month_end_adds_pending_income_and_unpaid_fixed_costs() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        add_fixed_cost_partial_payment_with_conn(
+            &mut conn,
+            1,
+            400_000,
+            Some("2025-05-01".into()),
+            &SystemClock,
+        )
+        .expect("installment");
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+             VALUES (1, '2025-05-01', 'IN', 2000000, 'manual')",
+            [],
+        )
+        .expect("insert income");
+        let today = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
+        recurring_income::add_income(&conn, "Gaji", 5_000_000, Some(25), today).expect("income");
+
+        let projection = insight::project_month_end(&conn, today).expect("projection");
+        assert_eq!(projection.current_net, 1_600_000);
+        assert_eq!(projection.expected_remaining_income, 5_000_000);
+        assert_eq!(projection.expected_remaining_fixed_costs, 600_000);
+        assert_eq!(projection.projected_month_end_net, 6_000_000);
+    }
+
+    #[test]
+    fn reset_all_data_clears_rows_and_keeps_config_row() {
+        let mut conn = setup_conn(5_000, 50_000, 7);
+        insert_tx(&conn, "IN", 100_000);
+        insert_tx(&conn, "OUT", 20_000);
+        conn.execute_batch(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1);
+             INSERT INTO coaching_memory (ts_utc, date_local, mode, headline, tags)
+               VALUES (1, '2025-05-01', 'calm', 'Tenang.', '');
+             UPDATE config SET coach_mode = 'strict', opening_balance = 75000,
+               spending_frozen = 1 WHERE id = 1;",
+        )
+        .expect("seed");
+
+        let removed = reset_all_data_with_conn(&mut conn, true).expect("reset");
+        assert_eq!(removed["transactions"], 2);
+        assert_eq!(removed["fixed_costs"], 1);
+        assert_eq!(removed["coaching_memory"], 1);
+        assert_eq!(fetch_config(&conn).expect("config").min_floor, 5_000);
+
+        reset_all_data_with_conn(&mut conn, false).expect("reset config");
+        let config = fetch_config(&conn).expect("config");
+        assert_eq!(config.min_floor, 0);
+        assert_eq!(config.max_ceil, 100_000);
+        assert_eq!(config.resilience_days, 30);
+        assert_eq!(config.coach_mode, "calm");
+        assert_eq!(config.opening_balance, 0);
+        assert!(!config.spending_frozen);
+        assert_eq!(config.memory_retention_limit, 200);
+        assert_eq!(
+            compute_pools_summary(&conn).expect("summary").net_balance,
+            0
+        );
+        let rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM config WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .expect("count");
+        assert_eq!(rows, 1);
+    }
+
+    #[test]
+    fn reset_config_to_defaults_keeps_data_and_records_history() {
+        let mut conn = setup_conn(5_000, 50_000, 10);
+        insert_tx(&conn, "IN", 10_000);
+        conn.execute(
+            "UPDATE config SET coach_mode = 'strict', opening_balance = 750000,
+               currency_code = 'USD' WHERE id = 1",
+            [],
+        )
+        .expect("tweak");
+
+        let config = reset_config_to_defaults_with_conn(&mut conn).expect("reset");
+        let defaults = db::config_defaults();
+        assert_eq!(config.min_floor, defaults.min_floor);
+        assert_eq!(config.max_ceil, defaults.max_ceil);
+        assert_eq!(config.resilience_days, defaults.resilience_days);
+        assert_eq!(config.opening_balance, 750_000);
+        assert_eq!(config.currency_code, "USD");
+        assert_eq!(config.coach_mode, "strict");
+
+        let history = config_history_with_conn(&conn, 5).expect("history");
+        assert_eq!(history[0].change_source, "reset_config_to_defaults");
+        assert_eq!(history[0].old_config["min_floor"], 5_000);
+        let tx_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count tx");
+        assert_eq!(tx_count, 1);
+    }
+
+    #[test]
+    fn hari_ketahanan_stop_pemasukan_never_negative() {
+        let conn = setup_conn(100, 500, 10);
+        insert_tx(&conn, "OUT", 500);
+
+        let summary = compute_pools_summary(&conn).expect("summary");
+        assert_eq!(summary.hari_ketahanan_stop_pemasukan, 0);
+    }
+
+    #[test]
+    fn unpaid_deletes_payment_and_transaction_on_legacy_not_null() {
+        let mut conn = Connection::open_in_memory().expect("open in-memory");
+        setup_fixed_cost_schema(&conn);
+
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Internet', 150000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (?1, '2025-01-10', 'OUT', 150000, 'fixed_cost', ?2)",
+            params![Utc::now().timestamp_millis(), fixed_cost_id],
+        )
+        .expect("insert tx");
+        let tx_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+             VALUES (?1, '2025-01', '2025-01-10', ?2, ?3)",
+            params![fixed_cost_id, Utc::now().timestamp_millis(), tx_id],
+        )
+        .expect("insert payment");
+
+        let result = mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None, &SystemClock)
+            .expect("unpaid");
+        assert!(result.paid_date_local.is_none());
+        let payment_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
+                [fixed_cost_id],
+                |row| row.get(0),
+            )
+            .expect("count payments");
+        assert_eq!(payment_count, 0);
+        let tx_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM transactions WHERE id = ?1",
+                [tx_id],
+                |row| row.get(0),
+            )
+            .expect("count tx");
+        assert_eq!(tx_count, 0);
+    }
+
+    #[test]
+    fn merge_fixed_costs_keeps_newer_payment_on_collision() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
+        conn.execute_batch(
+            "INSERT INTO fixed_costs (id, name, amount, is_active) VALUES
+               (1, 'Internet', 300000, 1), (2, 'Wifi', 300000, 1);
+             INSERT INTO transactions (id, ts_utc, date_local, kind, amount, source, fixed_cost_id) VALUES
+               (10, 1, '2025-01-05', 'OUT', 300000, 'fixed_cost', 1),
+               (11, 2, '2025-01-06', 'OUT', 300000, 'fixed_cost', 2),
+               (12, 3, '2025-02-05', 'OUT', 300000, 'fixed_cost', 2);
+             INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id) VALUES
+               (1, '2025-01', '2025-01-05', 1, 10),
+               (2, '2025-01', '2025-01-06', 2, 11),
+               (2, '2025-02', '2025-02-05', 3, 12);",
+        )
+        .expect("seed");
+        let today = NaiveDate::from_ymd_opt(2025, 2, 10).expect("date");
+
+        assert!(merge_fixed_costs_with_conn(&mut conn, 1, 1, today).is_err());
+        let merged = merge_fixed_costs_with_conn(&mut conn, 1, 2, today).expect("merge");
+        assert_eq!(merged.id, 1);
+        assert_eq!(merged.paid_tx_id, Some(12));
+
+        let payments = query_ids(
+            &conn,
+            "SELECT tx_id FROM fixed_cost_payments WHERE fixed_cost_id = 1 ORDER BY period_ym",
+        )
+        .expect("payments");
+        assert_eq!(payments, vec![11, 12]);
+        let (source, fixed_cost_id): (String, Option<i64>) = conn
+            .query_row(
+                "SELECT source, fixed_cost_id FROM transactions WHERE id = 10",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("older tx");
+        assert_eq!((source.as_str(), fixed_cost_id), ("manual", None));
+        assert!(fetch_fixed_cost_cadence(&conn, 2).is_err());
+        assert!(
+            check_integrity_with_conn(&conn)
+                .expect("integrity")
+                .is_clean
+        );
+    }
+
+    #[test]
+    fn unpaid_uses_latest_period_when_none_provided() {
+        let mut conn = Connection::open_in_memory().expect("open in-memory");
+        setup_fixed_cost_schema(&conn);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Sewa', 500000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+
+        conn.execute(
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+             VALUES (?1, '2025-01', '2025-01-05', 1, NULL)",
+            [fixed_cost_id],
+        )
+        .expect("insert old payment");
+        conn.execute(
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+             VALUES (?1, '2025-02', '2025-02-05', 1, NULL)",
+            [fixed_cost_id],
+        )
+        .expect("insert latest payment");
+
+        let _ = mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None, &SystemClock)
+            .expect("unpaid");
+        let remaining: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
+                [fixed_cost_id],
+                |row| row.get(0),
+            )
+            .expect("count payments");
+        assert_eq!(remaining, 1);
+        let period: String = conn
+            .query_row(
+                "SELECT period_ym FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
+                [fixed_cost_id],
+                |row| row.get(0),
+            )
+            .expect("fetch period");
+        assert_eq!(period, "2025-01");
+    }
+
+    #[test]
+    fn paid_recreates_payment_when_tx_missing() {
+        let mut conn = Connection::open_in_memory().expect("open in-memory");
+        setup_fixed_cost_schema(&conn);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Listrik', 200000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+             VALUES (?1, '2025-03', '2025-03-10', 1, 999)",
+            [fixed_cost_id],
+        )
+        .expect("insert stale payment");
+
+        let result = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-03-10".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        assert!(result.paid_date_local.is_some());
+        let payment_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
+                [fixed_cost_id],
+                |row| row.get(0),
+            )
+            .expect("count payments");
+        assert_eq!(payment_count, 1);
+        let tx_count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM transactions WHERE fixed_cost_id = ?1 AND source = 'fixed_cost' AND kind = 'OUT'",
+                [fixed_cost_id],
+                |row| row.get(0),
+            )
+            .expect("count tx");
+        assert_eq!(tx_count, 1);
+    }
+
+    #[test]
+    fn delete_transaction_removes_payment_row() {
+        let mut conn = Connection::open_in_memory().expect("open in-memory");
+        setup_fixed_cost_schema(&conn);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Air', 100000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (1, '2025-04-01', 'OUT', 100000, 'fixed_cost', ?1)",
+            [fixed_cost_id],
+        )
+        .expect("insert tx");
+        let tx_id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+             VALUES (?1, '2025-04', '2025-04-01', 1, ?2)",
+            params![fixed_cost_id, tx_id],
+        )
+        .expect("insert payment");
+
+        delete_transaction_with_conn(&mut conn, tx_id).expect("delete tx");
+        let count: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM fixed_cost_payments WHERE tx_id = ?1",
+                [tx_id],
+                |row| row.get(0),
+            )
+            .expect("count payment");
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn delete_transactions_between_removes_manual_and_imported_rows() {
+        let mut conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2025-04-01", "IN", 50_000);
+        insert_tx_on(&conn, "2025-04-02", "OUT", 10_000);
+        insert_tx_on(&conn, "2025-04-30", "OUT", 20_000);
+        insert_tx_on(&conn, "2025-05-01", "OUT", 30_000);
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (1, '2025-04-10', 'OUT', 100000, 'fixed_cost', NULL)",
+            [],
+        )
+        .expect("insert fixed tx");
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (1, '2025-04-11', 'OUT', 5000, 'import', NULL)",
+            [],
+        )
+        .expect("insert imported tx");
+
+        let deleted = delete_transactions_between_with_conn(
+            &mut conn,
+            "2025-04-01",
+            "2025-04-30",
+            Some("OUT"),
+        )
+        .expect("delete");
+        assert_eq!(deleted, 3);
+        let trashed: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transaction_trash", [], |row| {
+                row.get(0)
+            })
+            .expect("trash count");
+        assert_eq!(trashed, 3);
+        let deleted = delete_transactions_between_with_conn(
+            &mut conn,
+            "2025-04-01",
+            "2025-04-30",
+            Some("OUT"),
+        )
+        .expect("delete again");
+        assert_eq!(deleted, 0);
+        assert!(delete_transactions_between_with_conn(
+            &mut conn,
+            "2025-04-01",
+            "2025-04-30",
+            Some("X")
+        )
+        .is_err());
+
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(remaining, 3);
+    }
+
+    #[test]
+    fn check_integrity_reports_without_repairing() {
         let conn = Connection::open_in_memory().expect("open in-memory");
+        setup_fixed_cost_schema(&conn);
+        assert!(check_integrity_with_conn(&conn).expect("check").is_clean);
+
+        // Basis data lama bisa berisi pembayaran untuk biaya tetap yang sudah dihapus.
         conn.execute_batch(
-            "CREATE TABLE config (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                min_floor INTEGER NOT NULL,
-                max_ceil INTEGER NOT NULL,
-                resilience_days INTEGER NOT NULL,
-                created_ts_utc INTEGER NOT NULL,
-                updated_ts_utc INTEGER NOT NULL
-            );
-            CREATE TABLE transactions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                ts_utc INTEGER NOT NULL,
-                date_local TEXT NOT NULL,
-                kind TEXT NOT NULL,
-                amount INTEGER NOT NULL,
-                source TEXT NOT NULL DEFAULT 'manual',
-                fixed_cost_id INTEGER,
-                description TEXT
-            );",
+            "PRAGMA foreign_keys = OFF;
+             INSERT INTO fixed_costs (id, name, amount, is_active) VALUES (1, 'Air', 100000, 1);
+             INSERT INTO transactions (id, ts_utc, date_local, kind, amount, source, fixed_cost_id)
+               VALUES (10, 1, '2025-04-01', 'OUT', 100000, 'fixed_cost', 1),
+                      (11, 1, '2025-04-02', 'OUT', 5000, 'manual', NULL),
+                      (12, 1, '2025-04-03', 'OUT', 100000, 'fixed_cost', 1);
+             INSERT INTO fixed_cost_payments (id, fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
+               VALUES (20, 1, '2025-04', '2025-04-01', 1, 10),
+                      (21, 1, '2025-03', '2025-03-01', 1, 11),
+                      (22, 9, '2025-02', '2025-02-01', 1, NULL);",
         )
-        .expect("create tables");
+        .expect("seed");
+
+        let report = check_integrity_with_conn(&conn).expect("check");
+        assert!(!report.is_clean);
+        assert_eq!(report.orphaned_payment_ids, vec![21]);
+        assert_eq!(report.unpaid_fixed_cost_tx_ids, vec![12]);
+        assert_eq!(report.missing_fixed_cost_payment_ids, vec![22]);
+        // Pembayaran untuk biaya tetap yang dihapus hanya dilaporkan sekali.
+        let reported = [
+            &report.orphaned_payment_ids,
+            &report.missing_fixed_cost_payment_ids,
+        ]
+        .iter()
+        .filter(|ids| ids.contains(&22))
+        .count();
+        assert_eq!(reported, 1);
+        let payments: i64 = conn
+            .query_row("SELECT COUNT(*) FROM fixed_cost_payments", [], |row| {
+                row.get(0)
+            })
+            .expect("count");
+        assert_eq!(payments, 3);
+    }
+
+    #[test]
+    fn monthly_summary_groups_recent_months_and_skips_empty() {
+        let conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2024-12-20", "IN", 999_000);
+        insert_tx_on(&conn, "2025-01-05", "IN", 500_000);
+        insert_tx_on(&conn, "2025-01-06", "OUT", 120_000);
+        insert_tx_on(&conn, "2025-03-02", "OUT", 80_000);
+        insert_tx_on(&conn, "2025-03-10", "IN", 100_000);
+
+        let today = NaiveDate::from_ymd_opt(2025, 3, 15).expect("date");
+        let summaries = monthly_summary_with_conn(&conn, 3, today).expect("summary");
+        let periods: Vec<&str> = summaries.iter().map(|row| row.period_ym.as_str()).collect();
+        assert_eq!(periods, vec!["2025-03", "2025-01"]);
+        assert_eq!(summaries[0].net, 20_000);
+        assert_eq!(summaries[1].total_in, 500_000);
+        assert_eq!(summaries[1].total_out, 120_000);
+        assert_eq!(summaries[1].net, 380_000);
+    }
+
+    #[test]
+    fn no_spend_day_results_report_success_per_flagged_day() {
+        let conn = setup_conn(100, 1000, 10);
+        for date_local in ["2025-06-01", "2025-06-02", "2025-06-05"] {
+            conn.execute(
+                "INSERT INTO no_spend_days (date_local, created_ts_utc) VALUES (?1, 1)",
+                [date_local],
+            )
+            .expect("insert no spend day");
+        }
+        insert_tx_on(&conn, "2025-06-01", "IN", 5000);
+        insert_tx_on(&conn, "2025-06-02", "OUT", 250);
+        insert_tx_on(&conn, "2025-06-03", "OUT", 400);
+
+        let results =
+            no_spend_day_results_with_conn(&conn, "2025-06-01", "2025-06-30", "2025-06-04")
+                .expect("results");
+        assert_eq!(results.len(), 3);
+        assert!(results[0].success);
+        assert!(!results[1].success);
+        assert_eq!(results[1].total_out, 250);
+        assert!(results[2].success);
+        assert!(results[2].in_progress);
+        assert!(!results[0].in_progress);
+    }
 
-        let now = Utc::now().timestamp_millis();
+    #[test]
+    fn weekly_fixed_cost_status_follows_iso_week() {
+        let mut conn = setup_conn(100, 1000, 10);
         conn.execute(
-            "INSERT INTO config (id, min_floor, max_ceil, resilience_days, created_ts_utc, updated_ts_utc)
-             VALUES (1, ?1, ?2, ?3, ?4, ?4)",
-            params![min_floor, max_ceil, resilience_days, now],
+            "INSERT INTO fixed_costs (name, amount, is_active, cadence)
+             VALUES ('Kebersihan', 50000, 1, 'weekly')",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+
+        let paid = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-01-14".to_string()),
+            None,
+            &SystemClock,
         )
-        .expect("insert config");
+        .expect("paid");
+        assert_eq!(paid.cadence, "weekly");
+        assert_eq!(paid.paid_date_local.as_deref(), Some("2025-01-14"));
 
-        conn
+        let same_week = NaiveDate::from_ymd_opt(2025, 1, 19).expect("date");
+        let costs = list_fixed_costs_with_conn(&conn, same_week).expect("list");
+        assert!(costs[0].paid_date_local.is_some());
+        let (unpaid_count, _) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-01-19").expect("unpaid");
+        assert_eq!(unpaid_count, 0);
+
+        let next_week = NaiveDate::from_ymd_opt(2025, 1, 20).expect("date");
+        let costs = list_fixed_costs_with_conn(&conn, next_week).expect("list");
+        assert!(costs[0].paid_date_local.is_none());
+        let (unpaid_count, unpaid_amount) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-01-20").expect("unpaid");
+        assert_eq!(unpaid_count, 1);
+        assert_eq!(unpaid_amount, 50_000);
+
+        mark_fixed_cost_unpaid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-01-14".to_string()),
+            &SystemClock,
+        )
+        .expect("unpaid");
+        let costs = list_fixed_costs_with_conn(&conn, same_week).expect("list");
+        assert!(costs[0].paid_date_local.is_none());
     }
 
-    fn setup_fixed_cost_schema(conn: &Connection) {
-        conn.execute_batch(
-            "PRAGMA foreign_keys = ON;
-            CREATE TABLE fixed_costs (
-              id INTEGER PRIMARY KEY AUTOINCREMENT,
-              name TEXT NOT NULL,
-              amount INTEGER NOT NULL,
-              is_active INTEGER NOT NULL DEFAULT 1
-            );
-            CREATE TABLE transactions (
-              id INTEGER PRIMARY KEY AUTOINCREMENT,
-              ts_utc INTEGER NOT NULL,
-              date_local TEXT NOT NULL,
-              kind TEXT NOT NULL,
-              amount INTEGER NOT NULL,
-              source TEXT NOT NULL DEFAULT 'manual',
-              fixed_cost_id INTEGER,
-              description TEXT
-            );
-            CREATE TABLE fixed_cost_payments (
-              id INTEGER PRIMARY KEY AUTOINCREMENT,
-              fixed_cost_id INTEGER NOT NULL,
-              period_ym TEXT NOT NULL,
-              paid_date_local TEXT NOT NULL,
-              paid_ts_utc INTEGER NOT NULL,
-              tx_id INTEGER,
-              FOREIGN KEY(fixed_cost_id) REFERENCES fixed_costs(id)
-            );",
+    #[test]
+    fn update_transaction_edits_manual_entry_only() {
+        let mut conn = setup_conn(100, 1000, 10);
+        insert_tx_on(&conn, "2025-03-01", "OUT", 12_000);
+        let manual_id = conn.last_insert_rowid();
+        let original_ts: i64 = conn
+            .query_row(
+                "SELECT ts_utc FROM transactions WHERE id = ?1",
+                [manual_id],
+                |row| row.get(0),
+            )
+            .expect("ts");
+
+        let updated = update_transaction_with_conn(
+            &mut conn,
+            manual_id,
+            21_000,
+            "2025-03-02",
+            Some("  makan dengan klien ".to_string()),
+        )
+        .expect("update");
+        assert_eq!(updated.amount, 21_000);
+        assert_eq!(updated.date_local, "2025-03-02");
+        assert_eq!(updated.ts_utc, original_ts);
+        assert_eq!(updated.note.as_deref(), Some("makan dengan klien"));
+
+        let kept = update_transaction_with_conn(&mut conn, manual_id, 21_000, "2025-03-02", None)
+            .expect("keep note");
+        assert_eq!(kept.note.as_deref(), Some("makan dengan klien"));
+        let cleared = update_transaction_with_conn(
+            &mut conn,
+            manual_id,
+            21_000,
+            "2025-03-02",
+            Some(String::new()),
+        )
+        .expect("clear note");
+        assert!(cleared.note.is_none());
+
+        let result = update_transaction_with_conn(&mut conn, manual_id, 5_000, "2025-13-40", None);
+        assert!(matches!(result, Err(err) if err.contains("invalid date_local")));
+        assert_eq!(
+            fetch_transaction(&conn, manual_id).expect("tx").amount,
+            21_000
+        );
+
+        conn.execute(
+            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
+             VALUES (1, '2025-03-01', 'OUT', 50000, 'fixed_cost', 1)",
+            [],
+        )
+        .expect("insert fixed tx");
+        let fixed_id = conn.last_insert_rowid();
+        assert!(update_transaction_with_conn(&mut conn, fixed_id, 1, "2025-03-01", None).is_err());
+        for source in ["savings", "recurring_income", "import"] {
+            conn.execute(
+                "INSERT INTO transactions (ts_utc, date_local, kind, amount, source)
+                 VALUES (1, '2025-03-01', 'OUT', 50000, ?1)",
+                [source],
+            )
+            .expect("insert linked tx");
+            let linked_id = conn.last_insert_rowid();
+            let result = update_transaction_with_conn(&mut conn, linked_id, 1, "2025-03-01", None);
+            assert!(result.is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn amount_override_changes_payment_not_template() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Listrik', 200000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+
+        let paid = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-04-05".to_string()),
+            Some(235_000),
+            &SystemClock,
+        )
+        .expect("paid");
+        assert_eq!(paid.amount, 200_000);
+        assert_eq!(paid.paid_amount, Some(235_000));
+
+        let paid = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-04-06".to_string()),
+            Some(180_000),
+            &SystemClock,
+        )
+        .expect("repaid");
+        assert_eq!(paid.paid_amount, Some(180_000));
+        let out_total: i64 = conn
+            .query_row(
+                "SELECT SUM(amount) FROM transactions WHERE source = 'fixed_cost'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("sum");
+        assert_eq!(out_total, 180_000);
+
+        let result =
+            mark_fixed_cost_paid_with_conn(&mut conn, fixed_cost_id, None, Some(-1), &SystemClock);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn partial_payments_settle_once_total_reaches_amount() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+
+        let first = add_fixed_cost_partial_payment_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            400_000,
+            Some("2025-05-01".to_string()),
+            &SystemClock,
+        )
+        .expect("first installment");
+        assert_eq!(first.paid_total, 400_000);
+        assert!(first.paid_date_local.is_none());
+        let (unpaid_count, unpaid_amount) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-05-10").expect("unpaid");
+        assert_eq!(unpaid_count, 1);
+        assert_eq!(unpaid_amount, 600_000);
+
+        let second = add_fixed_cost_partial_payment_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            600_000,
+            Some("2025-05-15".to_string()),
+            &SystemClock,
+        )
+        .expect("second installment");
+        assert_eq!(second.paid_total, 1_000_000);
+        assert_eq!(second.paid_date_local.as_deref(), Some("2025-05-15"));
+        let (unpaid_count, _) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-05-20").expect("unpaid");
+        assert_eq!(unpaid_count, 0);
+
+        let reverted = mark_fixed_cost_unpaid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-15".to_string()),
+            &SystemClock,
+        )
+        .expect("unpaid");
+        assert_eq!(reverted.paid_total, 0);
+        let tx_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(tx_count, 0);
+    }
+
+    #[test]
+    fn fixed_cost_payments_default_to_the_given_clock() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        let clock = crate::clock::FixedClock::at(2025, 5, 3);
+
+        let paid = mark_fixed_cost_paid_with_conn(&mut conn, fixed_cost_id, None, None, &clock)
+            .expect("paid");
+        assert_eq!(paid.paid_date_local.as_deref(), Some("2025-05-03"));
+        let unpaid = mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None, &clock)
+            .expect("unpaid");
+        assert!(unpaid.paid_date_local.is_none());
+        let partial =
+            add_fixed_cost_partial_payment_with_conn(&mut conn, fixed_cost_id, 1_000, None, &clock)
+                .expect("installment");
+        assert_eq!(partial.paid_total, 1_000);
+    }
+
+    #[test]
+    fn mark_paid_after_installment_only_pays_the_remainder() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+
+        add_fixed_cost_partial_payment_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            300_000,
+            Some("2025-05-01".to_string()),
+            &SystemClock,
+        )
+        .expect("installment");
+        let paid = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-20".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        assert_eq!(paid.paid_amount, Some(700_000));
+        assert_eq!(paid.paid_total, 1_000_000);
+    }
+
+    #[test]
+    fn mark_paid_rejects_period_settled_by_installments() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        for (amount, date) in [
+            (600_000, "2025-05-01"),
+            (400_000, "2025-05-10"),
+            (200_000, "2025-06-01"),
+        ] {
+            add_fixed_cost_partial_payment_with_conn(
+                &mut conn,
+                fixed_cost_id,
+                amount,
+                Some(date.to_string()),
+                &SystemClock,
+            )
+            .expect("installment");
+        }
+
+        let err = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-20".to_string()),
+            None,
+            &SystemClock,
+        )
+        .err()
+        .expect("settled");
+        assert_eq!(err, "Periode ini sudah lunas");
+        // Override di bawah cicilan juga tidak menyisakan tagihan.
+        assert!(mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-06-20".to_string()),
+            Some(150_000),
+            &SystemClock,
+        )
+        .is_err());
+
+        let zero_rows: i64 = conn
+            .query_row(
+                "SELECT COUNT(*) FROM transactions WHERE amount = 0",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count");
+        assert_eq!(zero_rows, 0);
+    }
+
+    #[test]
+    fn partial_payment_rejects_settled_skipped_inactive_and_overpaying() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
+        let pay = |conn: &mut Connection, amount: i64, date: &str| {
+            add_fixed_cost_partial_payment_with_conn(
+                conn,
+                fixed_cost_id,
+                amount,
+                Some(date.to_string()),
+                &SystemClock,
+            )
+        };
+
+        pay(&mut conn, 400_000, "2025-05-01").expect("installment");
+        let result = pay(&mut conn, 700_000, "2025-05-02");
+        assert!(matches!(result, Err(err) if err.contains("600000")));
+
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-03".into()),
+            None,
+            &SystemClock,
         )
-        .expect("create schema");
-    }
+        .expect("paid");
+        assert!(pay(&mut conn, 1_000, "2025-05-04").is_err());
+
+        let june = NaiveDate::from_ymd_opt(2025, 6, 1).expect("date");
+        skip_fixed_cost_period_with_conn(&mut conn, fixed_cost_id, "2025-06", june).expect("skip");
+        assert!(pay(&mut conn, 1_000, "2025-06-04").is_err());
 
-    fn insert_tx(conn: &Connection, kind: &str, amount: i64) {
-        let date_local = Local::now().format("%Y-%m-%d").to_string();
         conn.execute(
-            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
-             VALUES (?1, ?2, ?3, ?4, 'manual', NULL)",
-            params![Utc::now().timestamp_millis(), date_local, kind, amount],
+            "UPDATE fixed_costs SET is_active = 0 WHERE id = ?1",
+            [fixed_cost_id],
         )
-        .expect("insert tx");
+        .expect("deactivate");
+        assert!(pay(&mut conn, 1_000, "2025-07-04").is_err());
+        let out_total: i64 = conn
+            .query_row(
+                "SELECT SUM(amount) FROM transactions WHERE source = 'fixed_cost'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("sum");
+        assert_eq!(out_total, 1_000_000);
     }
 
     #[test]
-    fn recommended_min_floor_when_penyangga_tercapai() {
-        let conn = setup_conn(100, 1000, 10);
-        insert_tx(&conn, "IN", 1100);
-
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 100);
-    }
+    fn fixed_cost_is_overdue_after_due_day_until_paid() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (name, amount, is_active, due_day) VALUES ('Sewa', 500000, 1, 5)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
 
-    #[test]
-    fn recommended_max_ceil_when_fleksibel_besar() {
-        let conn = setup_conn(100, 500, 10);
-        insert_tx(&conn, "IN", 7000);
+        let on_due_day = NaiveDate::from_ymd_opt(2025, 5, 5).expect("date");
+        let after_due_day = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
+        let costs = list_fixed_costs_with_conn(&conn, on_due_day).expect("list");
+        assert_eq!(costs[0].due_day, Some(5));
+        assert!(!costs[0].is_overdue);
+        let costs = list_fixed_costs_with_conn(&conn, after_due_day).expect("list");
+        assert!(costs[0].is_overdue);
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 500);
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-10".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        let costs = list_fixed_costs_with_conn(&conn, after_due_day).expect("list");
+        assert!(!costs[0].is_overdue);
     }
 
     #[test]
-    fn recommended_can_be_below_min_floor_when_penyangga_belum_aman() {
-        let conn = setup_conn(100, 500, 10);
-        insert_tx(&conn, "IN", 500);
+    fn list_fixed_costs_for_past_period_mixes_paid_and_unpaid() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute_batch(
+            "INSERT INTO fixed_costs (id, name, amount, is_active, due_day) VALUES
+               (1, 'Sewa', 500000, 1, 5), (2, 'Internet', 300000, 1, 20);",
+        )
+        .expect("insert fixed_costs");
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            1,
+            Some("2025-04-03".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid april");
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            2,
+            Some("2025-05-02".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid may");
+        let today = NaiveDate::from_ymd_opt(2025, 5, 10).expect("date");
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 0);
-    }
+        let april = list_fixed_costs_for_period_with_conn(&conn, "2025-04", today).expect("april");
+        let status: Vec<(i64, Option<&str>, bool)> = april
+            .iter()
+            .map(|cost| (cost.id, cost.paid_date_local.as_deref(), cost.is_overdue))
+            .collect();
+        assert_eq!(
+            status,
+            vec![(2, None, true), (1, Some("2025-04-03"), false)]
+        );
 
-    #[test]
-    fn recommended_floor_to_thousand() {
-        let conn = setup_conn(0, 100_000, 1);
-        insert_tx(&conn, "IN", 29_285);
+        let may = list_fixed_costs_for_period_with_conn(&conn, "2025-05", today).expect("may");
+        assert_eq!(may[0].paid_date_local.as_deref(), Some("2025-05-02"));
+        assert!(may[1].paid_date_local.is_none());
+        assert!(may[1].is_overdue);
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 29_000);
+        for invalid in ["2025-4", "2025/04", "2025-13", "25-04-01"] {
+            assert!(list_fixed_costs_for_period_with_conn(&conn, invalid, today).is_err());
+        }
     }
 
     #[test]
-    fn recommended_respects_min_floor_when_not_round() {
-        let conn = setup_conn(20_500, 100_000, 1);
-        insert_tx(&conn, "IN", 20_700);
+    fn fixed_cost_coverage_counts_settled_months_since_first_payment() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute(
+            "INSERT INTO fixed_costs (id, name, amount, is_active) VALUES (1, 'Sewa', 500000, 1)",
+            [],
+        )
+        .expect("insert fixed_cost");
+        let today = NaiveDate::from_ymd_opt(2025, 6, 15).expect("date");
+        let empty = fixed_cost_coverage_with_conn(&conn, 1, today).expect("empty");
+        assert!(empty.first_period.is_none());
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 20_500);
+        for date in ["2025-01-05", "2025-02-05", "2025-04-05", "2025-06-05"] {
+            mark_fixed_cost_paid_with_conn(
+                &mut conn,
+                1,
+                Some(date.to_string()),
+                None,
+                &SystemClock,
+            )
+            .expect("paid");
+        }
+        skip_fixed_cost_period_with_conn(&mut conn, 1, "2025-05", today).expect("skip may");
+
+        let coverage = fixed_cost_coverage_with_conn(&conn, 1, today).expect("coverage");
+        assert_eq!(coverage.first_period.as_deref(), Some("2025-01"));
+        assert_eq!(coverage.months_elapsed, 6);
+        assert_eq!(coverage.months_paid, 4);
+        assert_eq!(coverage.months_skipped, 1);
+        assert_eq!(coverage.coverage_ratio, 80);
     }
 
     #[test]
-    fn recommended_rounds_down_when_penyangga_belum_aman() {
-        let conn = setup_conn(1_000, 100_000, 2);
-        insert_tx(&conn, "IN", 1_500);
+    fn fixed_cost_calendar_orders_by_due_day_and_clamps_to_month() {
+        let mut conn = setup_conn(100, 1000, 10);
+        conn.execute_batch(
+            "INSERT INTO fixed_costs (id, name, amount, is_active, due_day, cadence) VALUES
+               (1, 'Sewa', 500000, 1, 31, 'monthly'),
+               (2, 'Internet', 300000, 1, 5, 'monthly'),
+               (3, 'Domain', 150000, 1, NULL, 'yearly'),
+               (4, 'Gym', 200000, 0, 10, 'monthly'),
+               (5, 'Listrik', 250000, 1, 3, 'monthly');",
+        )
+        .expect("insert fixed_costs");
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            2,
+            Some("2025-02-03".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        let today = NaiveDate::from_ymd_opt(2025, 2, 10).expect("date");
+        skip_fixed_cost_period_with_conn(&mut conn, 5, "2025-02", today).expect("skip");
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.recommended_spend_today, 0);
-    }
+        let calendar = fixed_cost_calendar_with_conn(&conn, "2025-02", today).expect("calendar");
+        let days: Vec<(i64, Option<u32>, bool, bool, bool, bool)> = calendar
+            .iter()
+            .map(|entry| {
+                (
+                    entry.fixed_cost_id,
+                    entry.day,
+                    entry.due,
+                    entry.paid,
+                    entry.is_skipped,
+                    entry.is_overdue,
+                )
+            })
+            .collect();
+        assert_eq!(
+            days,
+            vec![
+                (5, Some(3), false, false, true, false),
+                (2, Some(5), false, true, false, false),
+                (1, Some(28), true, false, false, false),
+                (3, None, false, false, false, false)
+            ]
+        );
 
-    #[test]
-    fn hari_ketahanan_stop_pemasukan_never_negative() {
-        let conn = setup_conn(100, 500, 10);
-        insert_tx(&conn, "OUT", 500);
+        // Bulan depan: semua biaya bulanan tampil sebagai jatuh tempo.
+        let april = fixed_cost_calendar_with_conn(&conn, "2025-04", today).expect("calendar");
+        let due: Vec<i64> = april
+            .iter()
+            .filter(|entry| entry.due)
+            .map(|entry| entry.fixed_cost_id)
+            .collect();
+        assert_eq!(due, vec![5, 2, 1]);
 
-        let summary = compute_pools_summary(&conn).expect("summary");
-        assert_eq!(summary.hari_ketahanan_stop_pemasukan, 0);
+        let march = NaiveDate::from_ymd_opt(2025, 3, 10).expect("date");
+        let calendar = fixed_cost_calendar_with_conn(&conn, "2025-03", march).expect("calendar");
+        assert!(calendar.iter().any(|entry| entry.fixed_cost_id == 5
+            && entry.due
+            && entry.is_overdue
+            && !entry.is_skipped));
     }
 
     #[test]
-    fn unpaid_deletes_payment_and_transaction_on_legacy_not_null() {
-        let mut conn = Connection::open_in_memory().expect("open in-memory");
-        setup_fixed_cost_schema(&conn);
-
+    fn update_fixed_cost_keeps_payment_history() {
+        let mut conn = setup_conn(100, 1000, 10);
         conn.execute(
-            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Internet', 150000, 1)",
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Kos', 1000000, 1)",
             [],
         )
         .expect("insert fixed_cost");
         let fixed_cost_id = conn.last_insert_rowid();
-        conn.execute(
-            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
-             VALUES (?1, '2025-01-10', 'OUT', 150000, 'fixed_cost', ?2)",
-            params![Utc::now().timestamp_millis(), fixed_cost_id],
-        )
-        .expect("insert tx");
-        let tx_id = conn.last_insert_rowid();
-        conn.execute(
-            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-01', '2025-01-10', ?2, ?3)",
-            params![fixed_cost_id, Utc::now().timestamp_millis(), tx_id],
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-02".to_string()),
+            None,
+            &SystemClock,
         )
-        .expect("insert payment");
+        .expect("paid");
 
-        let result =
-            mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None).expect("unpaid");
-        assert!(result.paid_date_local.is_none());
-        let payment_count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
-                [fixed_cost_id],
-                |row| row.get(0),
-            )
-            .expect("count payments");
-        assert_eq!(payment_count, 0);
-        let tx_count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM transactions WHERE id = ?1",
-                [tx_id],
-                |row| row.get(0),
-            )
-            .expect("count tx");
-        assert_eq!(tx_count, 0);
+        let today = NaiveDate::from_ymd_opt(2025, 5, 20).expect("date");
+        let updated =
+            update_fixed_cost_with_conn(&conn, fixed_cost_id, " Sewa ", 1_200_000, true, today)
+                .expect("update");
+        assert_eq!(updated.name, "Sewa");
+        assert_eq!(updated.amount, 1_200_000);
+        assert_eq!(updated.paid_amount, Some(1_000_000));
+        assert_eq!(updated.paid_date_local.as_deref(), Some("2025-05-02"));
+
+        assert!(update_fixed_cost_with_conn(&conn, fixed_cost_id, " ", 1, true, today).is_err());
+        assert!(
+            update_fixed_cost_with_conn(&conn, fixed_cost_id, "Sewa", -1, true, today).is_err()
+        );
+        assert!(
+            update_fixed_cost_with_conn(&conn, fixed_cost_id + 1, "Sewa", 1, true, today).is_err()
+        );
     }
 
     #[test]
-    fn unpaid_uses_latest_period_when_none_provided() {
-        let mut conn = Connection::open_in_memory().expect("open in-memory");
-        setup_fixed_cost_schema(&conn);
+    fn inactive_fixed_cost_is_listed_but_not_counted_unpaid() {
+        let conn = setup_conn(100, 1000, 10);
         conn.execute(
-            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Sewa', 500000, 1)",
+            "INSERT INTO fixed_costs (name, amount, is_active, due_day) VALUES ('Gym', 300000, 1, 1)",
             [],
         )
         .expect("insert fixed_cost");
         let fixed_cost_id = conn.last_insert_rowid();
+        let today = NaiveDate::from_ymd_opt(2025, 1, 10).expect("date");
 
+        let paused = set_fixed_cost_active_with_conn(&conn, fixed_cost_id, false, today)
+            .expect("deactivate");
+        assert!(!paused.is_active);
+        assert!(!paused.is_overdue);
+        let (unpaid_count, _) =
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-01-10").expect("unpaid");
+        assert_eq!(unpaid_count, 0);
+        let costs = list_fixed_costs_with_conn(&conn, today).expect("list");
+        assert_eq!(costs.len(), 1);
+        assert!(!costs[0].is_active);
+
+        let resumed =
+            set_fixed_cost_active_with_conn(&conn, fixed_cost_id, true, today).expect("activate");
+        assert!(resumed.is_active);
+        assert!(resumed.is_overdue);
+        assert!(set_fixed_cost_active_with_conn(&conn, fixed_cost_id + 1, true, today).is_err());
+    }
+
+    #[test]
+    fn yearly_fixed_cost_stays_paid_for_the_whole_year() {
+        let mut conn = setup_conn(100, 1000, 10);
         conn.execute(
-            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-01', '2025-01-05', 1, NULL)",
-            [fixed_cost_id],
-        )
-        .expect("insert old payment");
-        conn.execute(
-            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-02', '2025-02-05', 1, NULL)",
-            [fixed_cost_id],
+            "INSERT INTO fixed_costs (name, amount, is_active, cadence)
+             VALUES ('Domain', 150000, 1, 'yearly')",
+            [],
         )
-        .expect("insert latest payment");
+        .expect("insert fixed_cost");
+        let fixed_cost_id = conn.last_insert_rowid();
 
-        let _ = mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None).expect("unpaid");
-        let remaining: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
-                [fixed_cost_id],
-                |row| row.get(0),
-            )
-            .expect("count payments");
-        assert_eq!(remaining, 1);
-        let period: String = conn
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-02-10".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid");
+        let payment_period: String = conn
             .query_row(
                 "SELECT period_ym FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
                 [fixed_cost_id],
                 |row| row.get(0),
             )
-            .expect("fetch period");
-        assert_eq!(period, "2025-01");
+            .expect("period");
+        assert_eq!(payment_period, "2025");
+
+        let december = NaiveDate::from_ymd_opt(2025, 12, 31).expect("date");
+        let costs = list_fixed_costs_with_conn(&conn, december).expect("list");
+        assert!(costs[0].paid_date_local.is_some());
+
+        let next_year = NaiveDate::from_ymd_opt(2026, 1, 1).expect("date");
+        let costs = list_fixed_costs_with_conn(&conn, next_year).expect("list");
+        assert!(costs[0].paid_date_local.is_none());
     }
 
     #[test]
-    fn paid_recreates_payment_when_tx_missing() {
+    fn unpaid_is_idempotent() {
         let mut conn = Connection::open_in_memory().expect("open in-memory");
         setup_fixed_cost_schema(&conn);
         conn.execute(
-            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Listrik', 200000, 1)",
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Wifi', 250000, 1)",
             [],
         )
         .expect("insert fixed_cost");
         let fixed_cost_id = conn.last_insert_rowid();
         conn.execute(
             "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-03', '2025-03-10', 1, 999)",
+             VALUES (?1, '2025-05', '2025-05-01', 1, NULL)",
             [fixed_cost_id],
         )
-        .expect("insert stale payment");
+        .expect("insert payment");
 
-        let result = mark_fixed_cost_paid_with_conn(
-            &mut conn,
-            fixed_cost_id,
-            Some("2025-03-10".to_string()),
-        )
-        .expect("paid");
-        assert!(result.paid_date_local.is_some());
-        let payment_count: i64 = conn
+        mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None, &SystemClock)
+            .expect("unpaid");
+        mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None, &SystemClock)
+            .expect("unpaid again");
+        let count: i64 = conn
             .query_row(
                 "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
                 [fixed_cost_id],
                 |row| row.get(0),
             )
             .expect("count payments");
-        assert_eq!(payment_count, 1);
-        let tx_count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM transactions WHERE fixed_cost_id = ?1 AND source = 'fixed_cost' AND kind = 'OUT'",
-                [fixed_cost_id],
-                |row| row.get(0),
-            )
-            .expect("count tx");
-        assert_eq!(tx_count, 1);
+        assert_eq!(count, 0);
     }
 
     #[test]
-    fn delete_transaction_removes_payment_row() {
-        let mut conn = Connection::open_in_memory().expect("open in-memory");
-        setup_fixed_cost_schema(&conn);
+    fn undo_reverses_last_paid_and_unpaid_action() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
         conn.execute(
-            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Air', 100000, 1)",
+            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Wifi', 250000, 1)",
             [],
         )
         .expect("insert fixed_cost");
         let fixed_cost_id = conn.last_insert_rowid();
-        conn.execute(
-            "INSERT INTO transactions (ts_utc, date_local, kind, amount, source, fixed_cost_id)
-             VALUES (1, '2025-04-01', 'OUT', 100000, 'fixed_cost', ?1)",
-            [fixed_cost_id],
-        )
-        .expect("insert tx");
-        let tx_id = conn.last_insert_rowid();
-        conn.execute(
-            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-04', '2025-04-01', 1, ?2)",
-            params![fixed_cost_id, tx_id],
+
+        let paid = mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-03".to_string()),
+            None,
+            &SystemClock,
         )
-        .expect("insert payment");
+        .expect("paid");
+        let tx_id = paid.paid_tx_id.expect("tx id");
 
-        delete_transaction_with_conn(&mut conn, tx_id).expect("delete tx");
-        let count: i64 = conn
+        mark_fixed_cost_unpaid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-03".to_string()),
+            &SystemClock,
+        )
+        .expect("unpaid");
+        let restored = undo_last_fixed_cost_action_with_conn(&mut conn).expect("undo unpaid");
+        assert_eq!(restored.paid_tx_id, Some(tx_id));
+        assert_eq!(restored.paid_date_local.as_deref(), Some("2025-05-03"));
+        let amount: i64 = conn
             .query_row(
-                "SELECT COUNT(*) FROM fixed_cost_payments WHERE tx_id = ?1",
+                "SELECT amount FROM transactions WHERE id = ?1",
                 [tx_id],
                 |row| row.get(0),
             )
-            .expect("count payment");
-        assert_eq!(count, 0);
+            .expect("restored tx");
+        assert_eq!(amount, 250000);
+
+        // Hanya satu aksi yang disimpan.
+        assert!(undo_last_fixed_cost_action_with_conn(&mut conn).is_err());
+
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-06-01".to_string()),
+            None,
+            &SystemClock,
+        )
+        .expect("paid june");
+        let reverted = undo_last_fixed_cost_action_with_conn(&mut conn).expect("undo paid");
+        assert!(reverted.paid_tx_id.is_none());
+        let tx_count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count tx");
+        assert_eq!(tx_count, 1);
     }
 
     #[test]
-    fn unpaid_is_idempotent() {
-        let mut conn = Connection::open_in_memory().expect("open in-memory");
-        setup_fixed_cost_schema(&conn);
+    fn skipped_period_is_not_unpaid_and_survives_cleanup() {
+        let mut conn = setup_conn(1_000, 100_000, 10);
         conn.execute(
-            "INSERT INTO fixed_costs (name, amount, is_active) VALUES ('Wifi', 250000, 1)",
+            "INSERT INTO fixed_costs (name, amount, is_active, due_day) VALUES ('Gym', 300000, 1, 1)",
             [],
         )
         .expect("insert fixed_cost");
         let fixed_cost_id = conn.last_insert_rowid();
-        conn.execute(
-            "INSERT INTO fixed_cost_payments (fixed_cost_id, period_ym, paid_date_local, paid_ts_utc, tx_id)
-             VALUES (?1, '2025-05', '2025-05-01', 1, NULL)",
-            [fixed_cost_id],
+        let today = NaiveDate::from_ymd_opt(2025, 5, 20).expect("date");
+
+        let skipped = skip_fixed_cost_period_with_conn(&mut conn, fixed_cost_id, "2025-05", today)
+            .expect("skip");
+        assert!(skipped.is_skipped);
+        assert!(skipped.paid_date_local.is_none());
+
+        let costs = list_fixed_costs_with_conn(&conn, today).expect("list");
+        assert!(costs[0].is_skipped);
+        assert!(!costs[0].is_overdue);
+        assert_eq!(
+            insight::fetch_unpaid_fixed_cost_totals(&conn, "2025-05-20").expect("unpaid"),
+            (0, 0)
+        );
+        assert!(
+            check_integrity_with_conn(&conn)
+                .expect("integrity")
+                .is_clean
+        );
+
+        mark_fixed_cost_paid_with_conn(
+            &mut conn,
+            fixed_cost_id,
+            Some("2025-05-21".to_string()),
+            None,
+            &SystemClock,
         )
-        .expect("insert payment");
+        .expect("paid");
+        assert!(
+            skip_fixed_cost_period_with_conn(&mut conn, fixed_cost_id, "2025-05", today).is_err()
+        );
 
-        mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None).expect("unpaid");
-        mark_fixed_cost_unpaid_with_conn(&mut conn, fixed_cost_id, None).expect("unpaid again");
-        let count: i64 = conn
-            .query_row(
-                "SELECT COUNT(*) FROM fixed_cost_payments WHERE fixed_cost_id = ?1",
-                [fixed_cost_id],
-                |row| row.get(0),
-            )
-            .expect("count payments");
-        assert_eq!(count, 0);
+        let restored = undo_last_fixed_cost_action_with_conn(&mut conn).expect("undo paid");
+        assert!(restored.is_skipped);
     }
 }
@@ -0,0 +1,123 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+pub const DEFAULT_PROFILE_ID: i64 = 1;
+
+#[derive(Serialize)]
+pub struct Profile {
+    pub id: i64,
+    pub name: String,
+    pub created_ts_utc: i64,
+    pub is_active: bool,
+}
+
+fn profile_from_row(row: &rusqlite::Row) -> rusqlite::Result<Profile> {
+    Ok(Profile {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        created_ts_utc: row.get(2)?,
+        is_active: row.get::<_, i64>(3)? != 0,
+    })
+}
+
+pub fn fetch_profile(conn: &Connection, profile_id: i64) -> Result<Profile, String> {
+    conn.query_row(
+        "SELECT p.id, p.name, p.created_ts_utc,
+                p.id = COALESCE((SELECT active_profile_id FROM app_state WHERE id = 1), ?2)
+         FROM profiles p WHERE p.id = ?1",
+        params![profile_id, DEFAULT_PROFILE_ID],
+        profile_from_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())?
+    .ok_or_else(|| "Profil tidak ditemukan".to_string())
+}
+
+pub fn list_profiles(conn: &Connection) -> Result<Vec<Profile>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT p.id, p.name, p.created_ts_utc,
+                    p.id = COALESCE((SELECT active_profile_id FROM app_state WHERE id = 1), ?1)
+             FROM profiles p ORDER BY p.id ASC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([DEFAULT_PROFILE_ID], profile_from_row)
+        .map_err(|err| err.to_string())?;
+
+    let mut profiles = Vec::new();
+    for row in rows {
+        profiles.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(profiles)
+}
+
+pub fn create_profile(conn: &mut Connection, name: &str) -> Result<Profile, String> {
+    if name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    tx.execute(
+        "INSERT INTO profiles (name, created_ts_utc) VALUES (?1, ?2)",
+        params![name.trim(), Utc::now().timestamp_millis()],
+    )
+    .map_err(|err| err.to_string())?;
+    let profile_id = tx.last_insert_rowid();
+    crate::db::insert_profile_config(&tx, profile_id)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    fetch_profile(conn, profile_id)
+}
+
+pub fn set_active_profile(conn: &Connection, profile_id: i64) -> Result<Profile, String> {
+    fetch_profile(conn, profile_id)?;
+    conn.execute(
+        "INSERT INTO app_state (id, active_profile_id) VALUES (1, ?1)
+         ON CONFLICT(id) DO UPDATE SET active_profile_id = excluded.active_profile_id",
+        [profile_id],
+    )
+    .map_err(|err| err.to_string())?;
+    fetch_profile(conn, profile_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    #[test]
+    fn default_profile_is_active_until_switched() {
+        let mut conn = setup_conn();
+        let profiles = list_profiles(&conn).expect("list");
+        assert_eq!(profiles.len(), 1);
+        assert_eq!(profiles[0].id, DEFAULT_PROFILE_ID);
+        assert!(profiles[0].is_active);
+
+        let partner = create_profile(&mut conn, "  Pasangan ").expect("create");
+        assert_eq!(partner.name, "Pasangan");
+        assert!(!partner.is_active);
+        assert!(create_profile(&mut conn, " ").is_err());
+        let config_rows: i64 = conn
+            .query_row("SELECT COUNT(*) FROM config", [], |row| row.get(0))
+            .expect("config rows");
+        assert_eq!(config_rows, 2);
+
+        set_active_profile(&conn, partner.id).expect("switch");
+        assert!(fetch_profile(&conn, partner.id).expect("fetch").is_active);
+        let active: Vec<i64> = list_profiles(&conn)
+            .expect("list")
+            .iter()
+            .filter(|profile| profile.is_active)
+            .map(|profile| profile.id)
+            .collect();
+        assert_eq!(active, vec![partner.id]);
+
+        assert!(set_active_profile(&conn, 99).is_err());
+        assert!(fetch_profile(&conn, partner.id).expect("fetch").is_active);
+    }
+}
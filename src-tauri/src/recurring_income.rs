@@ -0,0 +1,277 @@
+use chrono::{NaiveDate, Utc};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+use crate::{parse_date_local, period_key_for_date};
+
+const PERIOD_CADENCE: &str = "monthly";
+
+#[derive(Serialize)]
+pub struct RecurringIncome {
+    pub id: i64,
+    pub name: String,
+    pub amount: i64,
+    pub is_active: bool,
+    pub day_of_month: Option<u32>,
+    pub received_date_local: Option<String>,
+    pub received_tx_id: Option<i64>,
+}
+
+fn validate_income(name: &str, amount: i64, day_of_month: Option<u32>) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    crate::validate_amount_for_kind("IN", amount)?;
+    if let Some(day) = day_of_month {
+        if !(1..=31).contains(&day) {
+            return Err("day_of_month must be between 1 and 31".to_string());
+        }
+    }
+    Ok(())
+}
+
+fn income_from_row(row: &rusqlite::Row) -> rusqlite::Result<RecurringIncome> {
+    Ok(RecurringIncome {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        amount: row.get(2)?,
+        is_active: row.get::<_, i64>(3)? != 0,
+        day_of_month: row.get(4)?,
+        received_date_local: row.get(5)?,
+        received_tx_id: row.get(6)?,
+    })
+}
+
+pub fn fetch_income_for_date(
+    conn: &Connection,
+    income_id: i64,
+    date: NaiveDate,
+) -> Result<RecurringIncome, String> {
+    conn.query_row(
+        "SELECT ri.id, ri.name, ri.amount, ri.is_active, ri.day_of_month,
+                r.received_date_local, r.tx_id
+         FROM recurring_incomes ri
+         LEFT JOIN recurring_income_receipts r
+           ON r.recurring_income_id = ri.id AND r.period_ym = ?2
+         WHERE ri.id = ?1 AND ri.profile_id = (SELECT active_profile_id FROM app_state)",
+        params![income_id, period_key_for_date(PERIOD_CADENCE, date)],
+        income_from_row,
+    )
+    .optional()
+    .map_err(|err| err.to_string())?
+    .ok_or_else(|| "Pemasukan rutin tidak ditemukan".to_string())
+}
+
+pub fn list_incomes(conn: &Connection, today: NaiveDate) -> Result<Vec<RecurringIncome>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT ri.id, ri.name, ri.amount, ri.is_active, ri.day_of_month,
+                    r.received_date_local, r.tx_id
+             FROM recurring_incomes ri
+             LEFT JOIN recurring_income_receipts r
+               ON r.recurring_income_id = ri.id AND r.period_ym = ?1
+             WHERE ri.profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY ri.id DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(
+            [period_key_for_date(PERIOD_CADENCE, today)],
+            income_from_row,
+        )
+        .map_err(|err| err.to_string())?;
+
+    let mut incomes = Vec::new();
+    for row in rows {
+        incomes.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(incomes)
+}
+
+pub fn expected_remaining_this_month(conn: &Connection, today: NaiveDate) -> Result<i64, String> {
+    conn.query_row(
+        "SELECT COALESCE(SUM(ri.amount), 0)
+         FROM recurring_incomes ri
+         LEFT JOIN recurring_income_receipts r
+           ON r.recurring_income_id = ri.id AND r.period_ym = ?1
+         WHERE ri.profile_id = (SELECT active_profile_id FROM app_state) AND ri.is_active = 1 AND r.id IS NULL",
+        [period_key_for_date(PERIOD_CADENCE, today)],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+pub fn add_income(
+    conn: &Connection,
+    name: &str,
+    amount: i64,
+    day_of_month: Option<u32>,
+    today: NaiveDate,
+) -> Result<RecurringIncome, String> {
+    validate_income(name, amount, day_of_month)?;
+    conn.execute(
+        "INSERT INTO recurring_incomes
+            (name, amount, is_active, day_of_month, created_ts_utc, profile_id)
+         VALUES (?1, ?2, 1, ?3, ?4, (SELECT active_profile_id FROM app_state))",
+        params![
+            name.trim(),
+            amount,
+            day_of_month,
+            Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    fetch_income_for_date(conn, conn.last_insert_rowid(), today)
+}
+
+// Pemasukan yang sudah diterima tetap di transactions: uangnya memang masuk, jadi saldo
+// tidak boleh berubah.
+pub fn delete_income(conn: &mut Connection, income_id: i64) -> Result<(), String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let owned: i64 = tx
+        .query_row(
+            "SELECT COUNT(*) FROM recurring_incomes
+             WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            [income_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if owned == 0 {
+        return Err("Pemasukan rutin tidak ditemukan".to_string());
+    }
+    tx.execute(
+        "DELETE FROM recurring_income_receipts WHERE recurring_income_id = ?1",
+        [income_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.execute("DELETE FROM recurring_incomes WHERE id = ?1", [income_id])
+        .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+// Satu bulan hanya bisa diterima sekali; baris receipt mencegah hitung ganda.
+pub fn mark_received(
+    conn: &mut Connection,
+    income_id: i64,
+    date_local: &str,
+) -> Result<RecurringIncome, String> {
+    let date = parse_date_local(date_local)?;
+    let period_ym = period_key_for_date(PERIOD_CADENCE, date);
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let (name, amount): (String, i64) = tx
+        .query_row(
+            "SELECT name, amount FROM recurring_incomes
+             WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            [income_id],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .ok_or_else(|| "Pemasukan rutin tidak ditemukan".to_string())?;
+
+    // Receipt yang transaksinya sudah dihapus tidak dihitung lagi.
+    tx.execute(
+        "DELETE FROM recurring_income_receipts
+         WHERE recurring_income_id = ?1 AND period_ym = ?2
+           AND (tx_id IS NULL OR tx_id NOT IN (SELECT id FROM transactions))",
+        params![income_id, &period_ym],
+    )
+    .map_err(|err| err.to_string())?;
+    let already_received: i64 = tx
+        .query_row(
+            "SELECT COUNT(*) FROM recurring_income_receipts
+             WHERE recurring_income_id = ?1 AND period_ym = ?2",
+            params![income_id, &period_ym],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    if already_received > 0 {
+        return Err("Pemasukan rutin sudah diterima untuk periode ini".to_string());
+    }
+
+    let ts_utc = Utc::now().timestamp_millis();
+    tx.execute(
+        "INSERT INTO transactions
+            (ts_utc, date_local, kind, amount, source, fixed_cost_id, description, profile_id)
+         VALUES (?1, ?2, 'IN', ?3, 'recurring_income', NULL, ?4, (SELECT active_profile_id FROM app_state))",
+        params![ts_utc, date_local, amount, name],
+    )
+    .map_err(|err| err.to_string())?;
+    let tx_id = tx.last_insert_rowid();
+    tx.execute(
+        "INSERT INTO recurring_income_receipts
+           (recurring_income_id, period_ym, received_date_local, received_ts_utc, tx_id)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![income_id, &period_ym, date_local, ts_utc, tx_id],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+    fetch_income_for_date(conn, income_id, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    #[test]
+    fn received_once_per_month_as_income_transaction() {
+        let mut conn = setup_conn();
+        let today = NaiveDate::from_ymd_opt(2025, 5, 20).expect("date");
+        let salary = add_income(&conn, "Gaji", 8_000_000, Some(25), today).expect("add");
+        assert!(salary.received_date_local.is_none());
+        assert!(add_income(&conn, "Bonus", 100, Some(32), today).is_err());
+        assert!(add_income(&conn, "Bonus", 0, Some(1), today).is_err());
+
+        let salary = mark_received(&mut conn, salary.id, "2025-05-25").expect("received");
+        assert_eq!(salary.received_date_local.as_deref(), Some("2025-05-25"));
+        let (source, amount): (String, i64) = conn
+            .query_row(
+                "SELECT source, amount FROM transactions WHERE kind = 'IN'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("tx");
+        assert_eq!(source, "recurring_income");
+        assert_eq!(amount, 8_000_000);
+
+        assert!(mark_received(&mut conn, salary.id, "2025-05-28").is_err());
+        mark_received(&mut conn, salary.id, "2025-06-25").expect("next month");
+
+        // Transaksi bulan Mei dihapus manual: bulan itu boleh ditandai lagi.
+        conn.execute(
+            "DELETE FROM transactions WHERE date_local = '2025-05-25'",
+            [],
+        )
+        .expect("delete tx");
+        mark_received(&mut conn, salary.id, "2025-05-26").expect("re-receive");
+
+        add_income(&conn, "Sewa kamar", 1_500_000, Some(1), today).expect("add rent");
+        let june = NaiveDate::from_ymd_opt(2025, 6, 20).expect("date");
+        assert_eq!(
+            expected_remaining_this_month(&conn, june).expect("june"),
+            1_500_000
+        );
+        assert_eq!(
+            expected_remaining_this_month(
+                &conn,
+                NaiveDate::from_ymd_opt(2025, 7, 1).expect("date")
+            )
+            .expect("july"),
+            9_500_000
+        );
+
+        delete_income(&mut conn, salary.id).expect("delete");
+        assert_eq!(list_incomes(&conn, today).expect("list").len(), 1);
+        let remaining: i64 = conn
+            .query_row("SELECT COUNT(*) FROM transactions", [], |row| row.get(0))
+            .expect("count");
+        assert_eq!(remaining, 2);
+    }
+}
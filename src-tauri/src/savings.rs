@@ -0,0 +1,246 @@
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+
+#[derive(Serialize)]
+pub struct SavingsGoal {
+    pub id: i64,
+    pub name: String,
+    pub target_amount: i64,
+    pub saved_amount: i64,
+    pub created_ts_utc: i64,
+}
+
+fn validate_goal(name: &str, target_amount: i64, saved_amount: i64) -> Result<(), String> {
+    if name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if target_amount < 0 || saved_amount < 0 {
+        return Err("target_amount and saved_amount must be >= 0".to_string());
+    }
+    Ok(())
+}
+
+pub fn fetch_goal(conn: &Connection, goal_id: i64) -> Result<SavingsGoal, String> {
+    conn.query_row(
+        "SELECT id, name, target_amount, saved_amount, created_ts_utc
+         FROM savings_goals WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+        [goal_id],
+        |row| {
+            Ok(SavingsGoal {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                target_amount: row.get(2)?,
+                saved_amount: row.get(3)?,
+                created_ts_utc: row.get(4)?,
+            })
+        },
+    )
+    .optional()
+    .map_err(|err| err.to_string())?
+    .ok_or_else(|| "Target tabungan tidak ditemukan".to_string())
+}
+
+pub fn list_goals(conn: &Connection) -> Result<Vec<SavingsGoal>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, target_amount, saved_amount, created_ts_utc
+             FROM savings_goals
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+             ORDER BY id DESC",
+        )
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(SavingsGoal {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                target_amount: row.get(2)?,
+                saved_amount: row.get(3)?,
+                created_ts_utc: row.get(4)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut goals = Vec::new();
+    for row in rows {
+        goals.push(row.map_err(|err| err.to_string())?);
+    }
+    Ok(goals)
+}
+
+pub fn add_goal(
+    conn: &Connection,
+    name: &str,
+    target_amount: i64,
+    saved_amount: i64,
+) -> Result<SavingsGoal, String> {
+    validate_goal(name, target_amount, saved_amount)?;
+    conn.execute(
+        "INSERT INTO savings_goals (name, target_amount, saved_amount, created_ts_utc, profile_id)
+         VALUES (?1, ?2, ?3, ?4, (SELECT active_profile_id FROM app_state))",
+        params![
+            name.trim(),
+            target_amount,
+            saved_amount,
+            Utc::now().timestamp_millis()
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    fetch_goal(conn, conn.last_insert_rowid())
+}
+
+pub fn update_goal(
+    conn: &Connection,
+    goal_id: i64,
+    name: &str,
+    target_amount: i64,
+    saved_amount: i64,
+) -> Result<SavingsGoal, String> {
+    validate_goal(name, target_amount, saved_amount)?;
+    let affected = conn
+        .execute(
+            "UPDATE savings_goals SET name = ?1, target_amount = ?2, saved_amount = ?3
+             WHERE id = ?4 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            params![name.trim(), target_amount, saved_amount, goal_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Target tabungan tidak ditemukan".to_string());
+    }
+    fetch_goal(conn, goal_id)
+}
+
+pub fn delete_goal(conn: &Connection, goal_id: i64) -> Result<(), String> {
+    let affected = conn
+        .execute(
+            "DELETE FROM savings_goals WHERE id = ?1 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            [goal_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Target tabungan tidak ditemukan".to_string());
+    }
+    Ok(())
+}
+
+// Transaksi OUT source = 'savings' membuat net_balance ikut berkurang.
+pub fn contribute(
+    conn: &mut Connection,
+    goal_id: i64,
+    amount: i64,
+    date_local: &str,
+) -> Result<SavingsGoal, String> {
+    if amount <= 0 {
+        return Err("amount must be > 0".to_string());
+    }
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let affected = tx
+        .execute(
+            "UPDATE savings_goals SET saved_amount = saved_amount + ?1
+             WHERE id = ?2 AND profile_id = (SELECT active_profile_id FROM app_state)",
+            params![amount, goal_id],
+        )
+        .map_err(|err| err.to_string())?;
+    if affected == 0 {
+        return Err("Target tabungan tidak ditemukan".to_string());
+    }
+    let name: String = tx
+        .query_row(
+            "SELECT name FROM savings_goals WHERE id = ?1",
+            [goal_id],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    tx.execute(
+        "INSERT INTO transactions
+            (ts_utc, date_local, kind, amount, source, fixed_cost_id, description, savings_goal_id,
+             profile_id)
+         VALUES (?1, ?2, 'OUT', ?3, 'savings', NULL, ?4, ?5, (SELECT active_profile_id FROM app_state))",
+        params![
+            Utc::now().timestamp_millis(),
+            date_local,
+            amount,
+            format!("Tabungan: {}", name),
+            goal_id
+        ],
+    )
+    .map_err(|err| err.to_string())?;
+    tx.commit().map_err(|err| err.to_string())?;
+    fetch_goal(conn, goal_id)
+}
+
+// Kontribusi sudah keluar dari net_balance lewat transaksinya, jadi tiap tujuan hanya
+// mencadangkan saved_amount yang belum tertutup transfernya sendiri.
+pub fn reserved_amount(conn: &Connection) -> Result<i64, String> {
+    let goal_reserves: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(MAX(0, g.saved_amount - (
+                SELECT COALESCE(SUM(t.amount), 0) FROM transactions t
+                WHERE t.kind = 'OUT' AND t.source = 'savings' AND t.savings_goal_id = g.id
+             ))), 0)
+             FROM savings_goals g
+             WHERE g.profile_id = (SELECT active_profile_id FROM app_state)",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    // Setoran lama tanpa target tidak bisa dipasangkan; tetap dikurangkan dari total.
+    let unlinked_total: i64 = conn
+        .query_row(
+            "SELECT COALESCE(SUM(amount), 0) FROM transactions
+             WHERE profile_id = (SELECT active_profile_id FROM app_state)
+               AND kind = 'OUT' AND source = 'savings' AND savings_goal_id IS NULL",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+    Ok(std::cmp::max(0, goal_reserves - unlinked_total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_conn() -> Connection {
+        let conn = Connection::open_in_memory().expect("open in-memory");
+        crate::db::init_schema(&conn).expect("init schema");
+        conn
+    }
+
+    #[test]
+    fn contribution_records_savings_transaction_without_double_reserving() {
+        let mut conn = setup_conn();
+        let goal = add_goal(&conn, "Laptop", 8_000_000, 500_000).expect("add");
+        assert_eq!(reserved_amount(&conn).expect("reserved"), 500_000);
+
+        let goal = contribute(&mut conn, goal.id, 200_000, "2025-05-01").expect("contribute");
+        assert_eq!(goal.saved_amount, 700_000);
+        let (source, amount): (String, i64) = conn
+            .query_row(
+                "SELECT source, amount FROM transactions WHERE kind = 'OUT'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .expect("tx");
+        assert_eq!(source, "savings");
+        assert_eq!(amount, 200_000);
+        assert_eq!(reserved_amount(&conn).expect("reserved"), 500_000);
+
+        assert!(contribute(&mut conn, goal.id + 1, 1_000, "2025-05-01").is_err());
+        delete_goal(&conn, goal.id).expect("delete");
+        assert!(list_goals(&conn).expect("list").is_empty());
+    }
+
+    #[test]
+    fn deleting_contributed_goal_keeps_other_reserves() {
+        let mut conn = setup_conn();
+        let bike = add_goal(&conn, "Sepeda", 3_000_000, 0).expect("add bike");
+        add_goal(&conn, "Dana darurat", 5_000_000, 400_000).expect("add fund");
+        contribute(&mut conn, bike.id, 250_000, "2025-05-01").expect("contribute");
+        assert_eq!(reserved_amount(&conn).expect("reserved"), 400_000);
+
+        delete_goal(&conn, bike.id).expect("delete");
+        assert_eq!(reserved_amount(&conn).expect("reserved"), 400_000);
+    }
+}